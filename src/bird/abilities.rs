@@ -0,0 +1,388 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+
+const DASH_SPEED: f32 = 260.0;
+const GROUND_POUND_SPEED: f32 = 320.0;
+const GROUND_POUND_RADIUS: f32 = 50.0;
+const GROUND_POUND_STUN: f32 = 1.5;
+const GROUND_POUND_KNOCKBACK: f32 = 120.0;
+
+/// Extra things the bird can do on top of launching and firing. Each has its own cooldown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum AbilityKind {
+    /// A short burst of speed in the movement direction. Can't be hurt while dashing.
+    Dash,
+    /// Can't be hurt, and bad bullets that hit the bird get sent back
+    Shield,
+    /// Slam straight down, stunning enemies near wherever we land
+    GroundPound,
+}
+impl AbilityKind {
+    pub fn all() -> [Self; 3] {
+        [Self::Dash, Self::Shield, Self::GroundPound]
+    }
+
+    /// Seconds before this can be used again, before skill multipliers
+    pub fn base_cooldown(&self) -> f32 {
+        match self {
+            Self::Dash => 1.5,
+            Self::Shield => 6.0,
+            Self::GroundPound => 3.0,
+        }
+    }
+
+    /// How long the ability lasts. For ground pound this is the max time before giving up.
+    pub fn duration(&self) -> f32 {
+        match self {
+            Self::Dash => 0.18,
+            Self::Shield => 2.0,
+            Self::GroundPound => 1.0,
+        }
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        match self {
+            Self::Dash | Self::Shield => true,
+            Self::GroundPound => false,
+        }
+    }
+
+    /// What its cooldown bar looks like
+    fn color(&self) -> Color {
+        match self {
+            Self::Dash => tailwind::SKY_400.into(),
+            Self::Shield => tailwind::AMBER_400.into(),
+            Self::GroundPound => tailwind::PURPLE_400.into(),
+        }
+    }
+}
+
+/// Lives on the bird. Tracks cooldowns and whatever ability is currently happening.
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct BirdAbilities {
+    cooldowns: HashMap<AbilityKind, Timer>,
+    active: Option<(AbilityKind, Timer)>,
+    dash_dir: Vec2,
+}
+impl BirdAbilities {
+    pub fn new() -> Self {
+        Self {
+            cooldowns: HashMap::new(),
+            active: None,
+            dash_dir: Vec2::X,
+        }
+    }
+
+    pub fn get_active(&self) -> Option<AbilityKind> {
+        self.active.as_ref().map(|(kind, _)| *kind)
+    }
+
+    pub fn is_ready(&self, kind: AbilityKind) -> bool {
+        self.cooldowns
+            .get(&kind)
+            .map(|timer| timer.finished())
+            .unwrap_or(true)
+    }
+
+    /// 0 means ready, 1 means it was just used
+    pub fn cooldown_frac(&self, kind: AbilityKind) -> f32 {
+        self.cooldowns
+            .get(&kind)
+            .map(|timer| timer.fraction_remaining())
+            .unwrap_or(0.0)
+    }
+}
+
+fn start_abilities(
+    mut use_ability: EventReader<UseAbility>,
+    mut bird_q: Query<(
+        Entity,
+        &mut BirdAbilities,
+        &mut DynoTran,
+        &mut Transform,
+        &mut MultiAnimationManager,
+    )>,
+    movement: Res<MovementInput>,
+    skills: Res<EphemeralSkill>,
+    mut commands: Commands,
+) {
+    let Ok((eid, mut abilities, mut dyno_tran, mut tran, mut multi)) = bird_q.get_single_mut()
+    else {
+        return;
+    };
+    for UseAbility(kind) in use_ability.read() {
+        if abilities.active.is_some() || !abilities.is_ready(*kind) {
+            continue;
+        }
+        abilities.cooldowns.insert(
+            *kind,
+            Timer::from_seconds(
+                kind.base_cooldown() * skills.get_ability_cooldown_mult(),
+                TimerMode::Once,
+            ),
+        );
+        abilities.active = Some((*kind, Timer::from_seconds(kind.duration(), TimerMode::Once)));
+        match kind {
+            AbilityKind::Dash => {
+                // Prefer the direction being held, then the direction we're already going
+                let mut dir = movement.get_dir().normalize_or_zero();
+                if dir == Vec2::ZERO {
+                    dir = dyno_tran.vel.normalize_or_zero();
+                }
+                if dir == Vec2::ZERO {
+                    dir = Vec2::X;
+                }
+                abilities.dash_dir = dir;
+                dyno_tran.vel = dir * DASH_SPEED;
+                tran.set_angle(0.0);
+                commands.entity(eid).remove::<Stuck>();
+                commands.spawn(SoundEffect::universal(
+                    "sound_effects/lenny_launch.ogg",
+                    0.2,
+                ));
+            }
+            AbilityKind::Shield => {
+                multi.manager_mut("shield").set_hidden(false, &mut commands);
+                commands.spawn(SoundEffect::universal("sound_effects/lenny_heal.ogg", 0.3));
+            }
+            AbilityKind::GroundPound => {
+                dyno_tran.vel = -Vec2::Y * GROUND_POUND_SPEED;
+                tran.set_angle(0.0);
+                commands.entity(eid).remove::<Stuck>();
+                commands.spawn(SoundEffect::universal(
+                    "sound_effects/lenny_launch.ogg",
+                    0.2,
+                ));
+            }
+        }
+    }
+}
+
+fn update_abilities(
+    mut bird_q: Query<
+        (
+            &mut Health,
+            &mut BirdAbilities,
+            &mut DynoTran,
+            &StaticReceiver,
+            &GlobalTransform,
+            &mut MultiAnimationManager,
+        ),
+        With<Bird>,
+    >,
+    mut guides: Query<
+        (Entity, &mut DynoTran, &GlobalTransform),
+        (
+            Or<(With<SimpGuide>, With<SpewGuide>, With<SpitGuide>)>,
+            Without<Bird>,
+            Without<Birthing>,
+        ),
+    >,
+    mut commands: Commands,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let Ok((mut health, mut abilities, mut dyno_tran, static_rx, gtran, mut multi)) =
+        bird_q.get_single_mut()
+    else {
+        return;
+    };
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for timer in abilities.cooldowns.values_mut() {
+        timer.tick(Duration::from_secs_f32(time_factor));
+    }
+    let dash_dir = abilities.dash_dir;
+    let mut finished = false;
+    if let Some((kind, timer)) = abilities.active.as_mut() {
+        timer.tick(Duration::from_secs_f32(time_factor));
+        finished = timer.finished();
+        let hit_something = !static_rx.collisions.is_empty();
+        match kind {
+            AbilityKind::Dash => {
+                if hit_something {
+                    finished = true;
+                } else if finished {
+                    // Don't keep all that speed after the dash is over
+                    dyno_tran.vel = dash_dir * DASH_SPEED * 0.3;
+                } else {
+                    dyno_tran.vel = dash_dir * DASH_SPEED;
+                }
+            }
+            AbilityKind::Shield => {
+                if finished {
+                    multi.manager_mut("shield").set_hidden(true, &mut commands);
+                }
+            }
+            AbilityKind::GroundPound => {
+                if hit_something {
+                    finished = true;
+                    let my_pos = gtran.translation().truncate();
+                    for (guide_eid, mut guide_dyno_tran, guide_gtran) in &mut guides {
+                        let diff = guide_gtran.translation().truncate() - my_pos;
+                        if diff.length() > GROUND_POUND_RADIUS {
+                            continue;
+                        }
+                        guide_dyno_tran.vel = diff.normalize_or_zero() * GROUND_POUND_KNOCKBACK;
+                        commands
+                            .entity(guide_eid)
+                            .insert(Stunned::new(GROUND_POUND_STUN));
+                    }
+                    commands.spawn(SoundEffect::universal("sound_effects/rock_normal.ogg", 0.8));
+                } else if !finished {
+                    dyno_tran.vel = -Vec2::Y * GROUND_POUND_SPEED;
+                }
+            }
+        }
+    }
+    if finished {
+        abilities.active = None;
+    }
+    health.set_invulnerable(
+        abilities
+            .get_active()
+            .map(|kind| kind.is_invulnerable())
+            .unwrap_or(false),
+    );
+}
+
+/// While shielded, bad bullets that touch the bird turn good and go back where they came from
+fn reflect_bad_bullets(
+    bird_q: Query<(&BirdAbilities, &TriggerReceiver), With<Bird>>,
+    mut bullets: Query<(&mut DynoTran, &mut TriggerReceiver), (With<AnyBullet>, Without<Bird>)>,
+    collisions: Query<&TriggerCollisionRecord>,
+) {
+    let Ok((abilities, rx)) = bird_q.get_single() else {
+        return;
+    };
+    if abilities.get_active() != Some(AbilityKind::Shield) {
+        return;
+    }
+    for cid in rx.collisions.iter() {
+        let Ok(collision) = collisions.get(*cid) else {
+            continue;
+        };
+        if collision.other_kind != TriggerKind::BulletBad {
+            continue;
+        }
+        let Ok((mut dyno_tran, mut bullet_rx)) = bullets.get_mut(collision.other_eid) else {
+            continue;
+        };
+        dyno_tran.vel = -dyno_tran.vel;
+        bullet_rx.kind = TriggerKind::BulletGood;
+    }
+}
+
+/// A little bar under the health bar for each ability. Fills back up as the cooldown wears off.
+#[derive(Component)]
+struct CooldownBar(AbilityKind);
+impl CooldownBar {
+    const DIMS: Vec2 = Vec2::new(18.0, 2.0);
+    const GAP: f32 = 3.0;
+    /// Lines the row up under the health bar
+    const CENTER: Vec2 = Vec2::new(-119.0, -83.0);
+}
+
+fn spawn_cooldown_bars(
+    mut commands: Commands,
+    meta_state: Res<State<MetaState>>,
+    tutorial_root: Res<TutorialRoot>,
+    room_root: Res<RoomRoot>,
+) {
+    let proper_parent = if meta_state.get_tutorial_state().is_some() {
+        tutorial_root.eid()
+    } else {
+        room_root.eid()
+    };
+    for (ix, kind) in AbilityKind::all().into_iter().enumerate() {
+        let x =
+            CooldownBar::CENTER.x + (ix as f32 - 1.0) * (CooldownBar::DIMS.x + CooldownBar::GAP);
+        let y = CooldownBar::CENTER.y;
+        commands
+            .spawn((
+                Name::new(format!("cooldown_bar_{kind:?}")),
+                CooldownBar(kind),
+                multi!([
+                    (
+                        "bg",
+                        anim_man!({
+                            path: "sprites/default.png",
+                            size: (1, 1),
+                            color: Color::BLACK,
+                        })
+                        .with_points(simple_rect(
+                            CooldownBar::DIMS.x + 2.0,
+                            CooldownBar::DIMS.y + 2.0
+                        ))
+                        .with_offset(-Vec3::Z),
+                    ),
+                    (
+                        "fg",
+                        anim_man!({
+                            path: "sprites/default.png",
+                            size: (1, 1),
+                            color: kind.color(),
+                        })
+                        .with_points(simple_rect(CooldownBar::DIMS.x, CooldownBar::DIMS.y)),
+                    ),
+                ]),
+                spat_tran(x, y, ZIX_MAX - 0.1),
+                ScreenSpace::new(x, y),
+            ))
+            .set_parent(proper_parent);
+    }
+}
+
+fn destroy_cooldown_bars(eids: Query<Entity, With<CooldownBar>>, mut commands: Commands) {
+    for eid in &eids {
+        commands.entity(eid).despawn_recursive();
+    }
+}
+
+fn update_cooldown_bars(
+    bird_q: Query<&BirdAbilities>,
+    mut bars: Query<(&CooldownBar, &mut MultiAnimationManager)>,
+    mut commands: Commands,
+) {
+    let Ok(abilities) = bird_q.get_single() else {
+        return;
+    };
+    for (bar, mut multi) in &mut bars {
+        let width = CooldownBar::DIMS.x * (1.0 - abilities.cooldown_frac(bar.0));
+        // Grows from the left edge
+        let new_points = simple_rect(width, CooldownBar::DIMS.y)
+            .into_iter()
+            .map(|p| p + Vec2::new((width - CooldownBar::DIMS.x) / 2.0, 0.0))
+            .collect::<Vec<_>>();
+        multi
+            .manager_mut("fg")
+            .set_points(new_points, &mut commands);
+    }
+}
+
+fn hide_shield(mut bird_q: Query<&mut MultiAnimationManager, With<Bird>>, mut commands: Commands) {
+    for mut multi in &mut bird_q {
+        multi.manager_mut("shield").set_hidden(true, &mut commands);
+    }
+}
+
+pub(super) fn register_abilities(app: &mut App) {
+    app.register_type::<BirdAbilities>();
+
+    app.add_systems(
+        Update,
+        (start_abilities, update_abilities, reflect_bad_bullets)
+            .chain()
+            .run_if(in_state(PhysicsState::Active))
+            .run_if(in_state(BirdAlive::Yes))
+            .after(PhysicsSet)
+            .before(DamageSet),
+    );
+    app.add_systems(OnExit(BirdAlive::Yes), hide_shield);
+    app.add_systems(OnEnter(BirdAlive::Yes), spawn_cooldown_bars);
+    app.add_systems(OnExit(BirdAlive::Yes), destroy_cooldown_bars);
+    app.add_systems(
+        Update,
+        update_cooldown_bars.run_if(in_state(BirdAlive::Yes)),
+    );
+}
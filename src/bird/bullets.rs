@@ -0,0 +1,408 @@
+use std::f32::consts::PI;
+
+use crate::prelude::*;
+
+/// The different kinds of bullets the bird can fire. Each kind has its own ammo count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum BulletKind {
+    /// The OG. Goes through everything.
+    Basic,
+    /// Bounces off of platforms a few times before popping
+    Bouncy,
+    /// Fires a spread of weak pellets
+    Shotgun,
+    /// Slowly turns towards the nearest enemy
+    Homing,
+    /// Blows up on impact, hurting everything nearby
+    Explosive,
+}
+
+/// The numbers that actually make each kind of bullet different
+#[derive(Debug, Clone)]
+pub struct BulletKindStats {
+    /// Multiplied by the drag vector to get the initial velocity
+    pub speed_mult: f32,
+    /// How much health is taken from an enemy on hit
+    pub damage: u32,
+    /// How many enemies can be hit before exploding. None means unlimited.
+    pub pierce: Option<u32>,
+    /// How many static collisions can be survived before exploding
+    pub bounces: u32,
+    /// How many bullets come out of a single fire
+    pub pellets: u32,
+    /// The total angle (radians) that pellets are spread across
+    pub spread: f32,
+    /// How quickly (radians/sec) the bullet turns towards enemies
+    pub homing: f32,
+    /// When some, the bullet damages everything within this radius when it explodes
+    pub explosion_radius: Option<f32>,
+    pub color: Color,
+}
+
+impl BulletKind {
+    pub fn all() -> [Self; 5] {
+        [
+            Self::Basic,
+            Self::Bouncy,
+            Self::Shotgun,
+            Self::Homing,
+            Self::Explosive,
+        ]
+    }
+
+    pub fn stats(&self) -> BulletKindStats {
+        match self {
+            Self::Basic => BulletKindStats {
+                speed_mult: 10.0,
+                damage: 1,
+                pierce: None,
+                bounces: 0,
+                pellets: 1,
+                spread: 0.0,
+                homing: 0.0,
+                explosion_radius: None,
+                color: Color::WHITE,
+            },
+            Self::Bouncy => BulletKindStats {
+                speed_mult: 9.0,
+                damage: 1,
+                pierce: Some(1),
+                bounces: 3,
+                pellets: 1,
+                spread: 0.0,
+                homing: 0.0,
+                explosion_radius: None,
+                color: Color::srgb_u8(110, 181, 196),
+            },
+            Self::Shotgun => BulletKindStats {
+                speed_mult: 8.0,
+                damage: 1,
+                pierce: Some(1),
+                bounces: 0,
+                pellets: 5,
+                spread: PI / 5.0,
+                homing: 0.0,
+                explosion_radius: None,
+                color: Color::srgb_u8(245, 180, 90),
+            },
+            Self::Homing => BulletKindStats {
+                speed_mult: 7.0,
+                damage: 1,
+                pierce: Some(1),
+                bounces: 0,
+                pellets: 1,
+                spread: 0.0,
+                homing: 6.0,
+                explosion_radius: None,
+                color: Color::srgb_u8(140, 220, 120),
+            },
+            Self::Explosive => BulletKindStats {
+                speed_mult: 8.0,
+                damage: 2,
+                pierce: Some(1),
+                bounces: 0,
+                pellets: 1,
+                spread: 0.0,
+                homing: 0.0,
+                explosion_radius: Some(20.0),
+                color: Color::srgb_u8(230, 90, 80),
+            },
+        }
+    }
+
+    /// How many of this kind the bird can hold, given how many basic bullets it gets
+    pub fn max_ammo(&self, num_bullets: u32) -> u32 {
+        match self {
+            Self::Basic | Self::Bouncy => num_bullets,
+            Self::Shotgun | Self::Homing => num_bullets.div_ceil(2),
+            Self::Explosive => num_bullets.div_ceil(3),
+        }
+    }
+
+    /// The next kind when cycling. Negative dirs go backwards.
+    pub fn cycle(&self, dir: i32) -> Self {
+        let all = Self::all();
+        let ix = all.iter().position(|kind| kind == self).unwrap_or(0) as i32;
+        all[(ix + dir).rem_euclid(all.len() as i32) as usize]
+    }
+}
+
+/// Lives on every bullet fired by the bird
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Bullet {
+    pub kind: BulletKind,
+    pub pierce_left: Option<u32>,
+    pub bounces_left: u32,
+    pub homing: f32,
+    pub explosion_radius: Option<f32>,
+    /// Enemies we've already counted towards pierce
+    hit: HashSet<Entity>,
+    detonated: bool,
+}
+impl Bullet {
+    pub fn new(kind: BulletKind) -> Self {
+        let stats = kind.stats();
+        Self {
+            kind,
+            pierce_left: stats.pierce,
+            bounces_left: stats.bounces,
+            homing: stats.homing,
+            explosion_radius: stats.explosion_radius,
+            hit: default(),
+            detonated: false,
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct BulletBundle {
+    name: Name,
+    any_bullet: AnyBullet,
+    bullet: Bullet,
+    damage: Damage,
+    physics: BulletPhysicsBundle,
+    multi: MultiAnimationManager,
+    particles: DynoAwareParticleSpawner,
+}
+impl BulletBundle {
+    pub fn new(kind: BulletKind, pos: Vec2, vel: Vec2) -> Self {
+        let stats = kind.stats();
+        let receiver_kind = if stats.bounces > 0 {
+            StaticReceiverKind::Bounce
+        } else {
+            StaticReceiverKind::Stop
+        };
+        Self {
+            name: Name::new(format!("bullet_{kind:?}")),
+            any_bullet: AnyBullet,
+            bullet: Bullet::new(kind),
+            damage: Damage::new(stats.damage)
+                .with_knockback(60.0)
+                .once_per_touch(),
+            physics: BulletPhysicsBundle::new(pos, vel, true).with_receiver_kind(receiver_kind),
+            multi: multi!([
+                (
+                    "core",
+                    anim_man!({
+                        solid: {
+                            path: "bullets/good.png",
+                            size: (5, 5),
+                            color: stats.color,
+                        },
+                        explode: {
+                            path: "bullets/good_explode.png",
+                            size: (7, 7),
+                            length: 2,
+                            next: "despawn",
+                            color: stats.color,
+                        },
+                    }),
+                ),
+                (
+                    "light",
+                    anim_man!({
+                        path: "bullets/good_light.png",
+                        size: (12, 12),
+                    })
+                    .with_render_layers(LightCamera::render_layers()),
+                )
+            ]),
+            particles: DynoAwareParticleSpawner::new(
+                Particle::new(default())
+                    .with_colors(stats.color, Color::srgba_u8(0, 0, 0, 0))
+                    .with_sizes(3.0, 0.2),
+            ),
+        }
+    }
+    pub fn with_bonus_damage(mut self, amt: u32) -> Self {
+        self.damage.amount += amt;
+        self
+    }
+}
+
+/// A short-lived trigger that looks like a good bullet to anything it overlaps
+#[derive(Bundle)]
+struct BulletExplosionBundle {
+    name: Name,
+    any_bullet: AnyBullet,
+    bullet: Bullet,
+    damage: Damage,
+    dyno_tran: DynoTran,
+    bounds: Bounds,
+    trigger_rx: TriggerReceiver,
+    spatial: SpatialBundle,
+    multi: MultiAnimationManager,
+    dying: Dying,
+}
+impl BulletExplosionBundle {
+    fn new(source: &Bullet, damage: &Damage, pos: Vec2, radius: f32) -> Self {
+        let mut bullet = source.clone();
+        bullet.pierce_left = None;
+        bullet.homing = 0.0;
+        bullet.explosion_radius = None;
+        bullet.detonated = true;
+        Self {
+            name: Name::new("bullet_explosion"),
+            any_bullet: AnyBullet,
+            bullet,
+            damage: damage.clone(),
+            dyno_tran: DynoTran { vel: Vec2::ZERO },
+            bounds: Bounds::from_shape(Shape::Circle { radius }),
+            trigger_rx: TriggerReceiver::from_kind(TriggerKind::BulletGood),
+            spatial: spat_tran(pos.x, pos.y, ZIX_BULLET),
+            multi: multi!(anim_man!({
+                path: "bullets/good_explode.png",
+                size: (7, 7),
+                length: 2,
+                fps: 8.0,
+                color: source.kind.stats().color,
+            })
+            .with_scale(Vec2::ONE * radius * 2.0 / 7.0)),
+            dying: Dying {
+                timer: Timer::from_seconds(0.25, TimerMode::Once),
+                dont_despawn: false,
+            },
+        }
+    }
+}
+
+/// Stops a bullet where it is and starts the explode animation
+pub(super) fn pop_bullet(
+    eid: Entity,
+    multi: &mut MultiAnimationManager,
+    dyno_tran: &mut DynoTran,
+    commands: &mut Commands,
+) {
+    if multi.manager("core").get_key().as_str() != "solid" {
+        return;
+    }
+    multi
+        .manager_mut("core")
+        .reset_key_with_points("explode", commands);
+    dyno_tran.vel = Vec2::ZERO;
+    commands.entity(eid).remove::<Gravity>();
+    commands.entity(eid).remove::<TriggerReceiver>();
+}
+
+/// Counts enemy hits against pierce, popping bullets that have run out
+fn pierce_bullets(
+    mut bullets: Query<(
+        Entity,
+        &mut Bullet,
+        &TriggerReceiver,
+        &mut DynoTran,
+        &mut MultiAnimationManager,
+    )>,
+    collisions: Query<&TriggerCollisionRecord>,
+    irrelevant_enemies: Query<Entity, Or<(With<Birthing>, With<Dying>, With<Dead>)>>,
+    mut commands: Commands,
+) {
+    for (eid, mut bullet, rx, mut dyno_tran, mut multi) in &mut bullets {
+        let Some(mut pierce_left) = bullet.pierce_left else {
+            continue;
+        };
+        for cid in rx.collisions.iter() {
+            let Ok(collision) = collisions.get(*cid) else {
+                continue;
+            };
+            if collision.other_kind != TriggerKind::SimpBody
+                || irrelevant_enemies.contains(collision.other_eid)
+                || bullet.hit.contains(&collision.other_eid)
+            {
+                continue;
+            }
+            bullet.hit.insert(collision.other_eid);
+            pierce_left = pierce_left.saturating_sub(1);
+        }
+        bullet.pierce_left = Some(pierce_left);
+        if pierce_left == 0 {
+            pop_bullet(eid, &mut multi, &mut dyno_tran, &mut commands);
+        }
+    }
+}
+
+/// Turns homing bullets towards the closest living enemy
+fn home_bullets(
+    mut bullets: Query<(&Bullet, &mut DynoTran, &GlobalTransform), With<TriggerReceiver>>,
+    enemies: Query<
+        (&TriggerReceiver, &GlobalTransform),
+        (Without<Bullet>, Without<Birthing>, Without<Dying>),
+    >,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (bullet, mut dyno_tran, gtran) in &mut bullets {
+        if bullet.homing <= 0.0 {
+            continue;
+        }
+        let my_pos = gtran.translation().truncate();
+        let closest = enemies
+            .iter()
+            .filter(|(rx, _)| rx.kind == TriggerKind::SimpBody)
+            .map(|(_, enemy_gtran)| enemy_gtran.translation().truncate())
+            .min_by(|a, b| {
+                a.distance_squared(my_pos)
+                    .total_cmp(&b.distance_squared(my_pos))
+            });
+        let Some(target) = closest else {
+            continue;
+        };
+        let speed = dyno_tran.vel.length();
+        let current_angle = dyno_tran.vel.to_angle();
+        let short_rot = shortest_rotation(current_angle, (target - my_pos).to_angle());
+        let max_rot = bullet.homing * time_factor;
+        let rot = short_rot.signum() * short_rot.abs().min(max_rot);
+        dyno_tran.vel = Vec2::from_angle(current_angle + rot) * speed;
+    }
+}
+
+/// Spawns the area damage for explosive bullets once they pop
+fn detonate_bullets(
+    mut bullets: Query<(
+        &mut Bullet,
+        &Damage,
+        &MultiAnimationManager,
+        &GlobalTransform,
+    )>,
+    mut commands: Commands,
+    meta_state: Res<State<MetaState>>,
+    room_root: Res<RoomRoot>,
+    tutorial_root: Res<TutorialRoot>,
+) {
+    let parent_eid = if meta_state.get_tutorial_state().is_some() {
+        tutorial_root.eid()
+    } else {
+        room_root.eid()
+    };
+    for (mut bullet, damage, multi, gtran) in &mut bullets {
+        if bullet.detonated || multi.manager("core").get_key().as_str() != "explode" {
+            continue;
+        }
+        bullet.detonated = true;
+        let Some(radius) = bullet.explosion_radius else {
+            continue;
+        };
+        commands
+            .spawn(BulletExplosionBundle::new(
+                &bullet,
+                damage,
+                gtran.translation().truncate(),
+                radius,
+            ))
+            .set_parent(parent_eid);
+        commands.spawn(SoundEffect::universal("sound_effects/spew_death1.ogg", 0.1));
+    }
+}
+
+pub(super) fn register_bullets(app: &mut App) {
+    app.register_type::<Bullet>();
+
+    app.add_systems(
+        Update,
+        (pierce_bullets, home_bullets, detonate_bullets)
+            .chain()
+            .run_if(in_state(PhysicsState::Active))
+            .after(PhysicsSet),
+    );
+}
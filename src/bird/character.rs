@@ -0,0 +1,154 @@
+use crate::prelude::*;
+
+/// Which bird is being played. Lives as a resource so anything that spawns a bird knows who to spawn.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Default)]
+pub enum BirdCharacter {
+    /// Our hero. Good at everything, great at nothing.
+    #[default]
+    Lenny,
+    /// Slow and heavy, but can take a beating
+    Brick,
+    /// Fast and fragile
+    Swift,
+    /// Can't fly for shit but boy can she shoot
+    Pepper,
+}
+
+/// Things each bird does that nobody else does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BirdPassive {
+    /// Every kill refunds a bullet of whatever kind is selected
+    Scavenger,
+    /// Stays invulnerable for longer after getting hit
+    ThickSkin,
+    /// Bouncing off hard platforms also refills launches
+    Featherweight,
+    /// Every bullet does an extra point of damage
+    Hotshot,
+}
+impl BirdPassive {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Scavenger => "Scavenger: kills refund a bullet",
+            Self::ThickSkin => "Thick Skin: longer recovery after getting hit",
+            Self::Featherweight => "Featherweight: hard platforms refill launches",
+            Self::Hotshot => "Hotshot: bullets do +1 damage",
+        }
+    }
+}
+
+/// Everything that makes a bird that bird
+pub struct BirdCharacterDef {
+    pub name: &'static str,
+    pub blurb: &'static str,
+    pub fly_path: &'static str,
+    pub fly_damage_path: &'static str,
+    /// Only have art for Lenny rn so everyone else is Lenny in a different coat of paint
+    pub color: Color,
+    /// The least we'll let the permanent skills drop to when starting an encounter
+    pub base_skill: PermanentSkill,
+    pub passive: BirdPassive,
+}
+
+impl BirdCharacter {
+    pub fn all() -> [Self; 4] {
+        [Self::Lenny, Self::Brick, Self::Swift, Self::Pepper]
+    }
+
+    pub fn def(&self) -> BirdCharacterDef {
+        match self {
+            Self::Lenny => BirdCharacterDef {
+                name: "Lenny",
+                blurb: "A little bit of everything",
+                fly_path: "lenny/fly.png",
+                fly_damage_path: "lenny/fly_damage.png",
+                color: Color::WHITE,
+                base_skill: PermanentSkill::new(2, 3, 3),
+                passive: BirdPassive::Scavenger,
+            },
+            Self::Brick => BirdCharacterDef {
+                name: "Brick",
+                blurb: "Heavy, slow, hard to kill",
+                fly_path: "lenny/fly.png",
+                fly_damage_path: "lenny/fly_damage.png",
+                color: Color::srgb_u8(170, 140, 120),
+                base_skill: PermanentSkill::new(2, 2, 5),
+                passive: BirdPassive::ThickSkin,
+            },
+            Self::Swift => BirdCharacterDef {
+                name: "Swift",
+                blurb: "Fast, floaty, fragile",
+                fly_path: "lenny/fly.png",
+                fly_damage_path: "lenny/fly_damage.png",
+                color: Color::srgb_u8(140, 210, 235),
+                base_skill: PermanentSkill::new(3, 2, 2),
+                passive: BirdPassive::Featherweight,
+            },
+            Self::Pepper => BirdCharacterDef {
+                name: "Pepper",
+                blurb: "Clumsy flier, deadly shot",
+                fly_path: "lenny/fly.png",
+                fly_damage_path: "lenny/fly_damage.png",
+                color: Color::srgb_u8(235, 130, 110),
+                base_skill: PermanentSkill::new(1, 6, 3),
+                passive: BirdPassive::Hotshot,
+            },
+        }
+    }
+
+    pub fn passive(&self) -> BirdPassive {
+        self.def().passive
+    }
+
+    /// Negative dirs go backwards
+    pub fn cycle(&self, dir: i32) -> Self {
+        let all = Self::all();
+        let ix = all.iter().position(|c| c == self).unwrap_or(0) as i32;
+        all[(ix + dir).rem_euclid(all.len() as i32) as usize]
+    }
+
+    /// Makes this the bird that gets played from now on. Skills start over, feathers don't.
+    pub fn choose(
+        &self,
+        current: &mut BirdCharacter,
+        flight_consts: &mut BirdFlightConsts,
+        permanent_skills: &mut PermanentSkill,
+    ) {
+        *current = *self;
+        *flight_consts = BirdFlightConsts::for_character(*self);
+        let feathers = permanent_skills.get_feathers();
+        *permanent_skills = self.def().base_skill;
+        permanent_skills.add_feathers(feathers);
+    }
+}
+
+/// Scavenger passive. Watches for the number of kills left to go down and hands out bullets.
+fn scavenge_bullets(
+    mut bird_q: Query<&mut Bird, Changed<Bird>>,
+    skills: Res<EphemeralSkill>,
+    mut last_kills_left: Local<Option<u32>>,
+) {
+    let Ok(mut bird) = bird_q.get_single_mut() else {
+        return;
+    };
+    let kills_left = bird.get_kills_left();
+    if let Some(last) = *last_kills_left {
+        if kills_left < last && bird.get_character().passive() == BirdPassive::Scavenger {
+            bird.refund_bullets(last - kills_left, skills.get_num_bullets());
+        }
+    }
+    *last_kills_left = Some(kills_left);
+}
+
+pub(super) fn register_character(app: &mut App) {
+    app.insert_resource(BirdCharacter::default());
+    app.register_type::<BirdCharacter>();
+
+    app.add_systems(
+        Update,
+        scavenge_bullets
+            .run_if(in_state(PhysicsState::Active))
+            .run_if(in_state(BirdAlive::Yes))
+            .after(PhysicsSet),
+    );
+}
@@ -1,63 +1,83 @@
-use std::time::Duration;
-
 use crate::prelude::*;
 
+use super::bullets::pop_bullet;
+
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct BirdTakeDamageSet;
 
-fn take_simp_damage(
-    mut birds: Query<(&mut Bird, &TriggerReceiver)>,
-    collisions: Query<&TriggerCollisionRecord>,
-    irrelevant_simps: Query<Entity, Or<(With<Birthing>, With<Dying>, With<Dead>)>>,
+/// `EphemeralSkill` is still the source of truth for the bird's health (hearts, upgrades, etc. all touch it)
+/// so make sure the bird's `Health` agrees with it before anything tries to hurt it
+fn sync_bird_health(mut birds: Query<&mut Health, With<Bird>>, skills: Res<EphemeralSkill>) {
+    for mut health in &mut birds {
+        health.set_max(skills.get_max_health());
+        health.set_current(skills.get_current_health());
+    }
+}
+
+fn take_damage(
+    mut damage_taken: EventReader<DamageTaken>,
+    birds: Query<Entity, With<Bird>>,
     mut commands: Commands,
     mut skills: ResMut<EphemeralSkill>,
+    mut camera_director: ResMut<CameraDirector>,
 ) {
-    for (mut bird, rx) in &mut birds {
-        if bird.taking_damage.is_some() {
-            return;
+    for event in damage_taken.read() {
+        if birds.get(event.target).is_err() {
+            continue;
         }
+        skills.dec_current_health(event.amount);
+        camera_director.punch_zoom(0.08, 0.35);
+        commands.spawn(SoundEffect::universal(
+            "sound_effects/lenny_take_damage.ogg",
+            0.8,
+        ));
+    }
+}
+
+/// Enemy bullets pop when they hit the bird, whether or not they actually did any damage.
+/// Ones that got reflected by the shield are good now and fly right past.
+fn absorb_bad_bullets(
+    birds: Query<&TriggerReceiver, With<Bird>>,
+    mut bad_bullets: Query<
+        (
+            Entity,
+            &TriggerReceiver,
+            &mut DynoTran,
+            &mut MultiAnimationManager,
+        ),
+        (With<BadBullet>, Without<Bird>),
+    >,
+    collisions: Query<&TriggerCollisionRecord>,
+    mut commands: Commands,
+) {
+    for rx in &birds {
         for cid in rx.collisions.iter() {
-            let collision = collisions.get(*cid).unwrap();
-            if collision.other_kind != TriggerKind::SimpBody {
+            let Ok(collision) = collisions.get(*cid) else {
+                continue;
+            };
+            if collision.other_kind != TriggerKind::BulletBad {
                 continue;
             }
-            if irrelevant_simps.get(collision.other_eid).is_ok() {
-                // The simp is either dying or not spawned
+            let Ok((eid, bullet_rx, mut dyno_tran, mut multi)) =
+                bad_bullets.get_mut(collision.other_eid)
+            else {
+                continue;
+            };
+            if bullet_rx.kind != TriggerKind::BulletBad {
                 continue;
             }
-            bird.taking_damage = Some(Timer::from_seconds(1.0, TimerMode::Once));
-            skills.dec_current_health(1);
-            commands.spawn(SoundEffect::universal(
-                "sound_effects/lenny_take_damage.ogg",
-                0.8,
-            ));
-            // Fuck it only take damage from simps at most once per frame
-            // Wait this actually makes sense
-            break;
+            pop_bullet(eid, &mut multi, &mut dyno_tran, &mut commands);
         }
     }
 }
 
 fn update_animation(
-    mut birds: Query<(&mut Bird, &mut MultiAnimationManager)>,
-    time: Res<Time>,
-    bullet_time: Res<BulletTime>,
+    mut birds: Query<(&Health, &mut MultiAnimationManager), With<Bird>>,
     mut commands: Commands,
 ) {
-    let time_factor = time.delta_seconds() * bullet_time.factor();
-    for (mut bird, mut multi) in &mut birds {
-        let is_taking_damage = match bird.taking_damage.as_mut() {
-            Some(timer) => {
-                timer.tick(Duration::from_secs_f32(time_factor));
-                !timer.finished()
-            }
-            None => false,
-        };
-        if !is_taking_damage {
-            bird.taking_damage = None;
-        }
+    for (health, mut multi) in &mut birds {
         multi.manager_mut("core").set_key(
-            if is_taking_damage {
+            if health.is_immune() {
                 "taking_damage"
             } else {
                 "normal"
@@ -70,10 +90,18 @@ fn update_animation(
 pub(super) fn register_damage(app: &mut App) {
     app.add_systems(
         Update,
-        (take_simp_damage, update_animation)
+        sync_bird_health
+            .run_if(in_state(PhysicsState::Active))
+            .run_if(in_state(BirdAlive::Yes))
+            .after(PhysicsSet)
+            .before(DamageSet),
+    );
+    app.add_systems(
+        Update,
+        (take_damage, absorb_bad_bullets, update_animation)
             .run_if(in_state(PhysicsState::Active))
             .run_if(in_state(BirdAlive::Yes))
             .in_set(BirdTakeDamageSet)
-            .after(PhysicsSet),
+            .after(DamageSet),
     );
 }
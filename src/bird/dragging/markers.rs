@@ -114,7 +114,7 @@ fn update_drag_markers(
 
     let (mut fire_multi, mut fire_tran) = fire_multi.single_mut();
     let fire_hidden = {
-        if bird.bullets_left > 0 {
+        if bird.get_bullets_left() > 0 {
             if let Some(start) = mouse_input.get_right_drag_start() {
                 let length = (start - mouse_input.get_world_pos()).length();
                 manage_multi!(fire_multi, fire_tran, length, start, &mut commands);
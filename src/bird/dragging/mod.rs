@@ -2,66 +2,23 @@ use crate::prelude::*;
 
 mod markers;
 
-#[derive(Bundle)]
-struct BulletBundle {
-    name: Name,
-    any_bullet: AnyBullet,
-    physics: BulletPhysicsBundle,
-    multi: MultiAnimationManager,
-    particles: DynoAwareParticleSpawner,
-}
-impl BulletBundle {
-    pub fn new(pos: Vec2, vel: Vec2) -> Self {
-        Self {
-            name: Name::new("bullet"),
-            any_bullet: AnyBullet,
-            physics: BulletPhysicsBundle::new(pos, vel, true),
-            multi: multi!([
-                (
-                    "core",
-                    anim_man!({
-                        solid: {
-                            path: "bullets/good.png",
-                            size: (5, 5),
-                        },
-                        explode: {
-                            path: "bullets/good_explode.png",
-                            size: (7, 7),
-                            length: 2,
-                            next: "despawn",
-                        },
-                    }),
-                ),
-                (
-                    "light",
-                    anim_man!({
-                        path: "bullets/good_light.png",
-                        size: (12, 12),
-                    })
-                    .with_render_layers(LightCamera::render_layers()),
-                )
-            ]),
-            particles: DynoAwareParticleSpawner::new(
-                Particle::new(default())
-                    .with_colors(Color::srgb_u8(255, 255, 255), Color::srgba_u8(0, 0, 0, 0))
-                    .with_sizes(3.0, 0.2),
-            ),
-        }
-    }
-}
-
 fn update_bullet_time(
     mut bullet_time: ResMut<BulletTime>,
     bird_q: Query<&Bird>,
     mouse_state: Res<MouseInput>,
+    hit_stop: Res<HitStop>,
 ) {
     let Ok(bird) = bird_q.get_single() else {
         // No bird = no bullet time
         *bullet_time = BulletTime::Inactive;
         return;
     };
+    if hit_stop.is_active() {
+        *bullet_time = BulletTime::Custom(0.0);
+        return;
+    }
     let is_launching = bird.launches_left > 0 && mouse_state.get_left_drag_start().is_some();
-    let is_firing = bird.bullets_left > 0 && mouse_state.get_right_drag_start().is_some();
+    let is_firing = bird.get_bullets_left() > 0 && mouse_state.get_right_drag_start().is_some();
     *bullet_time = if is_launching || is_firing {
         BulletTime::Active
     } else {
@@ -73,6 +30,7 @@ fn do_launch(
     mut launch: EventReader<Launch>,
     mut bird_q: Query<(Entity, &mut Bird, &mut DynoTran, &mut Transform)>,
     mut commands: Commands,
+    flight_consts: Res<BirdFlightConsts>,
 ) {
     let Some(launch) = launch.read().last() else {
         return;
@@ -85,7 +43,7 @@ fn do_launch(
     }
     bird.launches_left -= 1;
     commands.entity(eid).remove::<Stuck>();
-    dyno_tran.vel = launch.0 * 6.0;
+    dyno_tran.vel = launch.0 * flight_consts.get_launch_mult();
     tran.set_angle(0.0);
     commands.spawn(SoundEffect::universal(
         "sound_effects/lenny_launch.ogg",
@@ -107,23 +65,54 @@ fn do_fire(
     let Ok((mut bird, gtran)) = bird_q.get_single_mut() else {
         return;
     };
-    if bird.bullets_left == 0 {
+    if !bird.use_bullet() {
         return;
     }
-    bird.bullets_left -= 1;
     let parent_eid = if meta_state.get_tutorial_state().is_some() {
         tutorial_root.eid()
     } else {
         room_root.eid()
     };
     let pos = gtran.translation().truncate();
-    let vel = fire.0 * 10.0;
-    commands
-        .spawn(BulletBundle::new(pos, vel))
-        .set_parent(parent_eid);
+    let kind = bird.get_bullet_kind();
+    let stats = kind.stats();
+    let vel = fire.0 * stats.speed_mult;
+    for ix in 0..stats.pellets {
+        // Spread the pellets evenly across the spread angle, centered on the drag
+        let frac = if stats.pellets > 1 {
+            ix as f32 / (stats.pellets - 1) as f32 - 0.5
+        } else {
+            0.0
+        };
+        let mut bullet = BulletBundle::new(kind, pos, vel.my_rotate(frac * stats.spread));
+        if bird.get_character().passive() == BirdPassive::Hotshot {
+            bullet = bullet.with_bonus_damage(1);
+        }
+        commands.spawn(bullet).set_parent(parent_eid);
+    }
     commands.spawn(SoundEffect::universal("sound_effects/lenny_fire.ogg", 0.06));
 }
 
+fn cycle_bullet_kind(
+    mut cycle: EventReader<CycleBulletKind>,
+    mut bird_q: Query<&mut Bird>,
+    mut commands: Commands,
+) {
+    let dir = cycle.read().map(|event| event.0).sum::<i32>();
+    if dir == 0 {
+        return;
+    }
+    let Ok(mut bird) = bird_q.get_single_mut() else {
+        return;
+    };
+    let kind = bird.get_bullet_kind().cycle(dir);
+    bird.set_bullet_kind(kind);
+    commands.spawn(SoundEffect::universal(
+        "sound_effects/lenny_recharge.ogg",
+        0.04,
+    ));
+}
+
 fn refresh_launches_n_bullets(
     mut bird_q: Query<(&mut Bird, &StaticReceiver)>,
     static_collisions: Query<&StaticCollisionRecord>,
@@ -131,24 +120,36 @@ fn refresh_launches_n_bullets(
     mut commands: Commands,
 ) {
     for (mut bird, receiver) in bird_q.iter_mut() {
-        if receiver
-            .collisions
-            .clone()
-            .into_iter()
-            .any(|collision_eid| match static_collisions.get(collision_eid) {
-                Ok(record) => record.provider_kind == StaticProviderKind::Sticky,
-                Err(_) => false,
-            })
+        let hit_kind = |kind: StaticProviderKind| {
+            receiver
+                .collisions
+                .clone()
+                .into_iter()
+                .any(|collision_eid| match static_collisions.get(collision_eid) {
+                    Ok(record) => record.provider_kind == kind,
+                    Err(_) => false,
+                })
+        };
+        if bird.get_character().passive() == BirdPassive::Featherweight
+            && bird.launches_left < skills.get_num_launches()
+            && hit_kind(StaticProviderKind::Normal)
         {
-            if bird.launches_left < skills.get_num_launches()
-                || bird.bullets_left < skills.get_num_bullets()
-            {
+            // Featherweights get their launches back from hard platforms too, but not bullets
+            bird.launches_left = skills.get_num_launches();
+            commands.spawn(SoundEffect::universal(
+                "sound_effects/lenny_recharge.ogg",
+                0.08,
+            ));
+        }
+        if hit_kind(StaticProviderKind::Sticky) {
+            let refilled_launches = bird.launches_left < skills.get_num_launches();
+            bird.launches_left = skills.get_num_launches();
+            let refilled_bullets = bird.refill_bullets(skills.get_num_bullets());
+            if refilled_launches || refilled_bullets {
                 commands.spawn(SoundEffect::universal(
                     "sound_effects/lenny_recharge.ogg",
                     0.08,
                 ));
-                bird.launches_left = skills.get_num_launches();
-                bird.bullets_left = skills.get_num_bullets();
             }
         }
     }
@@ -162,9 +163,10 @@ impl Plugin for DraggingPlugin {
         app.add_systems(
             Update,
             (
-                update_bullet_time,
+                update_bullet_time.after(DamageSet),
                 do_launch,
                 do_fire,
+                cycle_bullet_kind,
                 refresh_launches_n_bullets,
             )
                 .run_if(in_state(PhysicsState::Active))
@@ -11,6 +11,7 @@ pub struct BirdFlightConsts {
     max_hor_speed: f32,
     max_up_speed: f32,
     max_down_speed: f32,
+    launch_mult: f32,
 }
 impl Default for BirdFlightConsts {
     fn default() -> Self {
@@ -31,10 +32,49 @@ impl Default for BirdFlightConsts {
             max_down_speed: 240.0,
             max_hor_speed: 80.0,
             max_up_speed: 80.0,
+            // Multiplied by the drag vector to get launch velocity
+            launch_mult: 6.0,
         }
     }
 }
 impl BirdFlightConsts {
+    /// Each bird gets its own flight profile. Lenny is the baseline.
+    pub fn for_character(character: BirdCharacter) -> Self {
+        let lenny = Self::default();
+        match character {
+            BirdCharacter::Lenny => lenny,
+            BirdCharacter::Brick => Self {
+                hor_mul: 90.0,
+                down_mul: 160.0,
+                up_mul: 600.0,
+                max_hor_speed: 60.0,
+                max_up_speed: 60.0,
+                max_down_speed: 300.0,
+                launch_mult: 5.0,
+                ..lenny
+            },
+            BirdCharacter::Swift => Self {
+                drag: 0.995,
+                hor_mul: 170.0,
+                up_mul: 900.0,
+                max_hor_speed: 120.0,
+                max_up_speed: 100.0,
+                launch_mult: 7.5,
+                ..lenny
+            },
+            BirdCharacter::Pepper => Self {
+                hor_mul: 110.0,
+                max_hor_speed: 70.0,
+                launch_mult: 5.5,
+                ..lenny
+            },
+        }
+    }
+
+    pub fn get_launch_mult(&self) -> f32 {
+        self.launch_mult
+    }
+
     fn apply(&self, dir: Vec2) -> Vec2 {
         let x = dir.x * self.hor_mul;
         let y = if dir.y > 0.0 {
@@ -58,6 +58,7 @@ fn spawn_health_bar(
                 )
             ]),
             spat_tran(-119.0, -77.0, ZIX_MAX - 0.1),
+            ScreenSpace::new(-119.0, -77.0),
         ))
         .set_parent(proper_parent);
 }
@@ -95,25 +96,38 @@ fn update_health_bar(
 
 fn start_dying(
     mut bird: Query<
-        (Entity, &mut Bird, &mut MultiAnimationManager),
+        (
+            Entity,
+            &mut Bird,
+            &mut MultiAnimationManager,
+            &GlobalTransform,
+        ),
         (Without<Dying>, Without<Dead>),
     >,
     mut commands: Commands,
     mut music_manager: ResMut<MusicManager>,
     skills: Res<EphemeralSkill>,
+    mut camera_director: ResMut<CameraDirector>,
 ) {
-    let Ok((eid, mut bird, mut _multi)) = bird.get_single_mut() else {
+    let Ok((eid, mut bird, mut _multi, gtran)) = bird.get_single_mut() else {
         return;
     };
     if skills.get_current_health() == 0 {
         bird.launches_left = 0;
-        bird.bullets_left = 0;
+        bird.empty_bullets();
         commands.entity(eid).insert(Dying {
             timer: Timer::from_seconds(3.0, TimerMode::Once),
             dont_despawn: true,
         });
         commands.spawn(SoundEffect::universal("sound_effects/lenny_death.ogg", 0.4));
         music_manager.fade_to_song(MusicKind::Elegy);
+        // Slowly close in on the poor thing. Gets let go when the state changes.
+        camera_director.pan_to(
+            gtran.translation().truncate(),
+            1.4,
+            2.5,
+            Spleen::EaseInOutCubic,
+        );
     }
 }
 
@@ -165,6 +179,7 @@ fn drop_dead(
     meta_state: Res<State<MetaState>>,
     tutorial_root: Res<TutorialRoot>,
     room_root: Res<RoomRoot>,
+    room_bounds: Res<RoomBounds>,
     children: Query<&Children>,
 ) {
     let Ok((eid, mut dyno_tran, mut tran, mut multi)) = dead_bird.get_single_mut() else {
@@ -195,7 +210,7 @@ fn drop_dead(
             commands.despawn_recursive();
         }
     }
-    let mut around_room = StickyPlatformBundle::around_room();
+    let mut around_room = StickyPlatformBundle::around_room_sized(room_bounds.size);
     for anim in around_room.multi.map.values_mut() {
         for node in anim.map.values_mut() {
             node.sprite.color = Color::BLACK;
@@ -232,6 +247,9 @@ fn ghost_up(
         tran.translation = Vec3::ZERO;
         if meta_state.get_tutorial_state().is_some() {
             next_meta_state.set(TutorialState::Dead.to_meta_state());
+        } else if meta_state.get_editor_state().is_some() {
+            // Playtesting, straight back to the editor
+            next_meta_state.set(EditorState::Editing.to_meta_state());
         } else {
             next_meta_state.set(RoomState::Dead.to_meta_state());
         }
@@ -2,6 +2,9 @@
 use bevy_inspector_egui::quick::ResourceInspectorPlugin;
 
 use crate::prelude::*;
+pub mod abilities;
+pub mod bullets;
+pub mod character;
 pub mod damage;
 pub mod dragging;
 pub mod egg;
@@ -12,8 +15,12 @@ mod progress;
 mod resource_markers;
 pub mod skill_tree;
 
+pub use abilities::*;
+pub use bullets::*;
+pub use character::*;
 pub use damage::*;
 pub use egg::*;
+pub use flight::*;
 pub use ghost::*;
 pub use skill_tree::*;
 
@@ -25,39 +32,100 @@ pub struct FakeBird;
 /// There should only ever be one of these
 #[derive(Component, Debug, Clone, Reflect)]
 pub struct Bird {
+    character: BirdCharacter,
     launches_left: u32,
-    bullets_left: u32,
-    pub taking_damage: Option<Timer>,
+    bullet_kind: BulletKind,
+    /// Each kind of bullet has its own ammo
+    ammo: HashMap<BulletKind, u32>,
     // How many more kills till this room is complete?
     kills_left: u32,
     total_kills_this_room: u32,
 }
 impl Bird {
+    pub fn get_character(&self) -> BirdCharacter {
+        self.character
+    }
+
     pub fn get_launches_left(&self) -> u32 {
         self.launches_left
     }
 
+    /// How many bullets of the currently selected kind are left
     pub fn get_bullets_left(&self) -> u32 {
-        self.bullets_left
+        self.ammo.get(&self.bullet_kind).copied().unwrap_or(0)
+    }
+
+    pub fn get_bullet_kind(&self) -> BulletKind {
+        self.bullet_kind
+    }
+
+    pub fn set_bullet_kind(&mut self, kind: BulletKind) {
+        self.bullet_kind = kind;
+    }
+
+    /// Uses up one bullet of the current kind. Returns false if there was nothing to use.
+    pub fn use_bullet(&mut self) -> bool {
+        match self.ammo.get_mut(&self.bullet_kind) {
+            Some(left) if *left > 0 => {
+                *left -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Fills up every kind of bullet. Returns true if anything actually changed.
+    pub fn refill_bullets(&mut self, num_bullets: u32) -> bool {
+        let mut changed = false;
+        for kind in BulletKind::all() {
+            let max = kind.max_ammo(num_bullets);
+            if self.ammo.insert(kind, max) != Some(max) {
+                changed = true;
+            }
+        }
+        changed
     }
 
-    pub fn get_taking_damage(&self) -> Option<Timer> {
-        self.taking_damage.clone()
+    /// Gives back bullets of the current kind, without going over the max
+    pub fn refund_bullets(&mut self, amt: u32, num_bullets: u32) {
+        let max = self.bullet_kind.max_ammo(num_bullets);
+        let left = self.ammo.entry(self.bullet_kind).or_insert(0);
+        *left = max.min(*left + amt);
+    }
+
+    pub fn empty_bullets(&mut self) {
+        self.ammo.clear();
     }
 
     pub fn get_kills_left(&self) -> u32 {
         self.kills_left
     }
 
+    /// How much of this room's enemies are dead, 0 to 1
+    pub fn frac_killed(&self) -> f32 {
+        if self.total_kills_this_room == 0 {
+            return 1.0;
+        }
+        (self.total_kills_this_room - self.kills_left) as f32 / self.total_kills_this_room as f32
+    }
+
     pub fn dec_kills_left(&mut self, amt: u32) {
         self.kills_left = self.kills_left.saturating_sub(amt);
     }
+
+    /// For when more enemies show up than the room planned for (splitting elites, etc.)
+    pub fn add_kills(&mut self, amt: u32) {
+        self.kills_left += amt;
+        self.total_kills_this_room += amt;
+    }
 }
 
 #[derive(Bundle)]
 pub struct BirdBundle {
     name: Name,
     bird: Bird,
+    abilities: BirdAbilities,
+    health: Health,
     face_dyno: FaceDyno,
     physics: BirdPhysicsBundle,
     multi: MultiAnimationManager,
@@ -65,21 +133,39 @@ pub struct BirdBundle {
 }
 impl BirdBundle {
     pub fn new(
+        character: BirdCharacter,
         pos: Vec2,
         vel: Vec2,
         launches_left: u32,
         bullets_left: u32,
         kills_left: u32,
     ) -> Self {
+        let def = character.def();
+        let mut bird = Bird {
+            character,
+            launches_left,
+            bullet_kind: BulletKind::Basic,
+            ammo: HashMap::new(),
+            kills_left,
+            total_kills_this_room: kills_left,
+        };
+        bird.refill_bullets(bullets_left);
         Self {
             name: Name::new("bird"),
-            bird: Bird {
-                launches_left,
-                bullets_left,
-                taking_damage: None,
-                kills_left,
-                total_kills_this_room: kills_left,
-            },
+            bird,
+            abilities: BirdAbilities::new(),
+            // Actual numbers get synced from `EphemeralSkill` every frame
+            health: Health::new(1)
+                .with_i_frames(if def.passive == BirdPassive::ThickSkin {
+                    2.0
+                } else {
+                    1.0
+                })
+                .with_vulnerable_to([
+                    TriggerKind::SimpBody,
+                    TriggerKind::BulletBad,
+                    TriggerKind::Hazard,
+                ]),
             face_dyno: FaceDyno,
             physics: BirdPhysicsBundle::new(pos, vel),
             multi: multi!([
@@ -87,23 +173,26 @@ impl BirdBundle {
                     "core",
                     anim_man!({
                         normal: {
-                            path: "lenny/fly.png",
+                            path: def.fly_path,
                             size: (24, 24),
                             length: 3,
                             fps: 16.0,
+                            color: def.color,
                         },
                         taking_damage: {
-                            path: "lenny/fly_damage.png",
+                            path: def.fly_damage_path,
                             size: (24, 24),
                             length: 3,
                             fps: 12.0,
+                            color: def.color,
                         }
                         dead: {
-                            path: "lenny/fly_damage.png",
+                            path: def.fly_damage_path,
                             size: (24, 24),
                             length: 3,
                             // I'm so lazy
                             fps: 0.0,
+                            color: def.color,
                         }
                     })
                     .with_offset(Vec3::new(-1.0, 0.0, 0.0))
@@ -118,6 +207,17 @@ impl BirdBundle {
                     .with_render_layers(LightCamera::render_layers())
                     .with_scale(Vec2::new(2.5, 2.5))
                 ),
+                (
+                    "shield",
+                    anim_man!({
+                        path: "bullets/good_light.png",
+                        size: (12, 12),
+                        color: Color::srgba_u8(110, 181, 196, 160),
+                    })
+                    .with_scale(Vec2::new(2.0, 2.0))
+                    .with_offset(Vec3::new(0.0, 0.0, 0.1))
+                    .with_hidden(true)
+                ),
             ]),
             particles: DynoAwareParticleSpawner::new(
                 Particle::new(default())
@@ -174,7 +274,7 @@ fn update_bird_alive_and_exists(
 pub(super) struct BirdPlugin;
 impl Plugin for BirdPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(flight::BirdFlightConsts::default());
+        app.insert_resource(flight::BirdFlightConsts::for_character(default()));
         app.add_plugins(
             ResourceInspectorPlugin::<flight::BirdFlightConsts>::new()
                 .run_if(input_toggle_active(false, KeyCode::Tab)),
@@ -193,6 +293,9 @@ impl Plugin for BirdPlugin {
                 .after(PhysicsSet),
         );
 
+        abilities::register_abilities(app);
+        bullets::register_bullets(app);
+        character::register_character(app);
         damage::register_damage(app);
         health::register_health_bar(app);
         progress::register_progress_bar(app);
@@ -48,6 +48,7 @@ fn spawn_progress_bar(mut commands: Commands, room_root: Res<RoomRoot>) {
                 )
             ]),
             spat_tran(119.0, -77.0, ZIX_MAX - 0.1),
+            ScreenSpace::new(119.0, -77.0),
         ))
         .set_parent(room_root.eid());
 }
@@ -60,6 +61,7 @@ fn destroy_progress_bar(eids: Query<Entity, With<ProgressBar>>, mut commands: Co
 
 fn update_progress_bar(
     bird: Query<&Bird>,
+    objective: Res<RoomObjective>,
     mut multi: Query<&mut MultiAnimationManager, With<ProgressBar>>,
     mut commands: Commands,
     skills: Res<EphemeralSkill>,
@@ -70,8 +72,7 @@ fn update_progress_bar(
     let Ok(mut multi) = multi.get_single_mut() else {
         return;
     };
-    let frac_complete =
-        (bird.total_kills_this_room - bird.kills_left) as f32 / (bird.total_kills_this_room as f32);
+    let frac_complete = objective.frac_complete(bird);
     // It looks too sad if it's totally empty, always show at least 5% complete
     let frac_complete = frac_complete.max(0.05);
     let new_points = simple_rect(ProgressBar::DIMS.x * frac_complete, ProgressBar::DIMS.y)
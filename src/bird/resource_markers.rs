@@ -6,7 +6,7 @@ enum ResourceMarkerKind {
     Fire,
 }
 impl ResourceMarkerKind {
-    fn to_multi(&self) -> MultiAnimationManager {
+    fn to_multi(&self, color: Color) -> MultiAnimationManager {
         let icon_size = 14;
         let empty_size = 7;
         let scale_down = 0.5;
@@ -52,6 +52,7 @@ impl ResourceMarkerKind {
                             on: {
                                 path: "drag_markers/bullet_resource_full.png",
                                 size: (icon_size, icon_size),
+                                color: color,
                             },
                             off: {
                                 path: "drag_markers/resource_empty.png",
@@ -84,6 +85,8 @@ impl ResourceMarkerKind {
 #[derive(Component, Default)]
 struct SketchyChildMap {
     map: HashMap<u32, Entity>,
+    /// Fire markers need to be respawned when the bird switches bullets
+    bullet_kind: Option<BulletKind>,
 }
 
 #[derive(Bundle)]
@@ -162,25 +165,37 @@ fn update_resource_markers(
     }
 
     for (eid, kind, mut smap) in &mut parents {
-        let (total, left) = if kind == &ResourceMarkerKind::Launch {
+        let (total, left, bullet_kind) = if kind == &ResourceMarkerKind::Launch {
             (
                 permanent_skills.get_num_launches(),
                 bird.get_launches_left(),
+                None,
             )
         } else {
-            (permanent_skills.get_num_bullets(), bird.get_bullets_left())
+            let bullet_kind = bird.get_bullet_kind();
+            (
+                bullet_kind.max_ammo(permanent_skills.get_num_bullets()),
+                bird.get_bullets_left(),
+                Some(bullet_kind),
+            )
         };
-        if total != smap.map.len() as u32 {
+        if total != smap.map.len() as u32 || bullet_kind != smap.bullet_kind {
             // We do not have the right number of things showing
             // Clear all the children, spawn them in and reset the map, then return
             // The animations will be fixed next frame
             smap.map.clear();
+            smap.bullet_kind = bullet_kind;
+            let color = bullet_kind.map_or(Color::WHITE, |kind| kind.stats().color);
             commands.entity(eid).despawn_descendants();
             commands.entity(eid).with_children(|me| {
                 // Rust no
                 for ix in 0..total {
                     let id = me
-                        .spawn(ResourceMarkerChildBundle::new(ix, total, kind.to_multi()))
+                        .spawn(ResourceMarkerChildBundle::new(
+                            ix,
+                            total,
+                            kind.to_multi(color),
+                        ))
                         .id();
                     smap.map.insert(ix, id);
                 }
@@ -12,6 +12,10 @@ pub struct PermanentSkill {
     num_launches: u32,
     num_bullets: u32,
     max_health: u32,
+    /// Multiplies the cooldown of every ability. Lower is better.
+    ability_cooldown_mult: f32,
+    /// Currency, dropped by enemies
+    feathers: u32,
 }
 impl Default for PermanentSkill {
     fn default() -> Self {
@@ -19,10 +23,22 @@ impl Default for PermanentSkill {
             num_launches: 5,
             num_bullets: 6,
             max_health: 3,
+            ability_cooldown_mult: 1.0,
+            feathers: 0,
         }
     }
 }
 impl PermanentSkill {
+    pub fn new(num_launches: u32, num_bullets: u32, max_health: u32) -> Self {
+        Self {
+            num_launches,
+            num_bullets,
+            max_health,
+            ability_cooldown_mult: 1.0,
+            feathers: 0,
+        }
+    }
+
     pub fn get_num_launches(&self) -> u32 {
         self.num_launches
     }
@@ -35,6 +51,10 @@ impl PermanentSkill {
         self.max_health
     }
 
+    pub fn get_ability_cooldown_mult(&self) -> f32 {
+        self.ability_cooldown_mult
+    }
+
     pub fn increase_num_launches(&mut self, amt: u32) {
         self.num_launches += amt;
     }
@@ -47,6 +67,11 @@ impl PermanentSkill {
         self.max_health += amt;
     }
 
+    /// Each amt knocks 10% off of the current cooldowns
+    pub fn decrease_ability_cooldown(&mut self, amt: u32) {
+        self.ability_cooldown_mult *= 0.9_f32.powi(amt as i32);
+    }
+
     pub fn force_set_num_launches(&mut self, val: u32) {
         self.num_launches = val;
     }
@@ -58,6 +83,23 @@ impl PermanentSkill {
     pub fn force_set_max_health(&mut self, val: u32) {
         self.max_health = val;
     }
+
+    pub fn get_feathers(&self) -> u32 {
+        self.feathers
+    }
+
+    pub fn add_feathers(&mut self, amt: u32) {
+        self.feathers += amt;
+    }
+
+    /// Returns false (and spends nothing) if there aren't enough
+    pub fn spend_feathers(&mut self, amt: u32) -> bool {
+        if self.feathers < amt {
+            return false;
+        }
+        self.feathers -= amt;
+        true
+    }
 }
 
 /// The skills that get reset at the beginning of each attempt
@@ -69,6 +111,7 @@ pub struct EphemeralSkill {
     /// Oh well, this is fine I think.
     current_health: u32,
     max_health: u32,
+    ability_cooldown_mult: f32,
 }
 impl Default for EphemeralSkill {
     fn default() -> Self {
@@ -77,6 +120,7 @@ impl Default for EphemeralSkill {
             num_bullets: 3,
             max_health: 3,
             current_health: 3,
+            ability_cooldown_mult: 1.0,
         }
     }
 }
@@ -87,6 +131,7 @@ impl EphemeralSkill {
         self.num_bullets = permanent.get_num_bullets();
         self.current_health = permanent.get_max_health();
         self.max_health = permanent.get_max_health();
+        self.ability_cooldown_mult = permanent.get_ability_cooldown_mult();
     }
 
     pub fn get_num_launches(&self) -> u32 {
@@ -101,6 +146,10 @@ impl EphemeralSkill {
         self.max_health
     }
 
+    pub fn get_ability_cooldown_mult(&self) -> f32 {
+        self.ability_cooldown_mult
+    }
+
     pub fn increase_num_launches(&mut self, amt: u32) {
         self.num_launches += amt;
     }
@@ -114,6 +163,10 @@ impl EphemeralSkill {
         self.max_health += amt;
     }
 
+    pub fn decrease_ability_cooldown(&mut self, amt: u32) {
+        self.ability_cooldown_mult *= 0.9_f32.powi(amt as i32);
+    }
+
     pub fn get_current_health(&self) -> u32 {
         self.current_health
     }
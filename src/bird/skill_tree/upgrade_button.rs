@@ -1,4 +1,3 @@
-use rand::thread_rng;
 use rand::Rng;
 
 use crate::prelude::*;
@@ -8,6 +7,7 @@ pub enum UpgradeKind {
     NumLaunches(u32),
     NumBullets(u32),
     MaxHealth(u32),
+    AbilityCooldown(u32),
 }
 impl UpgradeKind {
     fn rarity_text(amt: u32) -> String {
@@ -19,8 +19,7 @@ impl UpgradeKind {
         }
     }
 
-    pub fn new(prob_rare: f32, prob_epic: f32) -> Self {
-        let mut rng = thread_rng();
+    pub fn new(prob_rare: f32, prob_epic: f32, rng: &mut impl Rng) -> Self {
         let smpl = rng.gen::<f32>();
         let amt = {
             if smpl < prob_epic {
@@ -32,12 +31,14 @@ impl UpgradeKind {
             }
         };
         let smpl = rng.gen::<f32>();
-        if smpl < 0.33 {
+        if smpl < 0.27 {
             Self::NumLaunches(amt)
-        } else if smpl < 0.67 {
+        } else if smpl < 0.54 {
             Self::NumBullets(amt)
-        } else {
+        } else if smpl < 0.81 {
             Self::MaxHealth(amt)
+        } else {
+            Self::AbilityCooldown(amt)
         }
     }
 
@@ -54,6 +55,11 @@ impl UpgradeKind {
             Self::MaxHealth(amt) => {
                 format!("TOUGHER\nMax Health +{amt}\n({})", Self::rarity_text(*amt))
             }
+            Self::AbilityCooldown(amt) => format!(
+                "QUICKER\nAbility Cooldown -{}%\n({})",
+                amt * 10,
+                Self::rarity_text(*amt)
+            ),
         }
     }
 
@@ -62,10 +68,39 @@ impl UpgradeKind {
             Self::NumLaunches(amt) => permanent_skill.increase_num_launches(*amt),
             Self::NumBullets(amt) => permanent_skill.increase_num_bullets(*amt),
             Self::MaxHealth(amt) => permanent_skill.increase_max_health(*amt),
+            Self::AbilityCooldown(amt) => permanent_skill.decrease_ability_cooldown(*amt),
+        }
+    }
+
+    /// For upgrades bought mid-run, so they count right away and not just next attempt
+    pub fn apply_ephemeral(&self, ephemeral_skill: &mut EphemeralSkill) {
+        match self {
+            Self::NumLaunches(amt) => ephemeral_skill.increase_num_launches(*amt),
+            Self::NumBullets(amt) => ephemeral_skill.increase_num_bullets(*amt),
+            Self::MaxHealth(amt) => ephemeral_skill.increase_max_health(*amt),
+            Self::AbilityCooldown(amt) => ephemeral_skill.decrease_ability_cooldown(*amt),
+        }
+    }
+
+    fn amt(&self) -> u32 {
+        match self {
+            Self::NumLaunches(amt)
+            | Self::NumBullets(amt)
+            | Self::MaxHealth(amt)
+            | Self::AbilityCooldown(amt) => *amt,
         }
     }
 }
 
+/// On upgrade buttons in the shop. Costs this many feathers, and doesn't stop you buying the others.
+#[derive(Component, Reflect, Clone, Copy)]
+pub struct ShopPrice(pub u32);
+impl ShopPrice {
+    pub fn for_kind(kind: &UpgradeKind) -> Self {
+        Self(4 * kind.amt())
+    }
+}
+
 #[derive(Component, Reflect)]
 pub struct UpgradeButton {
     pub ix: u32,
@@ -89,73 +124,123 @@ impl UpgradeButtonBundle {
     const SIZE: Vec2 = Vec2::new(100.0, 50.0);
 
     pub fn spawn(ix: u32, pos: Vec2, kind: UpgradeKind, commands: &mut Commands, parent: Entity) {
-        commands
-            .spawn(UpgradeButtonBundle {
-                name: Name::new("upgrade_button"),
-                button: UpgradeButton { ix, kind },
-                spatial: spat_tran(pos.x, pos.y, ZIX_UPGRADE_BUTTON),
-                multi: multi!([
-                    (
-                        "outline",
-                        anim_man!({
-                            path: "lenny/skill_tree_button_outline.png",
-                            size: (Self::SIZE.x as u32, Self::SIZE.y as u32),
-                        })
-                        .with_offset(Vec3::Z * 0.2)
-                        .with_render_layers(MenuCamera::render_layers())
-                    ),
-                    (
-                        "fill",
-                        anim_man!({
-                            path: "lenny/skill_tree_button_fill.png",
-                            size: (Self::SIZE.x as u32, Self::SIZE.y as u32),
-                            color: Color::BLACK,
-                        })
-                        .with_render_layers(MenuCamera::render_layers())
-                    ),
-                ]),
-            })
-            .set_parent(parent)
-            .with_children(|dad| {
-                dad.spawn((
-                    Text2dBundle {
-                        text: Text::from_section(
-                            kind.to_button_text(),
-                            TextStyle {
-                                font_size: 10.0,
-                                color: Color::WHITE,
-                                ..default()
-                            },
-                        )
-                        .with_justify(JustifyText::Center),
-                        // text_2d_bounds: Text2dBounds {
-                        //     size: Vec2::new(Self::SIZE.x, Self::SIZE.y),
-                        // },
-                        transform: Transform::from_translation(Vec3::Z),
-                        ..default()
-                    },
-                    MenuCamera::render_layers(),
-                ));
-            });
+        Self::spawn_inner(ix, pos, kind, None, commands, parent);
+    }
+
+    pub fn spawn_for_sale(
+        ix: u32,
+        pos: Vec2,
+        kind: UpgradeKind,
+        price: ShopPrice,
+        commands: &mut Commands,
+        parent: Entity,
+    ) {
+        Self::spawn_inner(ix, pos, kind, Some(price), commands, parent);
+    }
+
+    fn spawn_inner(
+        ix: u32,
+        pos: Vec2,
+        kind: UpgradeKind,
+        price: Option<ShopPrice>,
+        commands: &mut Commands,
+        parent: Entity,
+    ) {
+        let text = match price {
+            Some(ShopPrice(price)) => format!("{}\n{price} feathers", kind.to_button_text()),
+            None => kind.to_button_text(),
+        };
+        let mut button_commands = commands.spawn(UpgradeButtonBundle {
+            name: Name::new("upgrade_button"),
+            button: UpgradeButton { ix, kind },
+            spatial: spat_tran(pos.x, pos.y, ZIX_UPGRADE_BUTTON),
+            multi: multi!([
+                (
+                    "outline",
+                    anim_man!({
+                        path: "lenny/skill_tree_button_outline.png",
+                        size: (Self::SIZE.x as u32, Self::SIZE.y as u32),
+                    })
+                    .with_offset(Vec3::Z * 0.2)
+                    .with_render_layers(MenuCamera::render_layers())
+                ),
+                (
+                    "fill",
+                    anim_man!({
+                        path: "lenny/skill_tree_button_fill.png",
+                        size: (Self::SIZE.x as u32, Self::SIZE.y as u32),
+                        color: Color::BLACK,
+                    })
+                    .with_render_layers(MenuCamera::render_layers())
+                ),
+            ]),
+        });
+        if let Some(price) = price {
+            button_commands.insert(price);
+        }
+        button_commands.set_parent(parent).with_children(|dad| {
+            dad.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        text,
+                        TextStyle {
+                            font_size: 10.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    // text_2d_bounds: Text2dBounds {
+                    //     size: Vec2::new(Self::SIZE.x, Self::SIZE.y),
+                    // },
+                    transform: Transform::from_translation(Vec3::Z),
+                    ..default()
+                },
+                MenuCamera::render_layers(),
+            ));
+        });
     }
 }
 
 pub(super) fn update_upgrade_buttons(
     mouse_input: Res<MouseInput>,
-    buttons: Query<(Entity, &GlobalTransform, &UpgradeButton)>,
+    buttons: Query<(
+        Entity,
+        &GlobalTransform,
+        &UpgradeButton,
+        Option<&ShopPrice>,
+        Option<&UpgradeButtonApplied>,
+    )>,
     mut commands: Commands,
     mut permanent_skills: ResMut<PermanentSkill>,
-    already_applied: Query<&UpgradeButtonApplied>,
+    mut ephemeral_skills: ResMut<EphemeralSkill>,
+    already_applied: Query<&UpgradeButtonApplied, Without<ShopPrice>>,
 ) {
     let world_pos = mouse_input.get_world_pos();
-    for (eid, gt, data) in &buttons {
+    for (eid, gt, data, price, applied) in &buttons {
         let gt = gt.translation();
         let hovered_x = (world_pos.x - gt.x).abs() < UpgradeButtonBundle::SIZE.x / 2.0;
         let hovered_y = (world_pos.y - gt.y).abs() < UpgradeButtonBundle::SIZE.y / 2.0;
         let hovered = hovered_x && hovered_y;
         if hovered {
             commands.entity(eid).insert(Hovered);
-            if already_applied.is_empty() && mouse_input.buttons.just_released(MouseButton::Left) {
+            if let Some(ShopPrice(price)) = price {
+                // Shop buttons can each be bought once, if you can afford it
+                if applied.is_none()
+                    && mouse_input.buttons.just_released(MouseButton::Left)
+                    && permanent_skills.spend_feathers(*price)
+                {
+                    commands.entity(eid).insert(UpgradeButtonApplied);
+                    data.kind.apply(&mut permanent_skills);
+                    data.kind.apply_ephemeral(&mut ephemeral_skills);
+                    commands.spawn(SoundEffect::universal(
+                        "sound_effects/choose_upgrade.ogg",
+                        0.2,
+                    ));
+                }
+            } else if already_applied.is_empty()
+                && mouse_input.buttons.just_released(MouseButton::Left)
+            {
                 commands.entity(eid).insert(UpgradeButtonApplied);
                 data.kind.apply(&mut permanent_skills);
                 commands.spawn(SoundEffect::universal(
@@ -1,6 +1,359 @@
 use crate::prelude::*;
 
+/// How far (in ideal pixels) the camera can get knocked at full trauma
+const MAX_SHAKE_OFFSET: f32 = 4.0;
+/// How much trauma goes away per (real) second
+const SHAKE_DECAY: f32 = 1.8;
+/// How quickly the camera catches up to where it wants to be. Bigger is snappier.
+const FOLLOW_RATE: f32 = 6.0;
+/// How far ahead of the bird the camera looks, at most
+const LOOK_AHEAD: f32 = 40.0;
+/// Drags (or speeds) this long (or fast) get the full look-ahead
+const LOOK_AHEAD_DRAG: f32 = 60.0;
+const LOOK_AHEAD_SPEED: f32 = 200.0;
+
+/// A quick zoom in that eases back out on its own
+#[derive(Debug, Clone, Reflect)]
+struct ZoomPunch {
+    amount: f32,
+    timer: Timer,
+    spleen: Spleen,
+}
+impl ZoomPunch {
+    fn zoom(&self) -> f32 {
+        1.0 + self.amount * (1.0 - self.spleen.interp(self.timer.fraction()))
+    }
+}
+
+/// A scripted move. `to` of `None` means going back to following the bird.
+#[derive(Debug, Clone, Reflect)]
+struct CameraPan {
+    from: (Vec2, f32),
+    to: Option<(Vec2, f32)>,
+    timer: Timer,
+    spleen: Spleen,
+}
+
+/// The one place to tell the cameras what to do. Left alone it follows the bird around the room.
+/// - Shake: add trauma, it wears off on its own. Offset goes with the square of trauma so small hits stay small.
+/// - Zoom punches: zoom in a bit and ease back out, for hits that should feel big
+/// - Pans: slide (and zoom) somewhere and stay there until `release`d
+///
+/// NOTE: Everything in here runs on real time, hit-stop freezes bullet time and the camera should keep going
+#[derive(Resource, Debug, Reflect)]
+pub struct CameraDirector {
+    trauma: f32,
+    /// Smoothed spot following the bird, clamped to the room
+    follow_pos: Vec2,
+    /// Where to look when there's no bird to follow (the editor pans this around)
+    pub idle: Vec2,
+    punch: Option<ZoomPunch>,
+    pan: Option<CameraPan>,
+    /// Where the cameras are this frame, before shake
+    pos: Vec2,
+    zoom: f32,
+    /// `pos` plus shake
+    offset: Vec2,
+}
+impl Default for CameraDirector {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            follow_pos: Vec2::ZERO,
+            idle: Vec2::ZERO,
+            punch: None,
+            pan: None,
+            pos: Vec2::ZERO,
+            zoom: 1.0,
+            offset: Vec2::ZERO,
+        }
+    }
+}
+impl CameraDirector {
+    pub fn add_trauma(&mut self, amt: f32) {
+        self.trauma = (self.trauma + amt).clamp(0.0, 1.0);
+    }
+
+    pub fn get_trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Zooms in by `amount` (0.1 is 10% closer) right away, then eases back out over `secs`.
+    /// A smaller punch won't cut off a bigger one that's still going.
+    pub fn punch_zoom(&mut self, amount: f32, secs: f32) {
+        if self
+            .punch
+            .as_ref()
+            .is_some_and(|punch| punch.zoom() - 1.0 > amount)
+        {
+            return;
+        }
+        self.punch = Some(ZoomPunch {
+            amount,
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+            spleen: Spleen::EaseOutQuad,
+        });
+    }
+
+    /// Slides over to `pos` (zoomed to `zoom`) over `secs`, and stays there until `release`
+    pub fn pan_to(&mut self, pos: Vec2, zoom: f32, secs: f32, spleen: Spleen) {
+        self.pan = Some(CameraPan {
+            from: (self.pos, self.zoom_without_punch()),
+            to: Some((pos, zoom)),
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+            spleen,
+        });
+    }
+
+    /// Eases back to following the bird after a `pan_to`
+    pub fn release(&mut self, secs: f32, spleen: Spleen) {
+        if self.pan.is_none() {
+            return;
+        }
+        self.pan = Some(CameraPan {
+            from: (self.pos, self.zoom_without_punch()),
+            to: None,
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+            spleen,
+        });
+    }
+
+    /// Drops any pan or punch on the spot. Shake is left to wear off.
+    pub fn reset(&mut self) {
+        self.pan = None;
+        self.punch = None;
+    }
+
+    /// Add this to a (zoomed) screen position to get a world position
+    pub fn get_offset(&self) -> Vec2 {
+        self.offset
+    }
+
+    /// Bigger is closer. Divide a screen position by this before adding the offset.
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    fn zoom_without_punch(&self) -> f32 {
+        self.zoom / self.punch.as_ref().map_or(1.0, |punch| punch.zoom())
+    }
+}
+
+/// How big the current room is. Rooms are always centered on the origin.
+/// Anything that isn't a fight (menus, tutorials, bosses...) is exactly one screen.
+#[derive(Resource, Debug, Clone, Copy, Reflect)]
+pub struct RoomBounds {
+    pub size: Vec2,
+}
+impl Default for RoomBounds {
+    fn default() -> Self {
+        Self {
+            size: IDEAL_VEC_f32,
+        }
+    }
+}
+impl RoomBounds {
+    pub fn new(size: Vec2) -> Self {
+        // Never smaller than the screen, there'd be nothing to clamp to
+        Self {
+            size: size.max(IDEAL_VEC_f32),
+        }
+    }
+
+    /// How far the camera can get from the origin before it'd see past the edge of the room.
+    /// Zoomed in, the screen covers less of the room so there's more space to move.
+    fn max_camera_offset(&self, zoom: f32) -> Vec2 {
+        ((self.size - IDEAL_VEC_f32 / zoom) / 2.0).max(Vec2::ZERO)
+    }
+}
+
+/// Stays put on the screen no matter where the camera goes (or how far it zooms).
+/// Sets xy and scale of `Transform` every frame.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+pub struct ScreenSpace {
+    pub anchor: Vec2,
+}
+impl ScreenSpace {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            anchor: Vec2::new(x, y),
+        }
+    }
+}
+
+/// Roots that hold screen stuff (menus, text, transitions...) ride along with the camera
+fn setup_screen_space_roots(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    debug_root: Res<DebugRoot>,
+    pause_root: Res<PauseRoot>,
+    transition_root: Res<TransitionRoot>,
+    convo_root: Res<ConvoRoot>,
+) {
+    for eid in [
+        menu_root.eid(),
+        debug_root.eid(),
+        pause_root.eid(),
+        transition_root.eid(),
+        convo_root.eid(),
+    ] {
+        commands.entity(eid).insert(ScreenSpace::default());
+    }
+}
+
+/// Only fights get to be bigger than the screen (and the editor, so you can build them).
+/// Pans and punches don't carry over to a new room either, but moving along in the same room (like clearing it) keeps them.
+fn reset_room_bounds(
+    meta_state: Res<State<MetaState>>,
+    mut transitions: EventReader<StateTransitionEvent<MetaState>>,
+    mut bounds: ResMut<RoomBounds>,
+    mut director: ResMut<CameraDirector>,
+) {
+    let same_room = transitions.read().last().is_some_and(|transition| {
+        let exited = transition
+            .exited
+            .as_ref()
+            .and_then(|state| state.get_room_state());
+        let entered = transition
+            .entered
+            .as_ref()
+            .and_then(|state| state.get_room_state());
+        match (exited, entered) {
+            (Some(from), Some(to)) => from.is_further_along(&to),
+            _ => false,
+        }
+    });
+    if !same_room {
+        director.reset();
+    }
+    let keep = matches!(
+        meta_state.get(),
+        MetaState::Room(RoomState::Encounter(_)) | MetaState::Editor(_)
+    );
+    if !keep {
+        *bounds = default();
+    }
+}
+
+fn direct_cameras(
+    mut director: ResMut<CameraDirector>,
+    bounds: Res<RoomBounds>,
+    bird: Query<(&Bird, &GlobalTransform, &DynoTran)>,
+    mouse_input: Res<MouseInput>,
+    mut cameras: Query<
+        (&mut Transform, &mut OrthographicProjection),
+        Or<(
+            With<BgSpriteCamera>,
+            With<BgLightCamera>,
+            With<SpriteCamera>,
+            With<LightCamera>,
+            With<MenuCamera>,
+        )>,
+    >,
+    time: Res<Time>,
+) {
+    let director = director.as_mut();
+    let delta = time.delta();
+
+    // Following
+    let target = match bird.get_single() {
+        Ok((bird, gtran, dyno_tran)) => {
+            // Peek where the bird's about to go, or where it's going now
+            let look_ahead = match mouse_input.get_left_drag() {
+                Some(drag) if bird.get_launches_left() > 0 => {
+                    drag.normalize_or_zero() * (drag.length() / LOOK_AHEAD_DRAG).min(1.0)
+                }
+                _ => {
+                    dyno_tran.vel.normalize_or_zero()
+                        * (dyno_tran.vel.length() / LOOK_AHEAD_SPEED).min(1.0)
+                }
+            };
+            gtran.translation().truncate() + look_ahead * LOOK_AHEAD
+        }
+        Err(_) => director.idle,
+    };
+    let max = bounds.max_camera_offset(1.0);
+    let target = target.clamp(-max, max);
+    if bounds.is_changed() {
+        // New room, don't swoop across it
+        director.follow_pos = target;
+    } else {
+        let t = 1.0 - (-FOLLOW_RATE * delta.as_secs_f32()).exp();
+        director.follow_pos = director.follow_pos.lerp(target, t);
+    }
+
+    // Pans take over from following while they're going
+    let (mut pos, mut zoom) = (director.follow_pos, 1.0);
+    if let Some(pan) = director.pan.as_mut() {
+        pan.timer.tick(delta);
+        let t = pan.spleen.interp(pan.timer.fraction());
+        let (to_pos, to_zoom) = pan.to.unwrap_or((director.follow_pos, 1.0));
+        pos = pan.from.0.lerp(to_pos, t);
+        zoom = pan.from.1.lerp(to_zoom, t);
+        if pan.timer.finished() && pan.to.is_none() {
+            director.pan = None;
+        }
+    }
+
+    // Punches go on top of whatever else is happening
+    if let Some(punch) = director.punch.as_mut() {
+        punch.timer.tick(delta);
+        zoom *= punch.zoom();
+        if punch.timer.finished() {
+            director.punch = None;
+        }
+    }
+    let max = bounds.max_camera_offset(zoom);
+    director.pos = pos.clamp(-max, max);
+    director.zoom = zoom;
+
+    // Shake
+    director.trauma = (director.trauma - SHAKE_DECAY * delta.as_secs_f32()).max(0.0);
+    let strength = director.trauma.powi(2) * MAX_SHAKE_OFFSET;
+    let mut rng = thread_rng();
+    let shake = Vec2::new(
+        rng.gen_range(-1.0..1.0) * strength,
+        rng.gen_range(-1.0..1.0) * strength,
+    );
+    director.offset = director.pos + shake;
+
+    for (mut tran, mut projection) in &mut cameras {
+        // All layers have to move together or the lights won't line up
+        tran.translation.x = director.offset.x;
+        tran.translation.y = director.offset.y;
+        projection.scale = 1.0 / (IDEAL_GROWTH_f32 * director.zoom);
+    }
+}
+
+fn pin_screen_space(
+    director: Res<CameraDirector>,
+    mut pinned: Query<(&mut Transform, &ScreenSpace)>,
+) {
+    let zoom = director.get_zoom();
+    for (mut tran, screen_space) in &mut pinned {
+        let pos = screen_space.anchor / zoom + director.get_offset();
+        tran.translation.x = pos.x;
+        tran.translation.y = pos.y;
+        tran.scale = Vec3::new(1.0 / zoom, 1.0 / zoom, 1.0);
+    }
+}
+
 pub(super) struct CameraPlugin;
 impl Plugin for CameraPlugin {
-    fn build(&self, _app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CameraDirector::default());
+        app.insert_resource(RoomBounds::default());
+        app.register_type::<CameraDirector>();
+        app.register_type::<RoomBounds>();
+
+        app.add_systems(Startup, setup_screen_space_roots.after(RootInit));
+        app.add_systems(Update, reset_room_bounds.run_if(state_changed::<MetaState>));
+        app.add_systems(
+            Update,
+            (direct_cameras, pin_screen_space)
+                .chain()
+                .after(reset_room_bounds)
+                .after(PhysicsSet),
+        );
+    }
 }
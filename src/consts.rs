@@ -41,6 +41,7 @@ pub const ZIX_PAUSE: f32 = 200.0;
 pub const ZIX_SIMP: f32 = 1.0;
 pub const ZIX_SPEW: f32 = 2.0;
 pub const ZIX_STICKY: f32 = 3.0;
+pub const ZIX_TELEGRAPH: f32 = 0.5;
 pub const ZIX_TRANSITION: f32 = 500.0;
 pub const ZIX_TUTORIAL_TRIGGER: f32 = 4.0;
 pub const ZIX_UPGRADE_BUTTON: f32 = 10.0; // NOTE: In menu camera
@@ -0,0 +1,527 @@
+//! Enemy brains. An `AiMachine` is a little state machine where every state is a list of `AiAction`s
+//! (the behaviours) plus an `AiExit` that says when to move on. Enemies compose these instead of
+//! each getting their own steering systems. Anything truly bespoke can listen for `AiStateChanged`.
+
+use std::{f32::consts::PI, time::Duration};
+
+use rand::Rng;
+
+use crate::prelude::*;
+
+/// All the AI runs in here, before physics so steering shows up the same frame
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AiSet;
+
+#[derive(Debug, Clone, Reflect)]
+pub enum AiAction {
+    /// Accelerate towards where the bird will be in `prefer_future` seconds
+    Seek {
+        accel: f32,
+        max_speed: f32,
+        prefer_future: f32,
+    },
+    /// Accelerate straight away from the bird
+    Flee { accel: f32, max_speed: f32 },
+    /// Circle the bird at about `radius`
+    Orbit {
+        radius: f32,
+        accel: f32,
+        max_speed: f32,
+    },
+    /// Go towards the bird if further than `distance`, away if closer
+    KeepAway {
+        distance: f32,
+        accel: f32,
+        max_speed: f32,
+    },
+    /// Rotate (the transform, not the velocity) so "up" points at the bird, at most `turn_speed` rad/s
+    Face { turn_speed: f32, prefer_future: f32 },
+    /// Slow to a stop
+    Wait,
+    /// Lock onto the bird when the state starts and rush that way
+    Charge { speed: f32, accel: f32 },
+    /// When the state starts, reset each (manager, key) pair in the multi
+    Animate { anims: Vec<(String, String)> },
+    /// Hurt the bird with the points of the `manager` animation whenever it's showing `key`
+    Attack {
+        manager: String,
+        key: String,
+        damage: Damage,
+        /// Played the first frame the attack is live
+        sound: Option<(String, f32)>,
+    },
+    /// Make the `PatternShooter` start an attack right away
+    Fire,
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub enum AiExit {
+    /// Stay in this state forever
+    Never,
+    /// Leave after a random number of seconds in this range
+    After(f32, f32),
+    /// Leave once the `manager` animation gets to `key`
+    AnimKey { manager: String, key: String },
+    /// Leave once the `PatternShooter` is done attacking
+    FireDone,
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct AiState {
+    pub name: String,
+    pub actions: Vec<AiAction>,
+    pub exit: AiExit,
+    pub next: String,
+}
+impl AiState {
+    pub fn new(name: &str, actions: Vec<AiAction>) -> Self {
+        Self {
+            name: name.to_string(),
+            actions,
+            exit: AiExit::Never,
+            next: name.to_string(),
+        }
+    }
+
+    pub fn with_exit(mut self, exit: AiExit, next: &str) -> Self {
+        self.exit = exit;
+        self.next = next.to_string();
+        self
+    }
+}
+
+/// Starts in the first state. Doesn't do anything while `Birthing`, `Dying`, `Dead` or `Stunned`.
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct AiMachine {
+    states: Vec<AiState>,
+    current: usize,
+    timer: Timer,
+    /// Whether the current state has run its on-enter stuff yet
+    entered: bool,
+    /// Whether the current state's attack has gone live yet (for the sound)
+    attack_live: bool,
+    charge_dir: Vec2,
+}
+impl AiMachine {
+    pub fn new(states: Vec<AiState>) -> Self {
+        assert!(!states.is_empty(), "AiMachine needs at least one state");
+        Self {
+            states,
+            current: 0,
+            timer: Timer::from_seconds(0.0, TimerMode::Once),
+            entered: false,
+            attack_live: false,
+            charge_dir: Vec2::ZERO,
+        }
+    }
+
+    /// A brain that only ever does one thing
+    pub fn single(actions: Vec<AiAction>) -> Self {
+        Self::new(vec![AiState::new("only", actions)])
+    }
+
+    /// Makes every movement in every state faster (or slower)
+    pub fn scale_speed(&mut self, mult: f32) {
+        for state in &mut self.states {
+            for action in &mut state.actions {
+                match action {
+                    AiAction::Seek {
+                        accel, max_speed, ..
+                    }
+                    | AiAction::Flee { accel, max_speed }
+                    | AiAction::Orbit {
+                        accel, max_speed, ..
+                    }
+                    | AiAction::KeepAway {
+                        accel, max_speed, ..
+                    } => {
+                        *accel *= mult;
+                        *max_speed *= mult;
+                    }
+                    AiAction::Charge { speed, accel } => {
+                        *speed *= mult;
+                        *accel *= mult;
+                    }
+                    AiAction::Face { turn_speed, .. } => {
+                        *turn_speed *= mult;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// The fastest the current state wants to move, if it moves at all
+    pub fn max_speed(&self) -> Option<f32> {
+        self.actions()
+            .filter_map(|action| match *action {
+                AiAction::Seek { max_speed, .. }
+                | AiAction::Flee { max_speed, .. }
+                | AiAction::Orbit { max_speed, .. }
+                | AiAction::KeepAway { max_speed, .. } => Some(max_speed),
+                AiAction::Charge { speed, .. } => Some(speed),
+                _ => None,
+            })
+            .reduce(f32::max)
+    }
+
+    pub fn state(&self) -> &AiState {
+        &self.states[self.current]
+    }
+
+    pub fn state_name(&self) -> &str {
+        &self.state().name
+    }
+
+    fn actions(&self) -> impl Iterator<Item = &AiAction> {
+        self.state().actions.iter()
+    }
+
+    /// Timers get rolled in `enter_state`, which is the first place that has the run's rng
+    fn reset_timer(&mut self, rng: &mut impl Rng) {
+        let secs = match self.state().exit {
+            AiExit::After(lo, hi) if lo < hi => rng.gen_range(lo..hi),
+            AiExit::After(lo, _) => lo,
+            _ => 0.0,
+        };
+        self.timer = Timer::from_seconds(secs, TimerMode::Once);
+    }
+
+    fn go_to(&mut self, name: &str) {
+        let Some(ix) = self.states.iter().position(|state| state.name == name) else {
+            warn!("AiMachine has no state named {name}");
+            return;
+        };
+        self.current = ix;
+        self.entered = false;
+        self.attack_live = false;
+    }
+}
+
+#[derive(Event, Debug, Clone)]
+pub struct AiStateChanged {
+    pub eid: Entity,
+    pub from: String,
+    pub to: String,
+}
+
+/// Runs on-enter actions and moves machines between states
+fn tick_ai_machines(
+    mut machines: Query<
+        (
+            Entity,
+            &mut AiMachine,
+            &GlobalTransform,
+            Option<&mut MultiAnimationManager>,
+            Option<&mut PatternShooter>,
+        ),
+        (
+            Without<Birthing>,
+            Without<Dying>,
+            Without<Dead>,
+            Without<Stunned>,
+        ),
+    >,
+    bird: Query<&GlobalTransform, With<Bird>>,
+    mut state_changed: EventWriter<AiStateChanged>,
+    mut commands: Commands,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    let rng = run_rng.stream(RngStream::Enemies);
+    let bird_pos = bird
+        .get_single()
+        .map(|gtran| gtran.translation().truncate())
+        .ok();
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (eid, mut machine, gtran, mut multi, mut shooter) in &mut machines {
+        let my_pos = gtran.translation().truncate();
+        if !machine.entered {
+            enter_state(
+                &mut machine,
+                multi.as_deref_mut(),
+                shooter.as_deref_mut(),
+                my_pos,
+                bird_pos,
+                rng,
+                &mut commands,
+            );
+        }
+        machine.timer.tick(Duration::from_secs_f32(time_factor));
+        let done = match &machine.state().exit {
+            AiExit::Never => false,
+            AiExit::After(..) => machine.timer.finished(),
+            AiExit::AnimKey { manager, key } => multi
+                .as_ref()
+                .map(|multi| multi.manager(manager).get_key() == *key)
+                .unwrap_or(false),
+            AiExit::FireDone => shooter
+                .as_ref()
+                .map(|shooter| !shooter.is_attacking() && !shooter.wants_to_fire())
+                .unwrap_or(true),
+        };
+        if done {
+            let from = machine.state_name().to_string();
+            let to = machine.state().next.clone();
+            machine.go_to(&to);
+            enter_state(
+                &mut machine,
+                multi.as_deref_mut(),
+                shooter.as_deref_mut(),
+                my_pos,
+                bird_pos,
+                rng,
+                &mut commands,
+            );
+            state_changed.send(AiStateChanged { eid, from, to });
+        }
+    }
+}
+
+/// The on-enter half of Animate, Fire and Charge
+fn enter_state(
+    machine: &mut AiMachine,
+    mut multi: Option<&mut MultiAnimationManager>,
+    mut shooter: Option<&mut PatternShooter>,
+    my_pos: Vec2,
+    bird_pos: Option<Vec2>,
+    rng: &mut impl Rng,
+    commands: &mut Commands,
+) {
+    machine.entered = true;
+    machine.reset_timer(rng);
+    let mut charge_dir = None;
+    for action in machine.actions() {
+        match action {
+            AiAction::Animate { anims } => {
+                let Some(multi) = multi.as_mut() else {
+                    continue;
+                };
+                for (manager, key) in anims {
+                    multi
+                        .manager_mut(manager)
+                        .reset_key_with_points(key, commands);
+                }
+            }
+            AiAction::Fire => {
+                if let Some(shooter) = shooter.as_mut() {
+                    shooter.fire_now();
+                }
+            }
+            AiAction::Charge { .. } => {
+                charge_dir = bird_pos.map(|bird_pos| (bird_pos - my_pos).normalize_or_zero());
+            }
+            _ => {}
+        }
+    }
+    if let Some(charge_dir) = charge_dir {
+        machine.charge_dir = charge_dir;
+    }
+}
+
+/// Seek, Flee, Orbit, KeepAway, Wait and Charge
+pub(super) fn steer_ai(
+    bird: Query<(&GlobalTransform, &DynoTran), With<Bird>>,
+    mut machines: Query<
+        (
+            &AiMachine,
+            &mut DynoTran,
+            &GlobalTransform,
+            Option<&NavAgent>,
+        ),
+        (
+            Without<Bird>,
+            Without<Birthing>,
+            Without<Dying>,
+            Without<Dead>,
+            Without<Stunned>,
+        ),
+    >,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let Ok((bird_gtran, bird_dyno_tran)) = bird.get_single() else {
+        return;
+    };
+    let bird_pos = bird_gtran.translation().truncate();
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (machine, mut dyno_tran, gtran, nav_agent) in &mut machines {
+        let my_pos = gtran.translation().truncate();
+        for action in machine.actions() {
+            match *action {
+                AiAction::Seek {
+                    accel,
+                    max_speed,
+                    prefer_future,
+                } => {
+                    // Follow the path if there is one, otherwise the bird is in plain sight
+                    let goal = nav_agent
+                        .and_then(|agent| agent.next_waypoint())
+                        .unwrap_or(bird_pos + bird_dyno_tran.vel * prefer_future);
+                    let diff = goal - my_pos;
+                    dyno_tran.vel += diff.normalize_or_zero() * accel * time_factor;
+                    dyno_tran.vel = dyno_tran.vel.clamp_length(0.0, max_speed);
+                }
+                AiAction::Flee { accel, max_speed } => {
+                    let diff = my_pos - bird_pos;
+                    dyno_tran.vel += diff.normalize_or_zero() * accel * time_factor;
+                    dyno_tran.vel = dyno_tran.vel.clamp_length(0.0, max_speed);
+                }
+                AiAction::Orbit {
+                    radius,
+                    accel,
+                    max_speed,
+                } => {
+                    let diff = bird_pos - my_pos;
+                    let towards = diff.normalize_or_zero();
+                    let around = towards.perp();
+                    // Mostly go around, drift in or out depending on how far off the radius we are
+                    let correction = ((diff.length() - radius) / radius.max(1.0)).clamp(-1.0, 1.0);
+                    let goal_dir = (around + towards * correction).normalize_or_zero();
+                    dyno_tran.vel += goal_dir * accel * time_factor;
+                    dyno_tran.vel = dyno_tran.vel.clamp_length(0.0, max_speed);
+                }
+                AiAction::KeepAway {
+                    distance,
+                    accel,
+                    max_speed,
+                } => {
+                    let diff = bird_pos - my_pos;
+                    let goal_dir = if diff.length() > distance {
+                        diff.normalize_or_zero()
+                    } else {
+                        -diff.normalize_or_zero()
+                    };
+                    dyno_tran.vel += goal_dir * accel * time_factor;
+                    dyno_tran.vel = dyno_tran.vel.clamp_length(0.0, max_speed);
+                }
+                AiAction::Wait => {
+                    dyno_tran.vel *= (1.0 - 3.0 * time_factor).max(0.0);
+                }
+                AiAction::Charge { speed, accel } => {
+                    let goal_vel = machine.charge_dir * speed;
+                    let diff = goal_vel - dyno_tran.vel;
+                    dyno_tran.vel += diff.clamp_length(0.0, accel * time_factor);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn face_ai(
+    bird: Query<(&GlobalTransform, &DynoTran), With<Bird>>,
+    mut machines: Query<
+        (&AiMachine, &mut Transform, &GlobalTransform),
+        (
+            Without<Bird>,
+            Without<Birthing>,
+            Without<Dying>,
+            Without<Dead>,
+        ),
+    >,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let Ok((bird_gtran, bird_dyno_tran)) = bird.get_single() else {
+        return;
+    };
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (machine, mut tran, gtran) in &mut machines {
+        for action in machine.actions() {
+            let AiAction::Face {
+                turn_speed,
+                prefer_future,
+            } = *action
+            else {
+                continue;
+            };
+            let (my_pos, my_angle) = gtran.tran_n_angle();
+            let goal_bird_pos =
+                bird_gtran.translation().truncate() + bird_dyno_tran.vel * prefer_future;
+            let diff = goal_bird_pos - my_pos;
+            let short_rot = shortest_rotation(my_angle - PI / 2.0, diff.to_angle());
+            let max_rot = turn_speed * time_factor;
+            let rot = short_rot.signum() * short_rot.abs().clamp(0.0, max_rot);
+            tran.set_angle(my_angle + rot);
+        }
+    }
+}
+
+fn attack_ai(
+    bird: Query<(Entity, &Bounds, &GlobalTransform), With<Bird>>,
+    mut machines: Query<
+        (
+            Entity,
+            &mut AiMachine,
+            &MultiAnimationManager,
+            &GlobalTransform,
+        ),
+        (Without<Birthing>, Without<Dying>, Without<Dead>),
+    >,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut commands: Commands,
+) {
+    let Ok((bird_eid, bird_bounds, bird_gtran)) = bird.get_single() else {
+        return;
+    };
+    for (eid, mut machine, multi, gtran) in &mut machines {
+        let mut went_live = false;
+        for action in machine.actions() {
+            let AiAction::Attack {
+                manager,
+                key,
+                damage,
+                sound,
+            } = action
+            else {
+                continue;
+            };
+            if multi.manager(manager).get_key() != *key {
+                continue;
+            }
+            if !machine.attack_live {
+                went_live = true;
+                if let Some((path, volume)) = sound {
+                    commands.spawn(SoundEffect::universal(path, *volume));
+                }
+            }
+            let harmful_shape = Shape::Polygon {
+                points: multi.manager(manager).get_points(),
+            };
+            let (hp1, hp2) = gtran.tran_n_angle();
+            if bird_bounds
+                .get_shape()
+                .bounce_off(bird_gtran.tran_n_angle(), (&harmful_shape, hp1, hp2))
+                .is_some()
+            {
+                // Fine to send every frame, i-frames will stop this from stacking
+                damage_events.send(DamageEvent::from_damage(
+                    bird_eid,
+                    Some(eid),
+                    damage,
+                    hp1,
+                    bird_gtran.translation().truncate(),
+                ));
+            }
+        }
+        if went_live {
+            machine.attack_live = true;
+        }
+    }
+}
+
+pub(super) fn register_ai(app: &mut App) {
+    app.register_type::<AiMachine>();
+    app.add_event::<AiStateChanged>();
+
+    app.configure_sets(Update, AiSet.before(PhysicsSet));
+    app.add_systems(
+        Update,
+        (tick_ai_machines, steer_ai, face_ai, attack_ai)
+            .chain()
+            .in_set(AiSet)
+            .run_if(in_state(PhysicsState::Active)),
+    );
+}
@@ -0,0 +1,437 @@
+use std::f32::consts::PI;
+
+use crate::prelude::*;
+
+/// The big one. Lives on the guide, same as the other enemies.
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct BossGuide {
+    level: u32,
+    phase: BossPhase,
+}
+impl BossGuide {
+    const SCALE: f32 = 3.0;
+    const RADIUS: f32 = 26.0;
+
+    pub fn max_health(level: u32) -> u32 {
+        30 + 15 * level
+    }
+}
+
+#[derive(Component, Debug, Clone, Default, Reflect)]
+pub struct BossHurtbox;
+
+/// Bosses get meaner as they get hurt. Each phase has its own movement, bullets and sprite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BossPhase {
+    /// Walks at you and fires fans of bullets
+    Stalk,
+    /// Circles around, rings of bullets, calls in some simps
+    Circle,
+    /// Charges at you over and over, spirals
+    Frenzy,
+}
+impl BossPhase {
+    pub const ALL: [Self; 3] = [Self::Stalk, Self::Circle, Self::Frenzy];
+
+    /// The phase starts once the boss is at or below this fraction of its health
+    pub fn threshold(&self) -> f32 {
+        match self {
+            Self::Stalk => 1.0,
+            Self::Circle => 0.66,
+            Self::Frenzy => 0.33,
+        }
+    }
+
+    /// Which phase the boss should be in at this fraction of its health
+    pub fn from_frac(frac: f32) -> Self {
+        Self::ALL
+            .into_iter()
+            .rev()
+            .find(|phase| frac <= phase.threshold())
+            .unwrap_or(Self::Stalk)
+    }
+
+    /// How many simps get called in when this phase starts
+    pub fn minions(&self, level: u32) -> u32 {
+        match self {
+            Self::Stalk => 0,
+            Self::Circle => 2 + level,
+            Self::Frenzy => 1 + level,
+        }
+    }
+
+    pub fn total_minions(level: u32) -> u32 {
+        Self::ALL.iter().map(|phase| phase.minions(level)).sum()
+    }
+
+    fn anim_key(&self) -> &'static str {
+        match self {
+            Self::Stalk => "stalk",
+            Self::Circle => "circle",
+            Self::Frenzy => "frenzy",
+        }
+    }
+
+    fn brain(&self, level: u32) -> AiMachine {
+        let speed_boost = 5.0 * level as f32;
+        match self {
+            Self::Stalk => AiMachine::single(vec![AiAction::Seek {
+                accel: 40.0,
+                max_speed: 15.0 + speed_boost,
+                prefer_future: 0.0,
+            }]),
+            Self::Circle => AiMachine::single(vec![AiAction::Orbit {
+                radius: 70.0,
+                accel: 60.0,
+                max_speed: 35.0 + speed_boost,
+            }]),
+            Self::Frenzy => AiMachine::new(vec![
+                AiState::new("windup", vec![AiAction::Wait])
+                    .with_exit(AiExit::After(0.6, 1.0), "charge"),
+                AiState::new(
+                    "charge",
+                    vec![AiAction::Charge {
+                        speed: 90.0 + speed_boost,
+                        accel: 200.0,
+                    }],
+                )
+                .with_exit(AiExit::After(1.2, 1.6), "windup"),
+            ]),
+        }
+    }
+
+    fn shooter(&self, level: u32) -> PatternShooter {
+        let cooldown_mult = 1.0 / (1.0 + 0.2 * (level - 1) as f32);
+        match self {
+            Self::Stalk => PatternShooter::new(
+                BulletPattern::Aimed {
+                    count: 5,
+                    spread: 0.9,
+                },
+                55.0,
+                2.0 * cooldown_mult,
+            ),
+            Self::Circle => PatternShooter::new(
+                BulletPattern::Radial { count: 12 },
+                45.0,
+                2.5 * cooldown_mult,
+            ),
+            Self::Frenzy => PatternShooter::new(
+                BulletPattern::Spiral {
+                    arms: 3,
+                    turn: PI / 12.0,
+                },
+                50.0,
+                3.0 * cooldown_mult,
+            ),
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct BossBundle {
+    name: Name,
+    guide: BossGuide,
+    face_dyno: FaceDyno,
+    physics: SimpGuidePhysicsBundle,
+    multi: MultiAnimationManager,
+    birthing: Birthing,
+}
+impl BossBundle {
+    pub fn new(pos: Vec2, level: u32) -> Self {
+        let color: Color = tailwind::PURPLE_400.into();
+        Self {
+            name: Name::new("boss_guide (body)"),
+            guide: BossGuide {
+                level,
+                phase: BossPhase::Stalk,
+            },
+            face_dyno: FaceDyno,
+            physics: SimpGuidePhysicsBundle::new(pos, BossGuide::RADIUS),
+            multi: multi!([
+                (
+                    "core",
+                    anim_man!({
+                        birthing: {
+                            path: "enemies/simp/simp_spawn.png",
+                            size: (20, 20),
+                            length: 6,
+                            fps: 6.0,
+                            next: "stalk",
+                            color: color,
+                        },
+                        stalk: {
+                            path: "enemies/simp/simp_health3.png",
+                            size: (20, 20),
+                            color: color,
+                        },
+                        circle: {
+                            path: "enemies/simp/simp_health2.png",
+                            size: (20, 20),
+                            color: color,
+                        },
+                        frenzy: {
+                            path: "enemies/simp/simp_health1.png",
+                            size: (20, 20),
+                            color: color,
+                        },
+                        death: {
+                            path: "enemies/simp/simp_death.png",
+                            size: (20, 20),
+                            length: 4,
+                            fps: 6.0,
+                            next: "despawn",
+                            color: color,
+                        },
+                    })
+                    .with_scale(Vec2::ONE * BossGuide::SCALE),
+                ),
+                (
+                    "light",
+                    anim_man!({
+                        spawn: {
+                            path: "enemies/simp/simp_spawn_light.png",
+                            size: (30, 30),
+                            length: 6,
+                            fps: 6.0,
+                            next: "steady",
+                        },
+                        steady: {
+                            path: "enemies/simp/simp_light.png",
+                            size: (30, 30),
+                        },
+                    })
+                    .with_render_layers(LightCamera::render_layers())
+                    .with_scale(Vec2::ONE * BossGuide::SCALE),
+                ),
+            ]),
+            birthing: Birthing,
+        }
+    }
+
+    pub fn spawn(pos: Vec2, level: u32, commands: &mut Commands, parent: Entity) {
+        commands
+            .spawn(Self::new(pos, level))
+            .with_children(|dad| {
+                dad.spawn((
+                    Name::new("boss_hurtbox"),
+                    BossHurtbox,
+                    EnemyHurtbox,
+                    Health::new(BossGuide::max_health(level))
+                        .with_i_frames(0.05)
+                        .with_vulnerable_to([TriggerKind::BulletGood]),
+                    Damage::new(1)
+                        .with_knockback(200.0)
+                        .with_hit_stop(0.12)
+                        .with_shake(0.7),
+                    SimpHurtboxPhysicsBundle::new(BossGuide::RADIUS - 4.0),
+                    // Bosses pay out
+                    LootTable(vec![
+                        LootDrop {
+                            kind: LootKind::Heart,
+                            chance: 1.0,
+                            count: 2,
+                        },
+                        LootDrop {
+                            kind: LootKind::Feather,
+                            chance: 0.8,
+                            count: 5 * level,
+                        },
+                    ]),
+                    Birthing,
+                ));
+            })
+            .set_parent(parent);
+        commands.spawn(SoundEffect::universal("sound_effects/simp_spawn.ogg", 0.8));
+    }
+}
+
+fn birth_bosses(
+    mut commands: Commands,
+    birthing: Query<(Entity, &MultiAnimationManager, &BossGuide, &Children), With<Birthing>>,
+) {
+    for (eid, multi, guide, children) in &birthing {
+        if multi.manager("core").get_key().as_str() == "birthing" {
+            continue;
+        }
+        commands.entity(eid).remove::<Birthing>().insert((
+            StaticReceiver::from_kind(StaticReceiverKind::Normal),
+            guide.phase.brain(guide.level),
+            guide.phase.shooter(guide.level),
+        ));
+        for child in children {
+            commands.entity(*child).remove::<Birthing>();
+        }
+    }
+}
+
+fn hurt_bosses(
+    mut damage_taken: EventReader<DamageTaken>,
+    mut boss_guides: Query<(&mut BossGuide, &mut MultiAnimationManager, &GlobalTransform)>,
+    boss_hurtboxes: Query<(&Health, &Parent), With<BossHurtbox>>,
+    mut commands: Commands,
+    mut bird: Query<&mut Bird>,
+    mut camera_director: ResMut<CameraDirector>,
+    mut hit_stop: ResMut<HitStop>,
+    room_root: Res<RoomRoot>,
+) {
+    for event in damage_taken.read() {
+        let Ok((health, parent)) = boss_hurtboxes.get(event.target) else {
+            continue;
+        };
+        let Ok((mut guide, mut multi, gtran)) = boss_guides.get_mut(parent.get()) else {
+            continue;
+        };
+        commands.spawn(SoundEffect::universal(
+            "sound_effects/simp_take_damage.ogg",
+            0.3,
+        ));
+        if event.killed {
+            multi.manager_mut("core").set_key("death", &mut commands);
+            multi.manager_mut("light").set_hidden(true, &mut commands);
+            commands
+                .entity(parent.get())
+                .remove::<AiMachine>()
+                .remove::<PatternShooter>();
+            commands.entity(event.target).remove::<Damage>();
+            commands.entity(event.target).insert(Dying {
+                timer: Timer::from_seconds(2.0, TimerMode::Once),
+                dont_despawn: false,
+            });
+            commands.spawn(SoundEffect::universal("sound_effects/simp_death2.ogg", 1.0));
+            camera_director.add_trauma(1.0);
+            hit_stop.start(0.4);
+            if let Ok(mut bird) = bird.get_single_mut() {
+                bird.dec_kills_left(1);
+            }
+            continue;
+        }
+        let frac = health.get_current() as f32 / health.get_max() as f32;
+        let phase = BossPhase::from_frac(frac);
+        if phase == guide.phase {
+            continue;
+        }
+        // New phase, make a big deal about it
+        guide.phase = phase;
+        multi
+            .manager_mut("core")
+            .set_key(phase.anim_key(), &mut commands);
+        commands
+            .entity(parent.get())
+            .insert((phase.brain(guide.level), phase.shooter(guide.level)));
+        commands.spawn(SoundEffect::universal("sound_effects/spew_death1.ogg", 0.4));
+        camera_director.add_trauma(0.8);
+        hit_stop.start(0.25);
+        let pos = gtran.translation().truncate();
+        let num_minions = phase.minions(guide.level);
+        for ix in 0..num_minions {
+            let angle = 2.0 * PI * ix as f32 / num_minions as f32;
+            let offset = Vec2::X.my_rotate(angle) * (BossGuide::RADIUS + 20.0);
+            SimpBundle::spawn(pos + offset, &mut commands, room_root.eid());
+        }
+    }
+}
+
+#[derive(Component)]
+struct BossHealthBar;
+impl BossHealthBar {
+    const DIMS: Vec2 = Vec2::new(160.0, 4.0);
+    const BORDER: f32 = 1.0;
+}
+
+fn spawn_boss_health_bars(
+    mut commands: Commands,
+    new_bosses: Query<(), Added<BossHurtbox>>,
+    room_root: Res<RoomRoot>,
+) {
+    if new_bosses.is_empty() {
+        return;
+    }
+    commands
+        .spawn((
+            Name::new("boss_health_bar"),
+            BossHealthBar,
+            multi!([
+                (
+                    "bg",
+                    anim_man!({
+                        path: "sprites/default.png",
+                        size: (1, 1),
+                        color: Color::BLACK,
+                    })
+                    .with_points(simple_rect(
+                        BossHealthBar::DIMS.x + BossHealthBar::BORDER * 2.0,
+                        BossHealthBar::DIMS.y + BossHealthBar::BORDER * 2.0
+                    ))
+                    .with_offset(-Vec3::Z),
+                ),
+                (
+                    "fg",
+                    anim_man!({
+                        path: "sprites/default.png",
+                        size: (1, 1),
+                        color: tailwind::PURPLE_500.into(),
+                    })
+                    .with_points(simple_rect(BossHealthBar::DIMS.x, BossHealthBar::DIMS.y)),
+                )(
+                    "light",
+                    anim_man!({
+                        path: "sprites/default.png",
+                        size: (1, 1),
+                    })
+                    .with_render_layers(LightCamera::render_layers())
+                    .with_points(simple_rect(
+                        BossHealthBar::DIMS.x + BossHealthBar::BORDER * 2.0,
+                        BossHealthBar::DIMS.y + BossHealthBar::BORDER * 2.0
+                    )),
+                )
+            ]),
+            spat_tran(0.0, 80.0, ZIX_MAX - 0.1),
+            ScreenSpace::new(0.0, 80.0),
+        ))
+        .set_parent(room_root.eid());
+}
+
+fn update_boss_health_bars(
+    bosses: Query<&Health, With<BossHurtbox>>,
+    mut bars: Query<(Entity, &mut MultiAnimationManager), With<BossHealthBar>>,
+    mut commands: Commands,
+) {
+    let Ok((eid, mut multi)) = bars.get_single_mut() else {
+        return;
+    };
+    let Ok(health) = bosses.get_single() else {
+        // Boss is gone, so is the bar
+        commands.entity(eid).despawn_recursive();
+        return;
+    };
+    let frac_alive = health.get_current() as f32 / health.get_max() as f32;
+    let new_points = simple_rect(BossHealthBar::DIMS.x * frac_alive, BossHealthBar::DIMS.y)
+        .into_iter()
+        .map(|mut p| {
+            // Same deal as the bird's bar, keep the left edge put
+            p.x += BossHealthBar::DIMS.x * (frac_alive - 1.0) / 2.0;
+            p
+        })
+        .collect::<Vec<_>>();
+    multi
+        .manager_mut("fg")
+        .set_points(new_points, &mut commands);
+}
+
+pub(super) fn register_bosses(app: &mut App) {
+    app.register_type::<BossGuide>();
+    app.register_type::<BossHurtbox>();
+
+    app.add_systems(
+        Update,
+        (birth_bosses, hurt_bosses)
+            .run_if(in_state(PhysicsState::Active))
+            .after(PhysicsSet)
+            .after(DamageSet),
+    );
+    app.add_systems(
+        Update,
+        (spawn_boss_health_bars, update_boss_health_bars).run_if(state_exists::<BossState>),
+    );
+}
@@ -0,0 +1,529 @@
+//! Enemy archetypes described in `assets/enemies/<name>.enemy.json`.
+//! Behaviour still lives in rust (see `EnemyArchetype`), but all the numbers, sprites and sounds come from the file.
+//! Defs load once at startup, so edits need a restart.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    ecs::system::EntityCommands,
+};
+
+use crate::prelude::*;
+
+use super::elite::{add_shield, register_elite_archetype};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundDef {
+    pub path: String,
+    pub volume: f32,
+}
+impl SoundDef {
+    pub fn play(&self, commands: &mut Commands) {
+        commands.spawn(SoundEffect::universal(&self.path, self.volume));
+    }
+}
+
+fn default_length() -> u32 {
+    1
+}
+fn default_fps() -> f32 {
+    DEFAULT_ANIMATION_FPS
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimNodeDef {
+    pub key: String,
+    pub path: String,
+    pub size: (u32, u32),
+    #[serde(default = "default_length")]
+    pub length: u32,
+    #[serde(default = "default_fps")]
+    pub fps: f32,
+    #[serde(default)]
+    pub next: Option<String>,
+    /// srgba
+    #[serde(default)]
+    pub color: Option<[f32; 4]>,
+}
+impl AnimNodeDef {
+    fn to_node(&self) -> AnimationNode {
+        let mut sprite = SpriteInfo::new(&self.path, self.size.0, self.size.1);
+        if let Some([r, g, b, a]) = self.color {
+            sprite = sprite.with_color(Color::srgba(r, g, b, a));
+        }
+        AnimationNode {
+            sprite,
+            length: self.length,
+            fps: self.fps,
+            next: self.next.clone(),
+        }
+    }
+}
+
+/// One `AnimationManager` in the enemy's multi. The first node is where it starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimManagerDef {
+    pub key: String,
+    pub nodes: Vec<AnimNodeDef>,
+    /// Goes on the light layer instead of the sprite layer
+    #[serde(default)]
+    pub light: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub offset: [f32; 3],
+}
+impl AnimManagerDef {
+    fn to_manager(&self) -> AnimationManager {
+        let mut manager = AnimationManager::from_nodes(
+            self.nodes
+                .iter()
+                .map(|node| (node.key.as_str(), node.to_node()))
+                .collect(),
+        )
+        .with_hidden(self.hidden)
+        .with_offset(Vec3::from_array(self.offset));
+        if self.light {
+            manager = manager.with_render_layers(LightCamera::render_layers());
+        }
+        manager
+    }
+}
+
+/// Which entity the sprites go on. Most enemies draw on the guide, spews draw on the hurtbox so they can spin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnimsOn {
+    #[default]
+    Guide,
+    Hurtbox,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactDamageDef {
+    pub amount: u32,
+    #[serde(default)]
+    pub knockback: f32,
+    #[serde(default)]
+    pub hit_stop: f32,
+    #[serde(default)]
+    pub shake: f32,
+}
+impl ContactDamageDef {
+    fn to_damage(&self) -> Damage {
+        Damage::new(self.amount)
+            .with_knockback(self.knockback)
+            .with_hit_stop(self.hit_stop)
+            .with_shake(self.shake)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShooterDef {
+    /// One gets picked at random for each enemy. If it's empty the loader takes the shooter away (and says so).
+    pub patterns: Vec<BulletPattern>,
+    pub bullet_speed: f32,
+    pub cooldown_range: (f32, f32),
+}
+
+/// Everything about an enemy that isn't code
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct EnemyDef {
+    pub name: String,
+    pub static_radius: f32,
+    pub trigger_radius: f32,
+    pub speed_range: (f32, f32),
+    pub mult_range: (i32, i32),
+    /// How far ahead of the bird to aim. Only chasers care.
+    #[serde(default)]
+    pub future_range: (f32, f32),
+    /// How far from the bird to hang out. Only ranged enemies care.
+    #[serde(default)]
+    pub keep_away_range: (f32, f32),
+    pub health: u32,
+    pub contact_damage: ContactDamageDef,
+    #[serde(default)]
+    pub face_dyno: bool,
+    /// Path around statics instead of bumping into them
+    #[serde(default)]
+    pub pathfind: bool,
+    #[serde(default)]
+    pub anims_on: AnimsOn,
+    pub anims: Vec<AnimManagerDef>,
+    #[serde(default)]
+    pub shooter: Option<ShooterDef>,
+    #[serde(default)]
+    pub spawn_sound: Option<SoundDef>,
+    #[serde(default)]
+    pub hurt_sound: Option<SoundDef>,
+    #[serde(default)]
+    pub death_sound: Option<SoundDef>,
+    /// Rolled when it dies
+    #[serde(default)]
+    pub loot: Vec<LootDrop>,
+}
+impl EnemyDef {
+    pub fn multi(&self) -> MultiAnimationManager {
+        MultiAnimationManager::from_pairs(
+            self.anims
+                .iter()
+                .map(|anim| (anim.key.as_str(), anim.to_manager()))
+                .collect(),
+        )
+    }
+
+    /// Just one of the anims, for things that want to look like part of this enemy
+    pub fn anim(&self, key: &str) -> Option<AnimationManager> {
+        self.anims
+            .iter()
+            .find(|anim| anim.key == key)
+            .map(|anim| anim.to_manager())
+    }
+
+    /// Hatching picks one of the patterns, so a shooter without any can't be kept.
+    /// Returns whether it had to go.
+    fn drop_empty_shooter(&mut self) -> bool {
+        if self
+            .shooter
+            .as_ref()
+            .is_some_and(|shooter| shooter.patterns.is_empty())
+        {
+            self.shooter = None;
+            return true;
+        }
+        false
+    }
+
+    pub fn roll_speed(&self, rng: &mut impl Rng) -> f32 {
+        roll_range(self.speed_range, rng)
+    }
+
+    pub fn roll_mult(&self, rng: &mut impl Rng) -> i32 {
+        if self.mult_range.0 >= self.mult_range.1 {
+            return self.mult_range.0;
+        }
+        rng.gen_range(self.mult_range.0..self.mult_range.1)
+    }
+
+    pub fn roll_future(&self, rng: &mut impl Rng) -> f32 {
+        roll_range(self.future_range, rng)
+    }
+
+    pub fn roll_keep_away(&self, rng: &mut impl Rng) -> f32 {
+        roll_range(self.keep_away_range, rng)
+    }
+}
+
+fn roll_range(range: (f32, f32), rng: &mut impl Rng) -> f32 {
+    if range.0 >= range.1 {
+        return range.0;
+    }
+    rng.gen_range(range.0..range.1)
+}
+
+/// On the hurtbox of anything that counts as an enemy (bosses too). Targets, eggs and the like don't get one.
+#[derive(Component, Debug, Clone, Copy, Default, Reflect)]
+pub struct EnemyHurtbox;
+
+/// The sounds an enemy makes when it gets hurt or dies. Lives on the hurtbox.
+#[derive(Component, Debug, Clone, Default)]
+pub struct EnemySounds {
+    pub hurt: Option<SoundDef>,
+    pub death: Option<SoundDef>,
+}
+impl EnemySounds {
+    pub fn play_hurt(&self, commands: &mut Commands) {
+        if let Some(sound) = &self.hurt {
+            sound.play(commands);
+        }
+    }
+
+    pub fn play_death(&self, commands: &mut Commands) {
+        if let Some(sound) = &self.death {
+            sound.play(commands);
+        }
+    }
+}
+
+#[derive(Default)]
+struct EnemyDefLoader;
+impl AssetLoader for EnemyDefLoader {
+    type Asset = EnemyDef;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut def: EnemyDef = serde_json::from_slice(&bytes)?;
+        // Still load it, a def that fails to load never hatches and the room can't be cleared
+        if def.drop_empty_shooter() {
+            error!(
+                "{}: shooter has no patterns, it won't shoot",
+                load_context.path().display()
+            );
+        }
+        Ok(def)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["enemy.json"]
+    }
+}
+
+/// Holds on to every def so they stay loaded
+#[derive(Resource, Default)]
+pub struct EnemyDefs {
+    map: HashMap<String, Handle<EnemyDef>>,
+}
+impl EnemyDefs {
+    pub fn get<'a>(&self, name: &str, defs: &'a Assets<EnemyDef>) -> Option<&'a EnemyDef> {
+        self.map.get(name).and_then(|handle| defs.get(handle))
+    }
+}
+
+/// The code half of an enemy. Implement this on the guide component and `DataEnemyBundle<Self>` can spawn it.
+pub trait EnemyArchetype: Component + Sized {
+    /// The def lives at `assets/enemies/{DEF}.enemy.json`
+    const DEF: &'static str;
+    /// Marker for the hurtbox
+    type Hurtbox: Component + Default;
+
+    /// Makes the guide, rolling whatever random ranges it needs from the def
+    fn from_def(def: &EnemyDef, rng: &mut impl Rng) -> Self;
+
+    /// What drives the guide around, if anything
+    fn brain(&self) -> Option<AiMachine> {
+        None
+    }
+
+    /// How these flock when they come out of a spawner, if they do
+    fn flocking() -> Option<Flocking> {
+        None
+    }
+
+    /// Anything else that should go on the hurtbox
+    fn extra_hurtbox(&self, _hurtbox: &mut EntityCommands) {}
+}
+
+/// Tags every enemy of an archetype, hatched or not. This is what spawners count.
+#[derive(Component)]
+pub struct DataEnemy<A: EnemyArchetype> {
+    pd: PhantomData<A>,
+}
+
+/// Stand-in for an enemy whose def is still loading. Turns into the real thing ASAP.
+#[derive(Component)]
+pub struct Unhatched;
+
+/// The one `EnemyBundle` to rule them all
+#[derive(Bundle)]
+pub struct DataEnemyBundle<A: EnemyArchetype> {
+    name: Name,
+    data_enemy: DataEnemy<A>,
+    unhatched: Unhatched,
+    spatial: SpatialBundle,
+}
+impl<A: EnemyArchetype> EnemyBundle for DataEnemyBundle<A> {
+    type CountComponent = DataEnemy<A>;
+
+    fn spawn(pos: Vec2, commands: &mut Commands, parent: Entity) -> Entity {
+        commands
+            .spawn(Self {
+                name: Name::new(format!("{}_unhatched", A::DEF)),
+                data_enemy: DataEnemy { pd: default() },
+                unhatched: Unhatched,
+                spatial: spat_tran(pos.x, pos.y, ZIX_SIMP),
+            })
+            .set_parent(parent)
+            .id()
+    }
+
+    fn spawn_elite(
+        pos: Vec2,
+        affix: EliteAffix,
+        commands: &mut Commands,
+        parent: Entity,
+    ) -> Entity {
+        commands
+            .spawn((
+                Self {
+                    name: Name::new(format!("{}_unhatched", A::DEF)),
+                    data_enemy: DataEnemy { pd: default() },
+                    unhatched: Unhatched,
+                    spatial: spat_tran(pos.x, pos.y, ZIX_SIMP),
+                },
+                Elite(affix),
+            ))
+            .set_parent(parent)
+            .id()
+    }
+}
+
+fn hatch_enemies<A: EnemyArchetype>(
+    unhatched: Query<(Entity, &Transform, Option<&Elite>), (With<DataEnemy<A>>, With<Unhatched>)>,
+    mut bird: Query<&mut Bird>,
+    enemy_defs: Res<EnemyDefs>,
+    defs: Res<Assets<EnemyDef>>,
+    mut commands: Commands,
+    mut run_rng: ResMut<RunRng>,
+) {
+    let Some(def) = enemy_defs.get(A::DEF, &defs) else {
+        return;
+    };
+    let rng = run_rng.stream(RngStream::Enemies);
+    for (eid, tran, elite) in &unhatched {
+        let affix = elite.map(|elite| elite.0);
+        let pos = tran.translation.truncate();
+        if let Some(sound) = &def.spawn_sound {
+            sound.play(&mut commands);
+        }
+        let archetype = A::from_def(def, rng);
+        let mut multi = def.multi();
+        if let Some(affix) = affix {
+            affix.tint_multi(&mut multi);
+        }
+        let mut guide = commands.entity(eid);
+        guide.remove::<Unhatched>().insert((
+            Name::new(format!("{}_guide (body)", def.name)),
+            SimpGuidePhysicsBundle::new(pos, def.static_radius),
+            Birthing,
+        ));
+        if let Some(mut brain) = archetype.brain() {
+            if let Some(affix) = affix {
+                brain.scale_speed(affix.speed_mult());
+            }
+            guide.insert(brain);
+        }
+        if def.face_dyno {
+            guide.insert(FaceDyno);
+        }
+        if def.pathfind {
+            guide.insert(NavAgent::new(rng));
+        }
+        if def.anims_on == AnimsOn::Guide {
+            guide.insert(multi.clone());
+        }
+        if let Some(shooter) = &def.shooter {
+            let pattern = shooter.patterns[rng.gen_range(0..shooter.patterns.len())];
+            guide.insert(PatternShooter::new(
+                pattern,
+                shooter.bullet_speed,
+                roll_range(shooter.cooldown_range, rng),
+            ));
+        }
+        guide.with_children(|dad| {
+            let mut hurtbox = dad.spawn((
+                Name::new(format!("{}_hurtbox", def.name)),
+                A::Hurtbox::default(),
+                EnemyHurtbox,
+                Health::new(affix.map_or(def.health, |affix| affix.health(def.health)))
+                    .with_vulnerable_to([TriggerKind::BulletGood]),
+                def.contact_damage.to_damage(),
+                EnemySounds {
+                    hurt: def.hurt_sound.clone(),
+                    death: def.death_sound.clone(),
+                },
+                SimpHurtboxPhysicsBundle::new(def.trigger_radius),
+                LootTable(def.loot.clone()),
+                Birthing,
+            ));
+            if def.anims_on == AnimsOn::Hurtbox {
+                hurtbox.insert(multi);
+            }
+            if affix == Some(EliteAffix::Shielded) {
+                add_shield(&mut hurtbox, def.trigger_radius, rng);
+            }
+            archetype.extra_hurtbox(&mut hurtbox);
+        });
+        if affix == Some(EliteAffix::Splitting) {
+            // Count the two halves now so the room can't end before they show up
+            if let Ok(mut bird) = bird.get_single_mut() {
+                bird.add_kills(2);
+            }
+        }
+        guide.insert(archetype);
+    }
+}
+
+/// Loads the def for an archetype, puts it in the `EnemyRegistry` and starts hatching its enemies. Call once per archetype.
+pub(super) fn register_archetype<A: EnemyArchetype>(app: &mut App) {
+    app.init_resource::<EnemyRegistry>();
+    app.world_mut()
+        .resource_mut::<EnemyRegistry>()
+        .register::<DataEnemyBundle<A>>(A::DEF, A::flocking());
+    app.add_systems(
+        Startup,
+        |mut enemy_defs: ResMut<EnemyDefs>, asset_server: Res<AssetServer>| {
+            enemy_defs.map.insert(
+                A::DEF.to_string(),
+                asset_server.load(format!("enemies/{}.enemy.json", A::DEF)),
+            );
+        },
+    );
+    app.add_systems(
+        Update,
+        hatch_enemies::<A>.run_if(in_state(PhysicsState::Active)),
+    );
+    register_elite_archetype::<A>(app);
+}
+
+pub(super) fn register_defs(app: &mut App) {
+    app.init_asset::<EnemyDef>();
+    app.init_asset_loader::<EnemyDefLoader>();
+    app.insert_resource(EnemyDefs::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPIT: &str = include_str!("../../assets/enemies/spit.enemy.json");
+
+    fn spit() -> EnemyDef {
+        serde_json::from_str(SPIT).unwrap()
+    }
+
+    #[test]
+    fn shipped_defs_load_as_is() {
+        for json in [
+            include_str!("../../assets/enemies/simp.enemy.json"),
+            include_str!("../../assets/enemies/spew.enemy.json"),
+            SPIT,
+        ] {
+            let mut def: EnemyDef = serde_json::from_str(json).unwrap();
+            assert!(!def.drop_empty_shooter(), "{} lost its shooter", def.name);
+        }
+        assert!(spit().shooter.is_some());
+    }
+
+    #[test]
+    fn empty_patterns_lose_the_shooter() {
+        let mut def = spit();
+        def.shooter.as_mut().unwrap().patterns.clear();
+        assert!(def.drop_empty_shooter());
+        assert!(def.shooter.is_none());
+        // Nothing left to drop the second time
+        assert!(!def.drop_empty_shooter());
+    }
+
+    #[test]
+    fn no_shooter_is_fine() {
+        let mut def = spit();
+        def.shooter = None;
+        assert!(!def.drop_empty_shooter());
+        assert!(def.shooter.is_none());
+    }
+
+    #[test]
+    fn anim_finds_by_key() {
+        let def = spit();
+        assert!(def.anim("core").is_some());
+        assert!(def.anim("nope").is_none());
+    }
+}
@@ -0,0 +1,202 @@
+use std::f32::consts::PI;
+
+use bevy::ecs::system::EntityCommands;
+
+use crate::prelude::*;
+
+/// A twist on a normal enemy. Rolled by spawners, more likely the deeper into the run you are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum EliteAffix {
+    /// Twice the health (and then some)
+    Armored,
+    /// Moves a lot faster
+    Swift,
+    /// Turns into two normal ones when it dies
+    Splitting,
+    /// Can't be hurt from one (slowly rotating) side
+    Shielded,
+    /// Goes off like a bullet bomb when it dies
+    Exploding,
+}
+impl EliteAffix {
+    pub const ALL: [Self; 5] = [
+        Self::Armored,
+        Self::Swift,
+        Self::Splitting,
+        Self::Shielded,
+        Self::Exploding,
+    ];
+
+    /// Chance any given spawn is an elite
+    pub fn chance(difficulty: u32) -> f32 {
+        (0.05 * difficulty as f32).min(0.4)
+    }
+
+    pub fn roll(difficulty: u32, rng: &mut impl Rng) -> Option<Self> {
+        if rng.gen::<f32>() >= Self::chance(difficulty) {
+            return None;
+        }
+        Some(Self::ALL[rng.gen_range(0..Self::ALL.len())])
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            Self::Armored => tailwind::SLATE_400.into(),
+            Self::Swift => tailwind::SKY_300.into(),
+            Self::Splitting => tailwind::LIME_300.into(),
+            Self::Shielded => tailwind::YELLOW_200.into(),
+            Self::Exploding => tailwind::ORANGE_400.into(),
+        }
+    }
+
+    /// Tints the "core" animation, which every enemy has
+    pub fn tint_multi(&self, multi: &mut MultiAnimationManager) {
+        let tint = self.tint();
+        for node in multi.manager_mut("core").map.values_mut() {
+            node.sprite.color = tint;
+        }
+    }
+
+    pub fn health(&self, base: u32) -> u32 {
+        match self {
+            Self::Armored => base * 2 + 1,
+            _ => base,
+        }
+    }
+
+    pub fn speed_mult(&self) -> f32 {
+        match self {
+            Self::Swift => 1.7,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Lives on the guide of elite enemies
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Elite(pub EliteAffix);
+
+/// The visible half of a `DamageShield`. Child of the hurtbox.
+#[derive(Component)]
+struct ShieldVisual;
+
+const SHIELD_HALF_ANGLE: f32 = PI / 3.0;
+const SHIELD_TURN_SPEED: f32 = 0.6;
+
+/// Puts a shield (and something to see it by) on a hurtbox
+pub(super) fn add_shield(hurtbox: &mut EntityCommands, radius: f32, rng: &mut impl Rng) {
+    let dir = Vec2::X.my_rotate(rng.gen_range(0.0..2.0 * PI));
+    hurtbox.insert(DamageShield::new(dir, SHIELD_HALF_ANGLE));
+    // Thin arc pointing along +x, rotated into place by `turn_shields`
+    let num_steps = 8;
+    let angles = (0..=num_steps)
+        .map(|ix| -SHIELD_HALF_ANGLE + 2.0 * SHIELD_HALF_ANGLE * ix as f32 / num_steps as f32)
+        .collect::<Vec<_>>();
+    let mut points = angles
+        .iter()
+        .map(|angle| Vec2::X.my_rotate(*angle) * (radius + 3.0))
+        .collect::<Vec<_>>();
+    points.extend(
+        angles
+            .iter()
+            .rev()
+            .map(|angle| Vec2::X.my_rotate(*angle) * (radius + 1.0)),
+    );
+    hurtbox.with_children(|dad| {
+        dad.spawn((
+            Name::new("shield"),
+            ShieldVisual,
+            multi!(anim_man!({
+                path: "sprites/default.png",
+                size: (1, 1),
+                color: EliteAffix::Shielded.tint(),
+            })
+            .with_points(points)),
+            spat_tran(0.0, 0.0, 0.5),
+        ));
+    });
+}
+
+fn turn_shields(
+    mut shields: Query<(&mut DamageShield, &GlobalTransform, &Children)>,
+    mut visuals: Query<&mut Transform, With<ShieldVisual>>,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (mut shield, gtran, children) in &mut shields {
+        shield.dir = shield.dir.my_rotate(SHIELD_TURN_SPEED * time_factor);
+        let (_, parent_angle) = gtran.tran_n_angle();
+        for child in children {
+            if let Ok(mut tran) = visuals.get_mut(*child) {
+                tran.set_angle(shield.dir.to_angle() - parent_angle);
+            }
+        }
+    }
+}
+
+/// Splitting and exploding both happen when the elite dies. The bird's kill count already knows about the splits.
+fn elite_deaths<A: EnemyArchetype>(
+    mut damage_taken: EventReader<DamageTaken>,
+    hurtboxes: Query<&Parent, With<A::Hurtbox>>,
+    elites: Query<(&Elite, &GlobalTransform), With<DataEnemy<A>>>,
+    mut commands: Commands,
+    meta_state: Res<State<MetaState>>,
+    room_root: Res<RoomRoot>,
+    tutorial_root: Res<TutorialRoot>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    let parent_eid = if meta_state.get_tutorial_state().is_some() {
+        tutorial_root.eid()
+    } else {
+        room_root.eid()
+    };
+    for event in damage_taken.read() {
+        if !event.killed {
+            continue;
+        }
+        let Ok(parent) = hurtboxes.get(event.target) else {
+            continue;
+        };
+        let Ok((elite, gtran)) = elites.get(parent.get()) else {
+            continue;
+        };
+        let pos = gtran.translation().truncate();
+        match elite.0 {
+            EliteAffix::Splitting => {
+                let offset =
+                    Vec2::X.my_rotate(run_rng.stream(RngStream::Enemies).gen_range(0.0..PI)) * 10.0;
+                DataEnemyBundle::<A>::spawn(pos + offset, &mut commands, parent_eid);
+                DataEnemyBundle::<A>::spawn(pos - offset, &mut commands, parent_eid);
+            }
+            EliteAffix::Exploding => {
+                let pattern = BulletPattern::Radial { count: 10 };
+                for dir in pattern.directions(Vec2::X, 0) {
+                    BadBulletBundle::spawn(pos, dir * 50.0, &mut commands, parent_eid);
+                }
+                commands.spawn(SoundEffect::universal("sound_effects/lenny_fire.ogg", 0.1));
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(super) fn register_elite_archetype<A: EnemyArchetype>(app: &mut App) {
+    app.add_systems(
+        Update,
+        elite_deaths::<A>
+            .run_if(in_state(PhysicsState::Active))
+            .after(DamageSet),
+    );
+}
+
+pub(super) fn register_elites(app: &mut App) {
+    app.register_type::<Elite>();
+
+    app.add_systems(
+        Update,
+        turn_shields
+            .run_if(in_state(PhysicsState::Active))
+            .before(DamageSet),
+    );
+}
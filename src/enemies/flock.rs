@@ -0,0 +1,108 @@
+use crate::prelude::*;
+
+use super::ai::steer_ai;
+
+/// Boids-style steering on top of whatever the `AiMachine` is doing.
+/// Everything with this flocks with everything else that has it.
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Flocking {
+    /// How far away a neighbour can be and still count
+    pub radius: f32,
+    /// Push away from neighbours that are too close
+    pub separation: f32,
+    /// Match neighbours' velocity
+    pub alignment: f32,
+    /// Pull towards the middle of the neighbours
+    pub cohesion: f32,
+}
+impl Flocking {
+    pub fn new(radius: f32, separation: f32, alignment: f32, cohesion: f32) -> Self {
+        Self {
+            radius,
+            separation,
+            alignment,
+            cohesion,
+        }
+    }
+
+    /// A nice loose murmuration
+    pub fn starlings() -> Self {
+        Self::new(28.0, 1600.0, 1.2, 0.8)
+    }
+}
+
+fn flock(
+    mut flockers: Query<
+        (
+            Entity,
+            &Flocking,
+            &AiMachine,
+            &mut DynoTran,
+            &GlobalTransform,
+        ),
+        (
+            Without<Birthing>,
+            Without<Dying>,
+            Without<Dead>,
+            Without<Stunned>,
+        ),
+    >,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let max_radius = flockers
+        .iter()
+        .map(|(_, flocking, ..)| flocking.radius)
+        .fold(0.0, f32::max);
+    if max_radius <= 0.0 {
+        return;
+    }
+    let mut grid = SpatialHash::new(max_radius);
+    let mut vels = HashMap::new();
+    for (eid, _, _, dyno_tran, gtran) in &flockers {
+        grid.insert(eid, gtran.translation().truncate());
+        vels.insert(eid, dyno_tran.vel);
+    }
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (eid, flocking, machine, mut dyno_tran, gtran) in &mut flockers {
+        let my_pos = gtran.translation().truncate();
+        let mut separation = Vec2::ZERO;
+        let mut avg_vel = Vec2::ZERO;
+        let mut avg_pos = Vec2::ZERO;
+        let mut count = 0;
+        for (other_eid, other_pos) in grid.within(my_pos, flocking.radius) {
+            if other_eid == eid {
+                continue;
+            }
+            let away = my_pos - other_pos;
+            separation += away / away.length_squared().max(1.0);
+            avg_vel += vels[&other_eid];
+            avg_pos += other_pos;
+            count += 1;
+        }
+        if count == 0 {
+            continue;
+        }
+        avg_vel /= count as f32;
+        avg_pos /= count as f32;
+        let steer = separation * flocking.separation
+            + (avg_vel - dyno_tran.vel) * flocking.alignment
+            + (avg_pos - my_pos) * flocking.cohesion;
+        dyno_tran.vel += steer * time_factor;
+        if let Some(max_speed) = machine.max_speed() {
+            dyno_tran.vel = dyno_tran.vel.clamp_length(0.0, max_speed);
+        }
+    }
+}
+
+pub(super) fn register_flocking(app: &mut App) {
+    app.register_type::<Flocking>();
+
+    app.add_systems(
+        Update,
+        flock
+            .in_set(AiSet)
+            .after(steer_ai)
+            .run_if(in_state(PhysicsState::Active)),
+    );
+}
@@ -1,26 +1,104 @@
+use std::time::Duration;
+
 use crate::prelude::*;
 
+pub mod ai;
+pub mod boss;
+pub mod defs;
+pub mod elite;
+pub mod flock;
+pub mod nav;
+pub mod projectiles;
 pub mod simp;
 pub mod spawner;
 pub mod spew;
+pub mod spit;
+pub mod waves;
 
+pub use ai::*;
+pub use boss::*;
+pub use defs::*;
+pub use elite::*;
+pub use flock::*;
+pub use nav::*;
+pub use projectiles::*;
 pub use simp::*;
 pub use spawner::*;
 pub use spew::*;
+pub use spit::*;
+pub use waves::*;
 
 pub trait EnemyBundle: Bundle {
     /// A queryable component to tell us how many of these enemies are alive
-    /// Useful so we can force a spawner to start spawning if all enemies of a given type are dead
     type CountComponent: Component;
 
-    fn spawn(pos: Vec2, commands: &mut Commands, parent: Entity);
+    fn spawn(pos: Vec2, commands: &mut Commands, parent: Entity) -> Entity;
+
+    /// Same as `spawn` but with an elite affix. Enemies that don't support elites just spawn normally.
+    fn spawn_elite(
+        pos: Vec2,
+        _affix: EliteAffix,
+        commands: &mut Commands,
+        parent: Entity,
+    ) -> Entity {
+        Self::spawn(pos, commands, parent)
+    }
+}
+
+/// Enemies with this don't steer themselves until the timer runs out. Put it on the guide.
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Stunned {
+    pub timer: Timer,
+}
+impl Stunned {
+    pub fn new(secs: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+        }
+    }
+}
+
+fn update_stunned(
+    mut stunned: Query<(Entity, &mut Stunned, Option<&mut DynoTran>)>,
+    mut commands: Commands,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (eid, mut stunned, dyno_tran) in &mut stunned {
+        stunned.timer.tick(Duration::from_secs_f32(time_factor));
+        if let Some(mut dyno_tran) = dyno_tran {
+            // Drift to a stop so knockback doesn't send them across the room
+            dyno_tran.vel *= (1.0 - 3.0 * time_factor).max(0.0);
+        }
+        if stunned.timer.finished() {
+            commands.entity(eid).remove::<Stunned>();
+        }
+    }
 }
 
 pub(super) struct EnemiesPlugin;
 impl Plugin for EnemiesPlugin {
     fn build(&self, app: &mut App) {
+        ai::register_ai(app);
+        boss::register_bosses(app);
+        defs::register_defs(app);
+        elite::register_elites(app);
+        flock::register_flocking(app);
+        nav::register_nav(app);
+        projectiles::register_projectiles(app);
         simp::register_simps(app);
         spawner::register_spawners(app);
         spew::register_spews(app);
+        spit::register_spits(app);
+        waves::register_waves(app);
+
+        app.register_type::<Stunned>();
+        app.add_systems(
+            Update,
+            update_stunned
+                .run_if(in_state(PhysicsState::Active))
+                .after(PhysicsSet),
+        );
     }
 }
@@ -0,0 +1,307 @@
+//! Pathfinding for enemies. The room gets chopped into a grid, any cell too close to a static is blocked,
+//! and agents run A* on it towards the bird. The grid gets rebuilt every so often since wheels spin.
+
+use std::{cmp::Ordering, collections::BinaryHeap, time::Duration};
+
+use crate::prelude::*;
+
+use super::ai::steer_ai;
+
+/// Which cells of the room can be walked (flown?) through
+#[derive(Resource, Debug, Clone)]
+pub struct NavGrid {
+    cell_size: f32,
+    /// World position of the bottom left corner of cell (0, 0)
+    origin: Vec2,
+    dims: UVec2,
+    blocked: Vec<bool>,
+    rebuild: Timer,
+}
+impl NavGrid {
+    const CELL_SIZE: f32 = 8.0;
+    /// How far a cell's center has to be from any static to be open
+    const CLEARANCE: f32 = 7.0;
+    const REBUILD_EVERY: f32 = 0.3;
+
+    pub fn new(center: Vec2, size: Vec2) -> Self {
+        let dims = (size / Self::CELL_SIZE).ceil().as_uvec2();
+        Self {
+            cell_size: Self::CELL_SIZE,
+            origin: center - size / 2.0,
+            dims,
+            blocked: vec![false; (dims.x * dims.y) as usize],
+            rebuild: Timer::from_seconds(Self::REBUILD_EVERY, TimerMode::Repeating),
+        }
+    }
+
+    fn ix(&self, cell: IVec2) -> Option<usize> {
+        if cell.x < 0 || cell.y < 0 || cell.x >= self.dims.x as i32 || cell.y >= self.dims.y as i32
+        {
+            return None;
+        }
+        Some((cell.y as u32 * self.dims.x + cell.x as u32) as usize)
+    }
+
+    pub fn cell_of(&self, pos: Vec2) -> IVec2 {
+        ((pos - self.origin) / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn center_of(&self, cell: IVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + Vec2::splat(0.5)) * self.cell_size
+    }
+
+    /// Off the grid counts as blocked
+    pub fn is_blocked(&self, cell: IVec2) -> bool {
+        self.ix(cell).map_or(true, |ix| self.blocked[ix])
+    }
+
+    /// Whether a straight line between the two points stays in open cells
+    pub fn line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        let steps = (from.distance(to) / (self.cell_size / 2.0)).ceil().max(1.0) as u32;
+        (0..=steps).all(|ix| {
+            let pos = from.lerp(to, ix as f32 / steps as f32);
+            !self.is_blocked(self.cell_of(pos))
+        })
+    }
+
+    fn rebuild(&mut self, statics: &[(Shape, (Vec2, f32))]) {
+        for y in 0..self.dims.y as i32 {
+            for x in 0..self.dims.x as i32 {
+                let cell = IVec2::new(x, y);
+                let center = self.center_of(cell);
+                let blocked = statics.iter().any(|(shape, placement)| {
+                    shape.closest_point(*placement, center).0 < Self::CLEARANCE
+                });
+                let ix = self.ix(cell).unwrap();
+                self.blocked[ix] = blocked;
+            }
+        }
+    }
+
+    /// A* from `from` to `to`. The start and end cells are always allowed, since things can be stuck to
+    /// (or squished against) platforms. Returns the waypoints to follow, not including `from`.
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let start = self.cell_of(from);
+        let goal = self.cell_of(to);
+        self.ix(start)?;
+        self.ix(goal)?;
+        let passable = |cell: IVec2| cell == start || cell == goal || !self.is_blocked(cell);
+        let heuristic = |cell: IVec2| (goal - cell).as_vec2().length();
+
+        let mut open = BinaryHeap::new();
+        let mut came_from = HashMap::<IVec2, IVec2>::new();
+        let mut cost_so_far = HashMap::<IVec2, f32>::new();
+        open.push(OpenCell {
+            cell: start,
+            priority: heuristic(start),
+        });
+        cost_so_far.insert(start, 0.0);
+        while let Some(OpenCell { cell, .. }) = open.pop() {
+            if cell == goal {
+                break;
+            }
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let next = cell + IVec2::new(dx, dy);
+                    if !passable(next) {
+                        continue;
+                    }
+                    // No cutting corners
+                    if dx != 0
+                        && dy != 0
+                        && (!passable(cell + IVec2::new(dx, 0))
+                            || !passable(cell + IVec2::new(0, dy)))
+                    {
+                        continue;
+                    }
+                    let step = if dx != 0 && dy != 0 {
+                        std::f32::consts::SQRT_2
+                    } else {
+                        1.0
+                    };
+                    let new_cost = cost_so_far[&cell] + step;
+                    if cost_so_far
+                        .get(&next)
+                        .map_or(true, |old_cost| new_cost < *old_cost)
+                    {
+                        cost_so_far.insert(next, new_cost);
+                        came_from.insert(next, cell);
+                        open.push(OpenCell {
+                            cell: next,
+                            priority: new_cost + heuristic(next),
+                        });
+                    }
+                }
+            }
+        }
+        if start != goal && !came_from.contains_key(&goal) {
+            return None;
+        }
+
+        let mut cells = vec![goal];
+        while let Some(prev) = came_from.get(cells.last().unwrap()) {
+            if *prev == start {
+                break;
+            }
+            cells.push(*prev);
+        }
+        cells.reverse();
+        let mut path = cells
+            .into_iter()
+            .map(|cell| self.center_of(cell))
+            .collect::<Vec<_>>();
+        if let Some(last) = path.last_mut() {
+            *last = to;
+        }
+        Some(self.smooth(from, path))
+    }
+
+    /// Drops every waypoint that can be skipped with a straight line
+    fn smooth(&self, from: Vec2, path: Vec<Vec2>) -> Vec<Vec2> {
+        let mut result = vec![];
+        let mut current = from;
+        let mut ix = 0;
+        while ix < path.len() {
+            let mut furthest = ix;
+            for test in (ix..path.len()).rev() {
+                if self.line_of_sight(current, path[test]) {
+                    furthest = test;
+                    break;
+                }
+            }
+            result.push(path[furthest]);
+            current = path[furthest];
+            ix = furthest + 1;
+        }
+        result
+    }
+}
+impl Default for NavGrid {
+    fn default() -> Self {
+        Self::new(Vec2::ZERO, IDEAL_VEC_f32)
+    }
+}
+
+#[derive(PartialEq)]
+struct OpenCell {
+    cell: IVec2,
+    priority: f32,
+}
+impl Eq for OpenCell {}
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Flipped so the heap pops the cheapest
+        other
+            .priority
+            .partial_cmp(&self.priority)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Put this on a guide and its `Seek` will follow a path around statics instead of beelining
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct NavAgent {
+    path: Vec<Vec2>,
+    replan: Timer,
+}
+impl NavAgent {
+    const REPLAN_EVERY: f32 = 0.5;
+    /// How close to a waypoint counts as reaching it
+    const REACHED: f32 = 6.0;
+
+    pub fn new(rng: &mut impl Rng) -> Self {
+        // Stagger replanning so a whole batch doesn't A* on the same frame
+        let mut replan = Timer::from_seconds(Self::REPLAN_EVERY, TimerMode::Repeating);
+        replan.set_elapsed(Duration::from_secs_f32(
+            rng.gen_range(0.0..Self::REPLAN_EVERY),
+        ));
+        Self {
+            path: vec![],
+            replan,
+        }
+    }
+
+    /// Where to head next, if the bird isn't in plain sight
+    pub fn next_waypoint(&self) -> Option<Vec2> {
+        self.path.first().copied()
+    }
+}
+
+fn rebuild_nav_grid(
+    mut grid: ResMut<NavGrid>,
+    statics: Query<(&Bounds, &GlobalTransform), With<StaticProvider>>,
+    new_statics: Query<(), Added<StaticProvider>>,
+    room_bounds: Res<RoomBounds>,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let resized = room_bounds.is_changed();
+    if resized {
+        // Bigger rooms need a bigger grid
+        *grid = NavGrid::new(Vec2::ZERO, room_bounds.size);
+    }
+    grid.rebuild
+        .tick(time.delta().mul_f32(bullet_time.factor()));
+    if !grid.rebuild.just_finished() && new_statics.is_empty() && !resized {
+        return;
+    }
+    let statics = statics
+        .iter()
+        .map(|(bounds, gtran)| (bounds.get_shape().clone(), gtran.tran_n_angle()))
+        .collect::<Vec<_>>();
+    grid.rebuild(&statics);
+}
+
+fn plan_paths(
+    grid: Res<NavGrid>,
+    bird: Query<&GlobalTransform, With<Bird>>,
+    mut agents: Query<(&mut NavAgent, &GlobalTransform), Without<Bird>>,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let Ok(bird_gtran) = bird.get_single() else {
+        return;
+    };
+    let bird_pos = bird_gtran.translation().truncate();
+    let time_delta = time.delta().mul_f32(bullet_time.factor());
+    for (mut agent, gtran) in &mut agents {
+        let my_pos = gtran.translation().truncate();
+        agent.replan.tick(time_delta);
+        if grid.line_of_sight(my_pos, bird_pos) {
+            agent.path.clear();
+            continue;
+        }
+        if agent.replan.just_finished() {
+            agent.path = grid.find_path(my_pos, bird_pos).unwrap_or_default();
+        }
+        while agent
+            .path
+            .first()
+            .is_some_and(|waypoint| waypoint.distance(my_pos) < NavAgent::REACHED)
+        {
+            agent.path.remove(0);
+        }
+    }
+}
+
+pub(super) fn register_nav(app: &mut App) {
+    app.register_type::<NavAgent>();
+    app.insert_resource(NavGrid::default());
+
+    app.add_systems(
+        Update,
+        (rebuild_nav_grid, plan_paths)
+            .chain()
+            .in_set(AiSet)
+            .before(steer_ai)
+            .run_if(in_state(PhysicsState::Active)),
+    );
+}
@@ -0,0 +1,248 @@
+use std::{f32::consts::PI, time::Duration};
+
+use crate::prelude::*;
+
+/// Marks bullets fired by enemies (even after they get reflected and turn good)
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct BadBullet;
+
+#[derive(Bundle)]
+pub struct BadBulletBundle {
+    name: Name,
+    any_bullet: AnyBullet,
+    bad_bullet: BadBullet,
+    damage: Damage,
+    physics: BulletPhysicsBundle,
+    multi: MultiAnimationManager,
+}
+impl BadBulletBundle {
+    pub fn new(pos: Vec2, vel: Vec2) -> Self {
+        Self {
+            name: Name::new("bad_bullet"),
+            any_bullet: AnyBullet,
+            bad_bullet: BadBullet,
+            damage: Damage::new(1)
+                .with_knockback(80.0)
+                .with_hit_stop(0.06)
+                .with_shake(0.35)
+                .once_per_touch(),
+            physics: BulletPhysicsBundle::new(pos, vel, false),
+            multi: multi!([
+                (
+                    "core",
+                    anim_man!({
+                        solid: {
+                            path: "bullets/good.png",
+                            size: (5, 5),
+                            color: tailwind::RED_400.into(),
+                        },
+                        explode: {
+                            path: "bullets/good_explode.png",
+                            size: (7, 7),
+                            length: 2,
+                            next: "despawn",
+                            color: tailwind::RED_400.into(),
+                        },
+                    }),
+                ),
+                (
+                    "light",
+                    anim_man!({
+                        path: "bullets/good_light.png",
+                        size: (12, 12),
+                        color: tailwind::RED_300.into(),
+                    })
+                    .with_render_layers(LightCamera::render_layers()),
+                )
+            ]),
+        }
+    }
+
+    /// Enemy bullets fly straight, it's way easier to dodge things you can read
+    pub fn spawn(pos: Vec2, vel: Vec2, commands: &mut Commands, parent: Entity) {
+        commands
+            .spawn(Self::new(pos, vel))
+            .remove::<Gravity>()
+            .set_parent(parent);
+    }
+}
+
+/// The bullet-hell pattern library. Each attack is a few shots, each shot is a handful of bullets.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum BulletPattern {
+    /// A fan of bullets pointed at the bird
+    Aimed { count: u32, spread: f32 },
+    /// A ring of bullets in every direction
+    Radial { count: u32 },
+    /// A few arms of bullets that rotate a little every shot
+    Spiral { arms: u32, turn: f32 },
+}
+impl BulletPattern {
+    /// How many shots make up one attack
+    pub fn shots_per_attack(&self) -> u32 {
+        match self {
+            Self::Aimed { .. } => 2,
+            Self::Radial { .. } => 3,
+            Self::Spiral { .. } => 16,
+        }
+    }
+
+    /// Seconds between shots in the same attack
+    pub fn shot_interval(&self) -> f32 {
+        match self {
+            Self::Aimed { .. } => 0.25,
+            Self::Radial { .. } => 0.4,
+            Self::Spiral { .. } => 0.08,
+        }
+    }
+
+    /// The directions (unit vectors) of every bullet in the `shot_ix`th shot of an attack
+    pub fn directions(&self, aim: Vec2, shot_ix: u32) -> Vec<Vec2> {
+        let aim = aim.normalize_or(Vec2::X);
+        match *self {
+            Self::Aimed { count, spread } => (0..count)
+                .map(|ix| {
+                    let frac = if count > 1 {
+                        ix as f32 / (count - 1) as f32 - 0.5
+                    } else {
+                        0.0
+                    };
+                    aim.my_rotate(frac * spread)
+                })
+                .collect(),
+            Self::Radial { count } => {
+                // Offset every other ring so there's always a gap to slip through
+                let offset = if shot_ix % 2 == 0 {
+                    0.0
+                } else {
+                    PI / count as f32
+                };
+                (0..count)
+                    .map(|ix| aim.my_rotate(offset + 2.0 * PI * ix as f32 / count as f32))
+                    .collect()
+            }
+            Self::Spiral { arms, turn } => (0..arms)
+                .map(|ix| aim.my_rotate(turn * shot_ix as f32 + 2.0 * PI * ix as f32 / arms as f32))
+                .collect(),
+        }
+    }
+}
+
+/// Put this on anything that should shoot at the bird. Fires `pattern` every `cooldown` seconds.
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct PatternShooter {
+    pub pattern: BulletPattern,
+    pub speed: f32,
+    cooldown: Timer,
+    shot_timer: Timer,
+    shots_left: u32,
+    shot_ix: u32,
+    /// Set by `fire_now`, starts an attack without waiting for the cooldown
+    fire_requested: bool,
+    /// Where the current attack is aimed. Locked in when the attack starts so spirals look right.
+    aim: Vec2,
+}
+impl PatternShooter {
+    pub fn new(pattern: BulletPattern, speed: f32, cooldown: f32) -> Self {
+        Self {
+            pattern,
+            speed,
+            cooldown: Timer::from_seconds(cooldown, TimerMode::Repeating),
+            shot_timer: Timer::from_seconds(0.0, TimerMode::Once),
+            shots_left: 0,
+            shot_ix: 0,
+            fire_requested: false,
+            aim: Vec2::X,
+        }
+    }
+
+    pub fn is_attacking(&self) -> bool {
+        self.shots_left > 0
+    }
+
+    /// Start an attack next frame, cooldown or not. Does nothing mid-attack.
+    pub fn fire_now(&mut self) {
+        if !self.is_attacking() {
+            self.fire_requested = true;
+        }
+    }
+
+    pub fn wants_to_fire(&self) -> bool {
+        self.fire_requested
+    }
+}
+
+fn shoot_patterns(
+    mut shooters: Query<
+        (&mut PatternShooter, &GlobalTransform),
+        (
+            Without<Birthing>,
+            Without<Dying>,
+            Without<Dead>,
+            Without<Stunned>,
+        ),
+    >,
+    bird: Query<&GlobalTransform, With<Bird>>,
+    mut commands: Commands,
+    meta_state: Res<State<MetaState>>,
+    room_root: Res<RoomRoot>,
+    tutorial_root: Res<TutorialRoot>,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let Ok(bird_gtran) = bird.get_single() else {
+        return;
+    };
+    let parent_eid = if meta_state.get_tutorial_state().is_some() {
+        tutorial_root.eid()
+    } else {
+        room_root.eid()
+    };
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    let bird_pos = bird_gtran.translation().truncate();
+    for (mut shooter, gtran) in &mut shooters {
+        let my_pos = gtran.translation().truncate();
+        if !shooter.is_attacking() {
+            shooter.cooldown.tick(Duration::from_secs_f32(time_factor));
+            if !shooter.cooldown.just_finished() && !shooter.fire_requested {
+                continue;
+            }
+            shooter.fire_requested = false;
+            shooter.shots_left = shooter.pattern.shots_per_attack();
+            shooter.shot_ix = 0;
+            shooter.aim = bird_pos - my_pos;
+            // Fire the first shot right away
+            shooter.shot_timer = Timer::from_seconds(0.0, TimerMode::Once);
+        }
+        shooter
+            .shot_timer
+            .tick(Duration::from_secs_f32(time_factor));
+        if !shooter.shot_timer.finished() {
+            continue;
+        }
+        let interval = shooter.pattern.shot_interval();
+        shooter.shot_timer = Timer::from_seconds(interval, TimerMode::Once);
+        // Aimed shots should keep tracking, everything else stays put
+        if matches!(shooter.pattern, BulletPattern::Aimed { .. }) {
+            shooter.aim = bird_pos - my_pos;
+        }
+        for dir in shooter.pattern.directions(shooter.aim, shooter.shot_ix) {
+            BadBulletBundle::spawn(my_pos, dir * shooter.speed, &mut commands, parent_eid);
+        }
+        shooter.shot_ix += 1;
+        shooter.shots_left -= 1;
+        commands.spawn(SoundEffect::universal("sound_effects/lenny_fire.ogg", 0.03));
+    }
+}
+
+pub(super) fn register_projectiles(app: &mut App) {
+    app.register_type::<BadBullet>();
+    app.register_type::<PatternShooter>();
+
+    app.add_systems(
+        Update,
+        shoot_patterns
+            .run_if(in_state(PhysicsState::Active))
+            .after(PhysicsSet),
+    );
+}
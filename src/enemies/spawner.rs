@@ -1,24 +1,155 @@
-use std::{marker::PhantomData, time::Duration};
+use std::{f32::consts::PI, time::Duration};
 
 use crate::prelude::*;
 
+/// Spawns an enemy without knowing (at compile time) what kind it is
+pub type SpawnFn = fn(Vec2, Option<EliteAffix>, &mut Commands, Entity) -> Entity;
+
+/// The `SpawnFn` for any `EnemyBundle`
+pub fn spawn_fn<B: EnemyBundle>() -> SpawnFn {
+    |pos, affix, commands, parent| match affix {
+        Some(affix) => B::spawn_elite(pos, affix, commands, parent),
+        None => B::spawn(pos, commands, parent),
+    }
+}
+
+/// Everything needed to spawn one kind of enemy, without knowing its type
+#[derive(Clone, Debug)]
+pub struct EnemyKind {
+    pub spawn: SpawnFn,
+    /// Goes on everything of this kind that a spawner or script makes
+    pub flocking: Option<Flocking>,
+}
+
+/// Every kind of enemy that spawners and wave scripts can ask for, by name.
+/// Archetypes register themselves under their `DEF`.
+#[derive(Resource, Default)]
+pub struct EnemyRegistry {
+    map: HashMap<String, EnemyKind>,
+}
+impl EnemyRegistry {
+    pub fn register<B: EnemyBundle>(&mut self, name: &str, flocking: Option<Flocking>) {
+        self.map.insert(
+            name.to_string(),
+            EnemyKind {
+                spawn: spawn_fn::<B>(),
+                flocking,
+            },
+        );
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EnemyKind> {
+        self.map.get(name)
+    }
+}
+
+/// Put on everything a spawner or script makes, pointing back at it
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SpawnedBy(pub Entity);
+
+/// A pulsing marker where an enemy is about to show up. Spawns the enemy when the timer runs out.
+#[derive(Component)]
+pub struct SpawnTelegraph {
+    timer: Timer,
+    kind: EnemyKind,
+    affix: Option<EliteAffix>,
+    /// Whatever asked for this spawn, so spawners know not to start the next batch early
+    pub source: Entity,
+}
+
+#[derive(Bundle)]
+pub struct SpawnTelegraphBundle {
+    name: Name,
+    telegraph: SpawnTelegraph,
+    multi: MultiAnimationManager,
+    spatial: SpatialBundle,
+}
+impl SpawnTelegraphBundle {
+    const RADIUS: f32 = 6.0;
+
+    pub fn new(
+        pos: Vec2,
+        lead_time: f32,
+        kind: EnemyKind,
+        affix: Option<EliteAffix>,
+        source: Entity,
+    ) -> Self {
+        let num_steps = 12;
+        let mut points = (0..=num_steps)
+            .map(|ix| Vec2::X.my_rotate(2.0 * PI * ix as f32 / num_steps as f32) * Self::RADIUS)
+            .collect::<Vec<_>>();
+        points.extend((0..=num_steps).rev().map(|ix| {
+            Vec2::X.my_rotate(2.0 * PI * ix as f32 / num_steps as f32) * (Self::RADIUS - 1.0)
+        }));
+        let color: Color = affix.map_or(tailwind::RED_400.into(), |affix| affix.tint());
+        Self {
+            name: Name::new("spawn_telegraph"),
+            telegraph: SpawnTelegraph {
+                timer: Timer::from_seconds(lead_time, TimerMode::Once),
+                kind,
+                affix,
+                source,
+            },
+            multi: multi!(anim_man!({
+                path: "sprites/default.png",
+                size: (1, 1),
+                color: color,
+            })
+            .with_points(points)),
+            spatial: spat_tran(pos.x, pos.y, ZIX_TELEGRAPH),
+        }
+    }
+}
+
+fn update_telegraphs(
+    mut telegraphs: Query<(Entity, &mut SpawnTelegraph, &mut Transform, &Parent)>,
+    mut commands: Commands,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (eid, mut telegraph, mut tran, parent) in &mut telegraphs {
+        telegraph.timer.tick(Duration::from_secs_f32(time_factor));
+        if telegraph.timer.finished() {
+            let pos = tran.translation.truncate();
+            let enemy_eid =
+                (telegraph.kind.spawn)(pos, telegraph.affix, &mut commands, parent.get());
+            commands
+                .entity(enemy_eid)
+                .insert(SpawnedBy(telegraph.source));
+            if let Some(flocking) = &telegraph.kind.flocking {
+                commands.entity(enemy_eid).insert(flocking.clone());
+            }
+            commands.entity(eid).despawn_recursive();
+            continue;
+        }
+        // Pulse faster the closer it gets
+        let frac = telegraph.timer.fraction();
+        let pulse = (frac * frac * 6.0 * PI).sin().abs();
+        tran.scale = Vec3::splat(0.6 + 0.6 * pulse);
+    }
+}
+
 #[derive(Component, Clone, Debug)]
 enum SpawnerState {
-    MidBatch { num_left: u32, timer: Timer },
+    /// Hasn't rolled its first wait yet, that needs the run's rng
+    Starting,
+    MidBatch {
+        num_left: u32,
+        timer: Timer,
+    },
     BetweenBatches(Timer),
     Done,
 }
 impl SpawnerState {
-    fn initial() -> Self {
-        let mut rng = thread_rng();
+    fn initial(rng: &mut impl Rng) -> Self {
         Self::BetweenBatches(Timer::from_seconds(
             rng.gen_range(0.5..2.0),
             TimerMode::Once,
         ))
     }
 
-    fn new_mid<B: EnemyBundle>(data: &mut EnemySpawner<B>) -> Self {
-        let mut rng = thread_rng();
+    fn new_mid(data: &mut EnemySpawner, rng: &mut impl Rng) -> Self {
         Self::MidBatch {
             num_left: data.batch_sizes.pop().unwrap_or(0) as u32,
             timer: Timer::from_seconds(
@@ -28,8 +159,7 @@ impl SpawnerState {
         }
     }
 
-    fn new_between<B: EnemyBundle>(data: &mut EnemySpawner<B>) -> Self {
-        let mut rng = thread_rng();
+    fn new_between(data: &mut EnemySpawner, rng: &mut impl Rng) -> Self {
         Self::BetweenBatches(Timer::from_seconds(
             rng.gen_range(data.between_range.clone()),
             TimerMode::Once,
@@ -37,67 +167,109 @@ impl SpawnerState {
     }
 }
 
+/// Spawns batches of enemies at random `poses`. Each enemy is rolled from `table`, so batches can be mixed.
 #[derive(Component, Clone, Debug)]
-pub struct EnemySpawner<B: EnemyBundle> {
-    pd: PhantomData<B>,
+pub struct EnemySpawner {
+    /// Names in the `EnemyRegistry`, with weights
+    pub table: Vec<(String, f32)>,
     pub batch_sizes: Vec<usize>,
     /// Range of time to wait between spawning enemies while actively in a batch
     batch_rate_range: Range<f32>,
     /// Range of time to wait between batches
     between_range: Range<f32>,
     pub poses: Vec<Vec2>,
+    /// How long the marker shows before each enemy actually appears
+    pub telegraph: f32,
 }
-impl<B: EnemyBundle> Default for EnemySpawner<B> {
+impl Default for EnemySpawner {
     fn default() -> Self {
         Self {
-            pd: default(),
+            table: vec![],
             batch_sizes: vec![],
             batch_rate_range: 0.2..1.0,
             between_range: 5.0..25.0,
             poses: default(),
+            telegraph: 0.8,
         }
     }
 }
+impl EnemySpawner {
+    pub fn has_kind(&self, name: &str) -> bool {
+        self.table.iter().any(|(kind, _)| kind == name)
+    }
+
+    fn roll_kind(&self, rng: &mut impl Rng) -> Option<&str> {
+        let total = self.table.iter().map(|(_, weight)| weight).sum::<f32>();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0.0..total);
+        for (kind, weight) in &self.table {
+            if roll < *weight {
+                return Some(kind);
+            }
+            roll -= weight;
+        }
+        self.table.last().map(|(kind, _)| kind.as_str())
+    }
+}
 
 #[derive(Bundle)]
-pub struct EnemySpawnerBundle<B: EnemyBundle> {
+pub struct EnemySpawnerBundle {
     name: Name,
-    spawner: EnemySpawner<B>,
+    spawner: EnemySpawner,
     state: SpawnerState,
 }
-impl<B: EnemyBundle> EnemySpawnerBundle<B> {
-    pub fn new(poses: Vec<Vec2>, batch_sizes: Vec<usize>) -> Self {
+impl EnemySpawnerBundle {
+    /// `table` is pairs of registry name and weight
+    pub fn new(table: &[(&str, f32)], poses: Vec<Vec2>, batch_sizes: Vec<usize>) -> Self {
         Self {
             name: Name::new("spawner"),
             spawner: EnemySpawner {
+                table: table
+                    .iter()
+                    .map(|(kind, weight)| (kind.to_string(), *weight))
+                    .collect(),
                 poses,
                 batch_sizes,
                 ..default()
             },
-            state: SpawnerState::initial(),
+            state: SpawnerState::Starting,
         }
     }
+
+    pub fn with_telegraph(mut self, lead_time: f32) -> Self {
+        self.spawner.telegraph = lead_time;
+        self
+    }
 }
 
-fn update_spawners<B: EnemyBundle>(
-    count: Query<&B::CountComponent>,
-    mut spawners: Query<(Entity, &mut EnemySpawner<B>, &mut SpawnerState)>,
+fn update_spawners(
+    spawned: Query<&SpawnedBy>,
+    mut spawners: Query<(Entity, &mut EnemySpawner, &mut SpawnerState)>,
+    telegraphs: Query<&SpawnTelegraph>,
+    registry: Res<EnemyRegistry>,
     mut commands: Commands,
     meta_state: Res<State<MetaState>>,
     tutorial_root: Res<TutorialRoot>,
     room_root: Res<RoomRoot>,
+    encounter_state: Option<Res<State<EncounterState>>>,
     time: Res<Time>,
     bullet_time: Res<BulletTime>,
+    mut run_rng: ResMut<RunRng>,
 ) {
+    // No elites in the tutorial
+    let difficulty = encounter_state.map_or(0, |state| state.get().elite_difficulty());
     let relevant_root = if meta_state.get_tutorial_state().is_some() {
         tutorial_root.eid()
     } else {
         room_root.eid()
     };
     let time_factor = time.delta_seconds() * bullet_time.factor();
-    let mut rng = thread_rng();
-    for (_eid, mut spawner, mut state) in &mut spawners {
+    let rng = run_rng.stream(RngStream::Spawns);
+    for (eid, mut spawner, mut state) in &mut spawners {
         let state_transition = match state.as_mut() {
+            SpawnerState::Starting => Some(SpawnerState::initial(rng)),
             SpawnerState::MidBatch { num_left, timer } => {
                 if *num_left == 0 {
                     if spawner.batch_sizes.is_empty() {
@@ -105,14 +277,30 @@ fn update_spawners<B: EnemyBundle>(
                         // Some(None) means there is some state transition, going to no state
                         Some(SpawnerState::Done)
                     } else {
-                        Some(SpawnerState::new_between(&mut spawner))
+                        Some(SpawnerState::new_between(&mut spawner, rng))
                     }
+                } else if spawner.poses.is_empty() {
+                    warn!("spawner has nowhere to spawn");
+                    Some(SpawnerState::Done)
                 } else {
                     timer.tick(Duration::from_secs_f32(time_factor));
                     if timer.finished() {
-                        // Spawn a new bad boi, but no state transition
+                        // Warn about a new bad boi, but no state transition
                         let pos = spawner.poses[rng.gen_range(0..spawner.poses.len())];
-                        B::spawn(pos, &mut commands, relevant_root);
+                        match spawner.roll_kind(rng).and_then(|name| registry.get(name)) {
+                            Some(kind) => {
+                                commands
+                                    .spawn(SpawnTelegraphBundle::new(
+                                        pos,
+                                        spawner.telegraph,
+                                        kind.clone(),
+                                        EliteAffix::roll(difficulty, rng),
+                                        eid,
+                                    ))
+                                    .set_parent(relevant_root);
+                            }
+                            None => warn!("spawner has nothing (registered) to spawn"),
+                        }
                         *num_left -= 1;
                         *timer = Timer::from_seconds(
                             rng.gen_range(spawner.batch_rate_range.clone()),
@@ -127,8 +315,11 @@ fn update_spawners<B: EnemyBundle>(
             }
             SpawnerState::BetweenBatches(timer) => {
                 timer.tick(Duration::from_secs_f32(time_factor));
-                if timer.finished() || count.is_empty() {
-                    Some(SpawnerState::new_mid(&mut spawner))
+                // Enemies still being telegraphed count as alive
+                let alive = spawned.iter().any(|spawned_by| spawned_by.0 == eid)
+                    || telegraphs.iter().any(|telegraph| telegraph.source == eid);
+                if timer.finished() || !alive {
+                    Some(SpawnerState::new_mid(&mut spawner, rng))
                 } else {
                     None
                 }
@@ -143,9 +334,10 @@ fn update_spawners<B: EnemyBundle>(
 }
 
 pub(super) fn register_spawners(app: &mut App) {
+    app.init_resource::<EnemyRegistry>();
+
     app.add_systems(
         Update,
-        (update_spawners::<SimpBundle>, update_spawners::<SpewBundle>)
-            .run_if(in_state(PhysicsState::Active)),
+        (update_telegraphs, update_spawners).run_if(in_state(PhysicsState::Active)),
     );
 }
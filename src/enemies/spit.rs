@@ -0,0 +1,121 @@
+use crate::prelude::*;
+
+use super::defs::register_archetype;
+
+/// Spitbeaks hang back and shoot. They try to keep about `keep_away` between themselves and the bird.
+#[derive(Component, Reflect)]
+pub struct SpitGuide {
+    mult: i32,
+    speed: f32,
+    keep_away: f32,
+}
+
+#[derive(Component, Reflect, Default)]
+pub struct SpitHurtbox;
+
+impl EnemyArchetype for SpitGuide {
+    const DEF: &'static str = "spit";
+    type Hurtbox = SpitHurtbox;
+
+    fn from_def(def: &EnemyDef, rng: &mut impl Rng) -> Self {
+        Self {
+            mult: def.roll_mult(rng),
+            speed: def.roll_speed(rng),
+            keep_away: def.roll_keep_away(rng),
+        }
+    }
+
+    fn brain(&self) -> Option<AiMachine> {
+        Some(AiMachine::single(vec![AiAction::KeepAway {
+            distance: self.keep_away,
+            accel: 80.0,
+            max_speed: self.speed,
+        }]))
+    }
+}
+
+pub type SpitBundle = DataEnemyBundle<SpitGuide>;
+
+fn birth_spits(
+    mut commands: Commands,
+    birthing: Query<
+        (
+            Entity,
+            &MultiAnimationManager,
+            &SpitGuide,
+            Option<&Children>,
+        ),
+        With<Birthing>,
+    >,
+) {
+    for (eid, multi, guide, children) in &birthing {
+        if multi.manager("core").get_key().as_str() != "birthing" {
+            commands.entity(eid).remove::<Birthing>();
+            commands
+                .entity(eid)
+                .insert(StaticReceiver::from_kind(StaticReceiverKind::GoAround {
+                    mult: guide.mult,
+                }));
+            if let Some(children) = children {
+                for child in children {
+                    commands.entity(*child).remove::<Birthing>();
+                }
+            }
+        }
+    }
+}
+
+fn hurt_spits(
+    mut damage_taken: EventReader<DamageTaken>,
+    mut spit_guides: Query<&mut MultiAnimationManager, With<SpitGuide>>,
+    spit_hurtboxes: Query<(&Health, &EnemySounds, &Parent), With<SpitHurtbox>>,
+    mut commands: Commands,
+    mut bird: Query<&mut Bird>,
+) {
+    for event in damage_taken.read() {
+        let Ok((health, sounds, parent)) = spit_hurtboxes.get(event.target) else {
+            continue;
+        };
+        let Ok(mut parent_multi) = spit_guides.get_mut(parent.get()) else {
+            continue;
+        };
+        sounds.play_hurt(&mut commands);
+        if !event.killed {
+            parent_multi.manager_mut("core").set_key(
+                format!("health{}", health.scaled_current(2)).as_str(),
+                &mut commands,
+            );
+        } else {
+            parent_multi
+                .manager_mut("core")
+                .set_key("death", &mut commands);
+            parent_multi
+                .manager_mut("light")
+                .set_hidden(true, &mut commands);
+            commands.entity(parent.get()).remove::<PatternShooter>();
+            commands.entity(event.target).remove::<Damage>();
+            commands.entity(event.target).insert(Dying {
+                timer: Timer::from_seconds(2.0, TimerMode::Once),
+                dont_despawn: false,
+            });
+            sounds.play_death(&mut commands);
+            if let Ok(mut bird) = bird.get_single_mut() {
+                bird.dec_kills_left(1);
+            }
+        }
+    }
+}
+
+pub(super) fn register_spits(app: &mut App) {
+    app.register_type::<SpitGuide>();
+    app.register_type::<SpitHurtbox>();
+    register_archetype::<SpitGuide>(app);
+
+    app.add_systems(
+        Update,
+        (birth_spits, hurt_spits)
+            .run_if(in_state(PhysicsState::Active))
+            .after(PhysicsSet)
+            .after(DamageSet),
+    );
+}
@@ -0,0 +1,201 @@
+//! Hand-authored waves, described in `assets/waves/<name>.wave.json`.
+//! A script is a list of "at this time, put this many of this enemy here". Every spawn gets telegraphed first.
+//! The random `EnemySpawner` is still around, this is just another way to fill a room.
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+
+use crate::prelude::*;
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveSpawn {
+    /// Seconds since the script started
+    pub time: f32,
+    /// Name in the `EnemyRegistry`
+    pub enemy: String,
+    /// Room coordinates. More than one enemy get spread in a little circle around this.
+    pub pos: [f32; 2],
+    #[serde(default = "default_count")]
+    pub count: u32,
+    #[serde(default)]
+    pub elite: Option<EliteAffix>,
+}
+impl WaveSpawn {
+    pub fn pos(&self) -> Vec2 {
+        Vec2::from_array(self.pos)
+    }
+}
+
+fn default_telegraph() -> f32 {
+    1.0
+}
+
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct WaveScript {
+    /// How long each telegraph shows before the enemy appears
+    #[serde(default = "default_telegraph")]
+    pub telegraph: f32,
+    pub spawns: Vec<WaveSpawn>,
+}
+impl WaveScript {
+    /// How many enemies the whole script spawns (not counting splits)
+    pub fn total(&self) -> u32 {
+        self.spawns.iter().map(|spawn| spawn.count).sum()
+    }
+}
+
+#[derive(Default)]
+struct WaveScriptLoader;
+impl AssetLoader for WaveScriptLoader {
+    type Asset = WaveScript;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut script: WaveScript = serde_json::from_slice(&bytes)?;
+        script.spawns.sort_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(script)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["wave.json"]
+    }
+}
+
+/// Every script the game knows about. Loaded at startup so rooms can count the enemies up front.
+#[derive(Resource, Default)]
+pub struct WaveScripts {
+    map: HashMap<String, Handle<WaveScript>>,
+}
+impl WaveScripts {
+    const NAMES: [&'static str; 1] = ["pukebeak"];
+
+    pub fn get<'a>(&self, name: &str, scripts: &'a Assets<WaveScript>) -> Option<&'a WaveScript> {
+        self.map.get(name).and_then(|handle| scripts.get(handle))
+    }
+
+    pub fn handle(&self, name: &str) -> Option<Handle<WaveScript>> {
+        self.map.get(name).cloned()
+    }
+}
+
+/// Plays a `WaveScript` from the top
+#[derive(Component)]
+pub struct ScriptedWaves {
+    script: Handle<WaveScript>,
+    time: f32,
+    /// Index of the next spawn in the script
+    next: usize,
+}
+
+#[derive(Bundle)]
+pub struct ScriptedWavesBundle {
+    name: Name,
+    waves: ScriptedWaves,
+}
+impl ScriptedWavesBundle {
+    pub fn new(script: Handle<WaveScript>) -> Self {
+        Self {
+            name: Name::new("scripted_waves"),
+            waves: ScriptedWaves {
+                script,
+                time: 0.0,
+                next: 0,
+            },
+        }
+    }
+}
+
+fn run_wave_scripts(
+    mut runners: Query<(Entity, &mut ScriptedWaves, &Parent)>,
+    scripts: Res<Assets<WaveScript>>,
+    spawned: Query<&SpawnedBy>,
+    telegraphs: Query<&SpawnTelegraph>,
+    registry: Res<EnemyRegistry>,
+    mut commands: Commands,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (eid, mut runner, parent) in &mut runners {
+        let Some(script) = scripts.get(&runner.script) else {
+            continue;
+        };
+        runner.time += time_factor;
+        // Don't make the bird sit around in an empty room waiting for the next wave
+        let alive = spawned.iter().any(|spawned_by| spawned_by.0 == eid)
+            || telegraphs.iter().any(|telegraph| telegraph.source == eid);
+        if !alive {
+            if let Some(spawn) = script.spawns.get(runner.next) {
+                runner.time = runner.time.max(spawn.time);
+            }
+        }
+        while let Some(spawn) = script.spawns.get(runner.next) {
+            if spawn.time > runner.time {
+                break;
+            }
+            let Some(kind) = registry.get(&spawn.enemy) else {
+                warn!(
+                    "wave script wants an enemy called {}, which doesn't exist",
+                    spawn.enemy
+                );
+                runner.next += 1;
+                continue;
+            };
+            for ix in 0..spawn.count {
+                let offset = if spawn.count > 1 {
+                    Vec2::X.my_rotate(2.0 * std::f32::consts::PI * ix as f32 / spawn.count as f32)
+                        * 12.0
+                } else {
+                    Vec2::ZERO
+                };
+                commands
+                    .spawn(SpawnTelegraphBundle::new(
+                        spawn.pos() + offset,
+                        script.telegraph,
+                        kind.clone(),
+                        spawn.elite,
+                        eid,
+                    ))
+                    .set_parent(parent.get());
+            }
+            runner.next += 1;
+        }
+    }
+}
+
+pub(super) fn register_waves(app: &mut App) {
+    app.init_asset::<WaveScript>();
+    app.init_asset_loader::<WaveScriptLoader>();
+    app.insert_resource(WaveScripts::default());
+
+    app.add_systems(
+        Startup,
+        |mut wave_scripts: ResMut<WaveScripts>, asset_server: Res<AssetServer>| {
+            for name in WaveScripts::NAMES {
+                wave_scripts.map.insert(
+                    name.to_string(),
+                    asset_server.load(format!("waves/{name}.wave.json")),
+                );
+            }
+        },
+    );
+    app.add_systems(
+        Update,
+        run_wave_scripts.run_if(in_state(PhysicsState::Active)),
+    );
+}
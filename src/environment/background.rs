@@ -32,7 +32,7 @@ fn spawn_sky(commands: &mut ChildBuilder) {
     ));
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum BackgroundKind {
     SkyOnly,
     Zenith,
@@ -44,6 +44,8 @@ impl BackgroundKind {
             .spawn((
                 Name::new(format!("background_{:?}", self)),
                 spat_tran(pos.x, pos.y, 0.0),
+                // It's far away, so it doesn't scroll with bigger rooms
+                ScreenSpace::new(pos.x, pos.y),
             ))
             .set_parent(parent)
             .with_children(|commands| match self {
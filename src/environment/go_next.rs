@@ -55,6 +55,7 @@ fn update_go_next(
     meta_state: Res<State<MetaState>>,
     mut next_meta_transition: ResMut<NextState<MetaTransitionState>>,
     birds: Query<Entity, With<Bird>>,
+    run_mode: Res<RunMode>,
 ) {
     // Too tired to do this with fancy iterator stuff
     let mut any_hit = false;
@@ -77,7 +78,7 @@ fn update_go_next(
         if let Some(room_state) = meta_state.get_room_state() {
             next_meta_transition.set(
                 TransitionKind::FadeToBlack
-                    .to_meta_transition_state(1.0, room_state.next_room().to_meta_state()),
+                    .to_meta_transition_state(1.0, run_mode.after_room(&room_state)),
             );
             for eid in &birds {
                 // We really shouldn't need to do this, skill issue on my part
@@ -0,0 +1,277 @@
+//! Parts of a room that are out to get you: spikes, lasers and platforms that don't hold up.
+
+use std::time::Duration;
+
+use crate::prelude::*;
+
+#[derive(Component)]
+pub struct Spikes;
+
+/// Hurts anything that touches it (well, the bird)
+#[derive(Bundle)]
+pub struct SpikesBundle {
+    name: Name,
+    spikes: Spikes,
+    damage: Damage,
+    physics: SpikesPhysicsBundle,
+    multi: MultiAnimationManager,
+}
+impl SpikesBundle {
+    const THICKNESS: f32 = 5.0;
+
+    /// A strip `length` long, centered on `pos`, running along `angle`
+    pub fn new(pos: Vec2, length: f32, angle: f32) -> Self {
+        // Teeth along the top, flat along the bottom
+        let num_teeth = (length / Self::THICKNESS).round().max(1.0) as usize;
+        let tooth = length / num_teeth as f32;
+        let mut points = vec![Vec2::new(-length / 2.0, -Self::THICKNESS / 2.0)];
+        for ix in 0..num_teeth {
+            let left = -length / 2.0 + tooth * ix as f32;
+            points.push(Vec2::new(left + tooth / 2.0, Self::THICKNESS / 2.0));
+            points.push(Vec2::new(left + tooth, -Self::THICKNESS / 2.0));
+        }
+        Self {
+            name: Name::new("spikes"),
+            spikes: Spikes,
+            damage: Damage::new(1)
+                .with_knockback(160.0)
+                .with_hit_stop(0.06)
+                .with_shake(0.4),
+            physics: SpikesPhysicsBundle::new(pos, angle, length, Self::THICKNESS),
+            multi: multi!(anim_man!({
+                path: "sprites/default.png",
+                size: (1, 1),
+                color: Color::from(tailwind::ZINC_300),
+            })
+            .with_points(points)),
+        }
+    }
+}
+
+/// Fires a laser out of its right side every so often. Same telegraph as the pukebeak laser.
+#[derive(Component)]
+pub struct LaserEmitter {
+    timer: Timer,
+    damage: Damage,
+    was_harmful: bool,
+    /// The child that draws (and is) the laser. Added by `dress_lasers` once the spew def is around.
+    material: Option<Entity>,
+}
+
+/// The beam part of a `LaserEmitter`
+#[derive(Component)]
+struct LaserMaterial;
+
+#[derive(Bundle)]
+pub struct LaserEmitterBundle {
+    name: Name,
+    emitter: LaserEmitter,
+    physics: LaserEmitterPhysicsBundle,
+    multi: MultiAnimationManager,
+}
+impl LaserEmitterBundle {
+    const SIZE: f32 = 8.0;
+    const WIDTH: f32 = 6.0;
+
+    /// Fires every `period` seconds, the first one `delay` seconds in
+    pub fn new(pos: Vec2, angle: f32, period: f32, delay: f32) -> Self {
+        let mut timer = Timer::from_seconds(period, TimerMode::Repeating);
+        timer.set_elapsed(Duration::from_secs_f32((period - delay).rem_euclid(period)));
+        Self {
+            name: Name::new("laser_emitter"),
+            emitter: LaserEmitter {
+                timer,
+                damage: Damage::new(1)
+                    .with_knockback(150.0)
+                    .with_hit_stop(0.1)
+                    .with_shake(0.6),
+                was_harmful: false,
+                material: None,
+            },
+            physics: LaserEmitterPhysicsBundle::new(pos, angle, Self::SIZE),
+            multi: multi!(anim_man!({
+                path: "debug/non_sticky_texture.png",
+                size: (36, 36),
+            })
+            .with_points(simple_rect(Self::SIZE, Self::SIZE))),
+        }
+    }
+}
+
+/// A sticky platform that gives out a little while after the bird lands on it
+#[derive(Component)]
+pub struct Crumbling {
+    /// Starts once something sticks
+    timer: Option<Timer>,
+}
+impl Crumbling {
+    const SECS: f32 = 1.5;
+    const SHAKE: f32 = 1.5;
+}
+
+#[derive(Bundle)]
+pub struct CrumblingPlatformBundle {
+    name: Name,
+    crumbling: Crumbling,
+    /// Same as a normal sticky platform until it goes
+    physics: StickyPhysicsBundle,
+    pub multi: MultiAnimationManager,
+}
+impl CrumblingPlatformBundle {
+    pub fn new(name: &str, pos: Vec2, shape: Shape) -> Self {
+        let mut anim = StickyPlatformBundle::log_anim(&shape);
+        // Paler, so you can tell it apart from the logs that'll hold
+        for node in anim.map.values_mut() {
+            node.sprite.color = Color::srgb(0.75, 0.65, 0.55);
+        }
+        Self {
+            name: Name::new(format!("crumbling_platform_{name}")),
+            crumbling: Crumbling { timer: None },
+            physics: StickyPhysicsBundle::new(pos, Bounds::from_shape(shape)),
+            multi: multi!(anim),
+        }
+    }
+}
+
+/// Lasers use the spew's material anim, straight from its def, so they get it once that's loaded.
+/// The beam is as long as the room's diagonal so it crosses the whole room from anywhere in it.
+fn dress_lasers(
+    mut emitters: Query<(Entity, &mut LaserEmitter)>,
+    enemy_defs: Res<EnemyDefs>,
+    defs: Res<Assets<EnemyDef>>,
+    bounds: Res<RoomBounds>,
+    mut commands: Commands,
+) {
+    let Some(anim) = enemy_defs
+        .get("spew", &defs)
+        .and_then(|def| def.anim("material"))
+    else {
+        return;
+    };
+    let length = bounds.size.length();
+    for (eid, mut emitter) in &mut emitters {
+        if emitter.material.is_some() {
+            continue;
+        }
+        let points = simple_rect(length, LaserEmitterBundle::WIDTH)
+            .into_iter()
+            .map(|p| p + Vec2::new(length / 2.0, 0.0))
+            .collect::<Vec<_>>();
+        let material_eid = commands
+            .spawn((
+                Name::new("laser_material"),
+                LaserMaterial,
+                SpatialBundle::default(),
+                MultiAnimationManager::from_single(anim.clone().with_points(points)),
+            ))
+            .set_parent(eid)
+            .id();
+        emitter.material = Some(material_eid);
+    }
+}
+
+fn update_lasers(
+    mut emitters: Query<(Entity, &mut LaserEmitter, &GlobalTransform)>,
+    mut materials: Query<&mut MultiAnimationManager, With<LaserMaterial>>,
+    bird: Query<(Entity, &Bounds, &GlobalTransform), With<Bird>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut camera_director: ResMut<CameraDirector>,
+    mut commands: Commands,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (eid, mut emitter, gtran) in &mut emitters {
+        emitter.timer.tick(Duration::from_secs_f32(time_factor));
+        let Some(mut multi) = emitter
+            .material
+            .and_then(|material_eid| materials.get_mut(material_eid).ok())
+        else {
+            continue;
+        };
+        if emitter.timer.just_finished() {
+            // Keep the long skinny points, just change what's drawn on them
+            multi.single_mut().reset_key("prelude", &mut commands);
+        }
+        let harmful = multi.single().get_key() == "harmful";
+        if harmful && !emitter.was_harmful {
+            commands.spawn(SoundEffect::universal("sound_effects/laser.ogg", 0.1));
+            camera_director.add_trauma(0.2);
+        }
+        emitter.was_harmful = harmful;
+        if !harmful {
+            continue;
+        }
+        let Ok((bird_eid, bird_bounds, bird_gtran)) = bird.get_single() else {
+            continue;
+        };
+        let harmful_shape = Shape::Polygon {
+            points: multi.single().get_points(),
+        };
+        let (pos, angle) = gtran.tran_n_angle();
+        if bird_bounds
+            .get_shape()
+            .bounce_off(bird_gtran.tran_n_angle(), (&harmful_shape, pos, angle))
+            .is_some()
+        {
+            // Fine to send every frame, i-frames will stop this from stacking
+            damage_events.send(DamageEvent::from_damage(
+                bird_eid,
+                Some(eid),
+                &emitter.damage,
+                pos,
+                bird_gtran.translation().truncate(),
+            ));
+        }
+    }
+}
+
+fn update_crumbling(
+    mut platforms: Query<(Entity, &mut Crumbling, &mut MultiAnimationManager)>,
+    stuck: Query<(Entity, &Stuck)>,
+    mut commands: Commands,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (eid, mut crumbling, mut multi) in &mut platforms {
+        let stuck_here = stuck
+            .iter()
+            .filter(|(_, stuck)| stuck.parent == eid)
+            .map(|(stuck_eid, _)| stuck_eid)
+            .collect::<Vec<_>>();
+        if crumbling.timer.is_none() && !stuck_here.is_empty() {
+            crumbling.timer = Some(Timer::from_seconds(Crumbling::SECS, TimerMode::Once));
+            commands.spawn(SoundEffect::universal("sound_effects/rock_normal.ogg", 0.2));
+        }
+        let Some(timer) = crumbling.timer.as_mut() else {
+            continue;
+        };
+        timer.tick(Duration::from_secs_f32(time_factor));
+        // Rattle harder the closer it is to going
+        let shake = Crumbling::SHAKE * timer.fraction();
+        let offset = Vec2::new(
+            thread_rng().gen_range(-1.0..=1.0),
+            thread_rng().gen_range(-1.0..=1.0),
+        ) * shake;
+        for anim in multi.map.values_mut() {
+            anim.set_offset(offset.extend(0.0), &mut commands);
+        }
+        if timer.finished() {
+            // Anything still on it falls off (otherwise it'd be stuck to nothing, forever)
+            for stuck_eid in stuck_here {
+                commands.entity(stuck_eid).remove::<Stuck>();
+            }
+            commands.entity(eid).despawn_recursive();
+        }
+    }
+}
+
+pub(super) fn register_hazards(app: &mut App) {
+    app.add_systems(
+        Update,
+        (dress_lasers, update_lasers, update_crumbling)
+            .run_if(in_state(PhysicsState::Active))
+            .after(PhysicsSet),
+    );
+}
@@ -0,0 +1,225 @@
+use std::f32::consts::PI;
+
+use crate::prelude::*;
+
+/// Things enemies can drop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum LootKind {
+    /// One health back
+    Heart,
+    /// Fills every kind of bullet
+    Ammo,
+    /// Currency. Sticks around between attempts.
+    Feather,
+    /// A few seconds of not having to worry about ammo
+    Frenzy,
+}
+impl LootKind {
+    fn color(&self) -> Color {
+        match self {
+            Self::Heart => tailwind::RED_400.into(),
+            Self::Ammo => tailwind::AMBER_300.into(),
+            Self::Feather => tailwind::SKY_200.into(),
+            Self::Frenzy => tailwind::FUCHSIA_400.into(),
+        }
+    }
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+/// One line of a loot table. Each of the `count` drops gets its own roll.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LootDrop {
+    pub kind: LootKind,
+    pub chance: f32,
+    #[serde(default = "default_count")]
+    pub count: u32,
+}
+
+/// What something drops when it starts `Dying`. Goes on whatever gets `Dying` (for enemies that's the hurtbox).
+#[derive(Component, Debug, Clone, Default)]
+pub struct LootTable(pub Vec<LootDrop>);
+impl LootTable {
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<LootKind> {
+        let mut result = vec![];
+        for drop in &self.0 {
+            for _ in 0..drop.count {
+                if rng.gen::<f32>() < drop.chance {
+                    result.push(drop.kind);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[derive(Component)]
+pub struct Loot {
+    kind: LootKind,
+    /// Drops don't wait around forever
+    lifetime: Timer,
+}
+
+#[derive(Bundle)]
+pub struct LootBundle {
+    name: Name,
+    loot: Loot,
+    dyno_tran: DynoTran,
+    gravity: Gravity,
+    bounds: Bounds,
+    static_rx: StaticReceiver,
+    trigger_rx: TriggerReceiver,
+    multi: MultiAnimationManager,
+    spatial: SpatialBundle,
+}
+impl LootBundle {
+    const RADIUS: f32 = 3.0;
+    const LIFETIME: f32 = 10.0;
+
+    pub fn new(kind: LootKind, pos: Vec2, vel: Vec2) -> Self {
+        let num_steps = 6;
+        let points = (0..num_steps)
+            .map(|ix| Vec2::X.my_rotate(2.0 * PI * ix as f32 / num_steps as f32) * Self::RADIUS)
+            .collect::<Vec<_>>();
+        Self {
+            name: Name::new(format!("loot_{kind:?}")),
+            loot: Loot {
+                kind,
+                lifetime: Timer::from_seconds(Self::LIFETIME, TimerMode::Once),
+            },
+            dyno_tran: DynoTran { vel },
+            gravity: Gravity::Normal,
+            bounds: Bounds::from_shape(Shape::Circle {
+                radius: Self::RADIUS,
+            }),
+            static_rx: StaticReceiver::from_kind(StaticReceiverKind::Stop),
+            trigger_rx: TriggerReceiver::from_kind(TriggerKind::Loot),
+            multi: multi!(anim_man!({
+                path: "sprites/default.png",
+                size: (1, 1),
+                color: kind.color(),
+            })
+            .with_points(points)),
+            spatial: spat_tran(pos.x, pos.y, ZIX_BULLET),
+        }
+    }
+}
+
+/// On the bird while a `LootKind::Frenzy` is going
+#[derive(Component)]
+pub struct Frenzy {
+    timer: Timer,
+}
+impl Frenzy {
+    const SECS: f32 = 4.0;
+}
+
+fn drop_loot(
+    dying: Query<(&LootTable, &GlobalTransform), Added<Dying>>,
+    mut commands: Commands,
+    meta_state: Res<State<MetaState>>,
+    room_root: Res<RoomRoot>,
+    tutorial_root: Res<TutorialRoot>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    let parent_eid = if meta_state.get_tutorial_state().is_some() {
+        tutorial_root.eid()
+    } else {
+        room_root.eid()
+    };
+    let rng = run_rng.stream(RngStream::Loot);
+    for (table, gtran) in &dying {
+        let pos = gtran.translation().truncate();
+        for kind in table.roll(rng) {
+            // Pop up and out a little so a pile of drops spreads out
+            let vel = Vec2::new(rng.gen_range(-40.0..40.0), rng.gen_range(60.0..100.0));
+            commands
+                .spawn(LootBundle::new(kind, pos, vel))
+                .set_parent(parent_eid);
+        }
+    }
+}
+
+fn pick_up_loot(
+    mut loot: Query<(Entity, &mut Loot, &TriggerReceiver, &mut Visibility)>,
+    collisions: Query<&TriggerCollisionRecord>,
+    mut bird: Query<(Entity, &mut Bird)>,
+    mut commands: Commands,
+    mut ephemeral_skills: ResMut<EphemeralSkill>,
+    mut permanent_skills: ResMut<PermanentSkill>,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    let Ok((bird_eid, mut bird)) = bird.get_single_mut() else {
+        return;
+    };
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for (eid, mut loot, trigger, mut visibility) in &mut loot {
+        let touched_bird = trigger.collisions.iter().any(|tid| {
+            collisions
+                .get(*tid)
+                .is_ok_and(|record| record.other_kind == TriggerKind::Bird)
+        });
+        if !touched_bird {
+            loot.lifetime
+                .tick(std::time::Duration::from_secs_f32(time_factor));
+            if loot.lifetime.finished() {
+                commands.entity(eid).despawn_recursive();
+            } else if loot.lifetime.remaining_secs() < 2.0 {
+                // Blink when it's about to go
+                *visibility = if (loot.lifetime.remaining_secs() * 8.0) as u32 % 2 == 0 {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+            }
+            continue;
+        }
+        match loot.kind {
+            LootKind::Heart => {
+                ephemeral_skills.inc_current_health(1);
+                commands.spawn(SoundEffect::universal("sound_effects/lenny_heal.ogg", 0.1));
+            }
+            LootKind::Ammo => {
+                bird.refill_bullets(ephemeral_skills.get_num_bullets());
+            }
+            LootKind::Feather => {
+                permanent_skills.add_feathers(1);
+            }
+            LootKind::Frenzy => {
+                commands.entity(bird_eid).insert(Frenzy {
+                    timer: Timer::from_seconds(Frenzy::SECS, TimerMode::Once),
+                });
+            }
+        }
+        commands.entity(eid).despawn_recursive();
+    }
+}
+
+fn update_frenzy(
+    mut bird: Query<(Entity, &mut Bird, &mut Frenzy)>,
+    mut commands: Commands,
+    ephemeral_skills: Res<EphemeralSkill>,
+    time: Res<Time>,
+) {
+    let Ok((eid, mut bird, mut frenzy)) = bird.get_single_mut() else {
+        return;
+    };
+    // Real time, slowing down shouldn't make it last longer
+    frenzy.timer.tick(time.delta());
+    bird.refill_bullets(ephemeral_skills.get_num_bullets());
+    if frenzy.timer.finished() {
+        commands.entity(eid).remove::<Frenzy>();
+    }
+}
+
+pub(super) fn register_loot(app: &mut App) {
+    app.add_systems(
+        Update,
+        (drop_loot, pick_up_loot, update_frenzy)
+            .run_if(in_state(PhysicsState::Active))
+            .after(PhysicsSet),
+    );
+}
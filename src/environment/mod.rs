@@ -2,18 +2,27 @@ use crate::prelude::*;
 
 pub mod background;
 pub mod go_next;
+pub mod hazards;
 pub mod heart;
+pub mod loot;
 pub mod platforms;
+pub mod props;
 
 pub use background::*;
 pub use go_next::*;
+pub use hazards::*;
 pub use heart::*;
+pub use loot::*;
 pub use platforms::*;
+pub use props::*;
 
 pub(super) struct EnvironmentPlugin;
 impl Plugin for EnvironmentPlugin {
     fn build(&self, app: &mut App) {
         heart::register_hearts(app);
+        loot::register_loot(app);
         go_next::register_go_next(app);
+        hazards::register_hazards(app);
+        props::register_props(app);
     }
 }
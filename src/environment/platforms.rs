@@ -8,8 +8,17 @@ pub struct StickyPlatformBundle {
 }
 impl StickyPlatformBundle {
     pub fn new(name: &str, pos: Vec2, shape: Shape) -> StickyPlatformBundle {
+        Self {
+            name: Name::new(format!("sticky_platform_{name}")),
+            multi: multi!(Self::log_anim(&shape)),
+            physics: StickyPhysicsBundle::new(pos, Bounds::from_shape(shape)),
+        }
+    }
+
+    /// Picks the log sprite that best fits the shape
+    pub fn log_anim(shape: &Shape) -> AnimationManager {
         let multi_points = shape.to_anim_points();
-        let anim_man = match &shape {
+        match shape {
             Shape::Circle { radius } => anim_man!({
                 path: "environment/log_circular.png",
                 size: (70, 70),
@@ -31,67 +40,17 @@ impl StickyPlatformBundle {
                     .with_points(multi_points)
                 }
             }
-        };
-        Self {
-            name: Name::new(format!("sticky_platform_{name}")),
-            physics: StickyPhysicsBundle::new(pos, Bounds::from_shape(shape)),
-            multi: multi!(anim_man),
         }
     }
 
     // Common configuration
     pub fn around_room() -> Self {
-        let buffer_out = 5.0;
-        let buffer_in = 5.0;
-        let name = "around_room";
-        let shape = Shape::Polygon {
-            points: vec![
-                // GO FORWARD
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 - buffer_out,
-                    -IDEAL_HEIGHT_f32 / 2.0 - buffer_out,
-                ),
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 - buffer_out,
-                    IDEAL_HEIGHT_f32 / 2.0 + buffer_out,
-                ),
-                Vec2::new(
-                    IDEAL_WIDTH_f32 / 2.0 + buffer_out,
-                    IDEAL_HEIGHT_f32 / 2.0 + buffer_out,
-                ),
-                Vec2::new(
-                    IDEAL_WIDTH_f32 / 2.0 + buffer_out,
-                    -IDEAL_HEIGHT_f32 / 2.0 - buffer_out,
-                ),
-                // PAUSE
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 - buffer_out,
-                    -IDEAL_HEIGHT_f32 / 2.0 - buffer_out,
-                ),
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 + buffer_in,
-                    -IDEAL_HEIGHT_f32 / 2.0 + buffer_in,
-                ),
-                // GO BACK
-                Vec2::new(
-                    IDEAL_WIDTH_f32 / 2.0 - buffer_in,
-                    -IDEAL_HEIGHT_f32 / 2.0 + buffer_in,
-                ),
-                Vec2::new(
-                    IDEAL_WIDTH_f32 / 2.0 - buffer_in,
-                    IDEAL_HEIGHT_f32 / 2.0 - buffer_in,
-                ),
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 + buffer_in,
-                    IDEAL_HEIGHT_f32 / 2.0 - buffer_in,
-                ),
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 + buffer_in,
-                    -IDEAL_HEIGHT_f32 / 2.0 + buffer_in,
-                ),
-            ],
-        };
-        Self::new(name, Vec2::ZERO, shape)
+        Self::around_room_sized(IDEAL_VEC_f32)
+    }
+
+    /// Same as `around_room` but for a room of any size (still centered on the origin)
+    pub fn around_room_sized(size: Vec2) -> Self {
+        Self::new("around_room", Vec2::ZERO, around_room_shape(size))
     }
 
     // Common configuration
@@ -175,56 +134,34 @@ impl HardPlatformBundle {
 
     // Common configuration
     pub fn around_room() -> Self {
-        let buffer_out = 5.0;
-        let buffer_in = 5.0;
-        let name = "around_room";
-        let shape = Shape::Polygon {
-            points: vec![
-                // GO FORWARD
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 - buffer_out,
-                    -IDEAL_HEIGHT_f32 / 2.0 - buffer_out,
-                ),
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 - buffer_out,
-                    IDEAL_HEIGHT_f32 / 2.0 + buffer_out,
-                ),
-                Vec2::new(
-                    IDEAL_WIDTH_f32 / 2.0 + buffer_out,
-                    IDEAL_HEIGHT_f32 / 2.0 + buffer_out,
-                ),
-                Vec2::new(
-                    IDEAL_WIDTH_f32 / 2.0 + buffer_out,
-                    -IDEAL_HEIGHT_f32 / 2.0 - buffer_out,
-                ),
-                // PAUSE
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 - buffer_out,
-                    -IDEAL_HEIGHT_f32 / 2.0 - buffer_out,
-                ),
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 + buffer_in,
-                    -IDEAL_HEIGHT_f32 / 2.0 + buffer_in,
-                ),
-                // GO BACK
-                Vec2::new(
-                    IDEAL_WIDTH_f32 / 2.0 - buffer_in,
-                    -IDEAL_HEIGHT_f32 / 2.0 + buffer_in,
-                ),
-                Vec2::new(
-                    IDEAL_WIDTH_f32 / 2.0 - buffer_in,
-                    IDEAL_HEIGHT_f32 / 2.0 - buffer_in,
-                ),
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 + buffer_in,
-                    IDEAL_HEIGHT_f32 / 2.0 - buffer_in,
-                ),
-                Vec2::new(
-                    -IDEAL_WIDTH_f32 / 2.0 + buffer_in,
-                    -IDEAL_HEIGHT_f32 / 2.0 + buffer_in,
-                ),
-            ],
-        };
-        Self::new(name, Vec2::ZERO, shape)
+        Self::around_room_sized(IDEAL_VEC_f32)
+    }
+
+    /// Same as `around_room` but for a room of any size (still centered on the origin)
+    pub fn around_room_sized(size: Vec2) -> Self {
+        Self::new("around_room", Vec2::ZERO, around_room_shape(size))
+    }
+}
+
+/// A frame that hugs the inside edge of a `size` room, with a little lip outside too
+fn around_room_shape(size: Vec2) -> Shape {
+    let buffer_out = 5.0;
+    let buffer_in = 5.0;
+    Shape::Polygon {
+        points: vec![
+            // GO FORWARD
+            Vec2::new(-size.x / 2.0 - buffer_out, -size.y / 2.0 - buffer_out),
+            Vec2::new(-size.x / 2.0 - buffer_out, size.y / 2.0 + buffer_out),
+            Vec2::new(size.x / 2.0 + buffer_out, size.y / 2.0 + buffer_out),
+            Vec2::new(size.x / 2.0 + buffer_out, -size.y / 2.0 - buffer_out),
+            // PAUSE
+            Vec2::new(-size.x / 2.0 - buffer_out, -size.y / 2.0 - buffer_out),
+            Vec2::new(-size.x / 2.0 + buffer_in, -size.y / 2.0 + buffer_in),
+            // GO BACK
+            Vec2::new(size.x / 2.0 - buffer_in, -size.y / 2.0 + buffer_in),
+            Vec2::new(size.x / 2.0 - buffer_in, size.y / 2.0 - buffer_in),
+            Vec2::new(-size.x / 2.0 + buffer_in, size.y / 2.0 - buffer_in),
+            Vec2::new(-size.x / 2.0 + buffer_in, -size.y / 2.0 + buffer_in),
+        ],
     }
 }
@@ -0,0 +1,146 @@
+//! Stuff lying around a room that breaks when you shoot it. Uses `Health` like everything else, and drops loot when it goes.
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub enum PropKind {
+    /// Takes a few hits, usually has ammo in it
+    Crate,
+    /// Pops in one hit, sometimes has a feather
+    Nest,
+}
+impl PropKind {
+    fn health(&self) -> u32 {
+        match self {
+            Self::Crate => 3,
+            Self::Nest => 1,
+        }
+    }
+
+    fn size(&self) -> f32 {
+        match self {
+            Self::Crate => 12.0,
+            Self::Nest => 8.0,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Self::Crate => tailwind::AMBER_700.into(),
+            Self::Nest => tailwind::YELLOW_600.into(),
+        }
+    }
+
+    fn loot(&self) -> LootTable {
+        let drops = match self {
+            Self::Crate => vec![
+                LootDrop {
+                    kind: LootKind::Ammo,
+                    chance: 0.8,
+                    count: 1,
+                },
+                LootDrop {
+                    kind: LootKind::Heart,
+                    chance: 0.2,
+                    count: 1,
+                },
+            ],
+            Self::Nest => vec![LootDrop {
+                kind: LootKind::Feather,
+                chance: 0.5,
+                count: 2,
+            }],
+        };
+        LootTable(drops)
+    }
+
+    fn points(&self) -> Vec<Vec2> {
+        let size = self.size();
+        match self {
+            Self::Crate => simple_rect(size, size),
+            // Squat bowl, wider than it is tall
+            Self::Nest => vec![
+                Vec2::new(-size, size / 3.0),
+                Vec2::new(size, size / 3.0),
+                Vec2::new(size * 0.6, -size / 2.0),
+                Vec2::new(-size * 0.6, -size / 2.0),
+            ],
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Prop {
+    pub kind: PropKind,
+}
+
+#[derive(Bundle)]
+pub struct PropBundle {
+    name: Name,
+    prop: Prop,
+    health: Health,
+    loot: LootTable,
+    physics: PropTriggerPhysicsBundle,
+    multi: MultiAnimationManager,
+}
+impl PropBundle {
+    pub fn new(kind: PropKind, pos: Vec2) -> Self {
+        Self {
+            name: Name::new(format!("prop_{kind:?}")),
+            prop: Prop { kind },
+            // A little bit of i-frames so one spread shot doesn't take a crate out all at once
+            health: Health::new(kind.health())
+                .with_i_frames(0.1)
+                .with_vulnerable_to([TriggerKind::BulletGood]),
+            loot: kind.loot(),
+            physics: PropTriggerPhysicsBundle::new(pos, kind.points()),
+            multi: multi!(anim_man!({
+                path: "sprites/default.png",
+                size: (1, 1),
+                color: kind.color(),
+            })
+            .with_points(kind.points())),
+        }
+    }
+}
+
+fn hurt_props(
+    mut damage_taken: EventReader<DamageTaken>,
+    mut props: Query<(&Health, &mut MultiAnimationManager), With<Prop>>,
+    mut commands: Commands,
+) {
+    for event in damage_taken.read() {
+        let Ok((health, mut multi)) = props.get_mut(event.target) else {
+            continue;
+        };
+        commands.spawn(SoundEffect::universal(
+            "sound_effects/rock_normal.ogg",
+            0.15,
+        ));
+        if !event.killed {
+            // Smaller the closer it is to breaking
+            let frac = health.get_current() as f32 / health.get_max() as f32;
+            multi
+                .manager_mut("core")
+                .set_scale(Vec2::splat(0.6 + 0.4 * frac), &mut commands);
+            continue;
+        }
+        multi.manager_mut("core").set_hidden(true, &mut commands);
+        // `Dying` is what makes the loot drop
+        commands.entity(event.target).insert(Dying {
+            timer: Timer::from_seconds(0.1, TimerMode::Once),
+            dont_despawn: false,
+        });
+    }
+}
+
+pub(super) fn register_props(app: &mut App) {
+    app.register_type::<Prop>();
+    app.add_systems(
+        Update,
+        hurt_props
+            .run_if(in_state(PhysicsState::Active))
+            .after(PhysicsSet)
+            .after(DamageSet),
+    );
+}
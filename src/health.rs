@@ -0,0 +1,362 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+
+/// Everything that figures out who got hurt. React to `DamageTaken` after this.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DamageSet;
+
+/// Anything that can get hurt. Should live on the entity with the `TriggerReceiver` that gets hit.
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Health {
+    current: u32,
+    max: u32,
+    /// How many seconds we can't be hurt for after getting hit. 0 means no i-frames at all.
+    i_frames: f32,
+    immune: Option<Timer>,
+    /// Nothing gets through while this is set (dashing, shields, etc.)
+    invulnerable: bool,
+    /// The kinds of triggers that hurt us when they touch us
+    vulnerable_to: Vec<TriggerKind>,
+    /// Once-per-touch sources that are still touching us
+    touching: HashSet<Entity>,
+}
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self {
+            current: max,
+            max,
+            i_frames: 0.0,
+            immune: None,
+            invulnerable: false,
+            vulnerable_to: vec![],
+            touching: default(),
+        }
+    }
+
+    pub fn with_i_frames(mut self, secs: f32) -> Self {
+        self.i_frames = secs;
+        self
+    }
+
+    pub fn with_vulnerable_to(mut self, kinds: impl IntoIterator<Item = TriggerKind>) -> Self {
+        self.vulnerable_to = kinds.into_iter().collect();
+        self
+    }
+
+    pub fn get_current(&self) -> u32 {
+        self.current
+    }
+
+    pub fn set_current(&mut self, current: u32) {
+        self.current = current.min(self.max);
+    }
+
+    pub fn get_max(&self) -> u32 {
+        self.max
+    }
+
+    pub fn set_max(&mut self, max: u32) {
+        self.max = max;
+        self.current = self.current.min(max);
+    }
+
+    /// Current health as if max health were `out_of`, rounded up. Handy for picking sprites when elites have extra health.
+    pub fn scaled_current(&self, out_of: u32) -> u32 {
+        if self.max == 0 {
+            return 0;
+        }
+        (self.current * out_of).div_ceil(self.max)
+    }
+
+    pub fn heal_full(&mut self) {
+        self.current = self.max;
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+
+    /// Still recovering from the last hit
+    pub fn is_immune(&self) -> bool {
+        self.immune.is_some()
+    }
+
+    pub fn set_invulnerable(&mut self, invulnerable: bool) {
+        self.invulnerable = invulnerable;
+    }
+
+    pub fn can_take_damage(&self) -> bool {
+        !self.is_dead() && !self.is_immune() && !self.invulnerable
+    }
+}
+
+/// Blocks contact damage coming from within `half_angle` of `dir` (world space). Goes next to `Health`.
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct DamageShield {
+    pub dir: Vec2,
+    pub half_angle: f32,
+}
+impl DamageShield {
+    pub fn new(dir: Vec2, half_angle: f32) -> Self {
+        Self {
+            dir: dir.normalize_or(Vec2::X),
+            half_angle,
+        }
+    }
+
+    /// Whether something hitting at `from` would bounce off the shield of something at `pos`
+    pub fn blocks(&self, from: Vec2, pos: Vec2) -> bool {
+        let diff = from - pos;
+        if diff.length_squared() < 0.0001 {
+            return false;
+        }
+        diff.angle_between(self.dir).abs() <= self.half_angle
+    }
+}
+
+/// Anything that hurts the things it touches
+#[derive(Component, Debug, Clone, Reflect)]
+pub struct Damage {
+    pub amount: u32,
+    /// How hard whatever gets hit is pushed away from the point of contact
+    pub knockback: f32,
+    /// Seconds to freeze the game for when this lands. Sells the hit.
+    pub hit_stop: f32,
+    /// Trauma to add to the `CameraDirector` when this lands
+    pub shake: f32,
+    /// Bullets should only hurt once no matter how long they overlap. Bodies keep hurting.
+    pub once_per_touch: bool,
+}
+impl Damage {
+    pub fn new(amount: u32) -> Self {
+        Self {
+            amount,
+            knockback: 0.0,
+            hit_stop: 0.0,
+            shake: 0.0,
+            once_per_touch: false,
+        }
+    }
+
+    pub fn with_knockback(mut self, knockback: f32) -> Self {
+        self.knockback = knockback;
+        self
+    }
+
+    pub fn with_hit_stop(mut self, secs: f32) -> Self {
+        self.hit_stop = secs;
+        self
+    }
+
+    pub fn with_shake(mut self, trauma: f32) -> Self {
+        self.shake = trauma;
+        self
+    }
+
+    pub fn once_per_touch(mut self) -> Self {
+        self.once_per_touch = true;
+        self
+    }
+}
+
+/// Ask for something to get hurt. Goes through i-frames and invulnerability so it's safe to spam.
+#[derive(Event, Debug, Clone)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub amount: u32,
+    /// Impulse added to the target's velocity (or its parent's, for hurtboxes)
+    pub knockback: Vec2,
+    pub hit_stop: f32,
+    pub shake: f32,
+}
+impl DamageEvent {
+    /// Fills in everything but the target and source from a `Damage`, pushing away from `from`
+    pub fn from_damage(
+        target: Entity,
+        source: Option<Entity>,
+        damage: &Damage,
+        from: Vec2,
+        target_pos: Vec2,
+    ) -> Self {
+        Self {
+            target,
+            source,
+            amount: damage.amount,
+            knockback: (target_pos - from).normalize_or_zero() * damage.knockback,
+            hit_stop: damage.hit_stop,
+            shake: damage.shake,
+        }
+    }
+}
+
+/// Freezes bullet time for a moment after big hits. Ticks in real time (otherwise it'd never end).
+#[derive(Resource, Debug, Default)]
+pub struct HitStop {
+    timer: Option<Timer>,
+}
+impl HitStop {
+    /// Won't shorten a hit-stop that's already going
+    pub fn start(&mut self, secs: f32) {
+        if secs <= 0.0 {
+            return;
+        }
+        let remaining = self
+            .timer
+            .as_ref()
+            .map(|timer| timer.remaining_secs())
+            .unwrap_or(0.0);
+        if secs > remaining {
+            self.timer = Some(Timer::from_seconds(secs, TimerMode::Once));
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.timer.is_some()
+    }
+}
+
+/// Sent once damage actually lands
+#[derive(Event, Debug, Clone)]
+pub struct DamageTaken {
+    pub target: Entity,
+    pub source: Option<Entity>,
+    pub amount: u32,
+    /// True only for the hit that took the last of the health
+    pub killed: bool,
+}
+
+fn tick_immunity(mut healths: Query<&mut Health>, time: Res<Time>, bullet_time: Res<BulletTime>) {
+    let time_factor = time.delta_seconds() * bullet_time.factor();
+    for mut health in &mut healths {
+        let Some(timer) = health.immune.as_mut() else {
+            continue;
+        };
+        timer.tick(Duration::from_secs_f32(time_factor));
+        if timer.finished() {
+            health.immune = None;
+        }
+    }
+}
+
+fn update_hit_stop(mut hit_stop: ResMut<HitStop>, time: Res<Time>) {
+    let Some(timer) = hit_stop.timer.as_mut() else {
+        return;
+    };
+    timer.tick(time.delta());
+    if timer.finished() {
+        hit_stop.timer = None;
+    }
+}
+
+fn detect_contact_damage(
+    mut targets: Query<
+        (
+            Entity,
+            &mut Health,
+            &TriggerReceiver,
+            &GlobalTransform,
+            Option<&DamageShield>,
+        ),
+        (Without<Birthing>, Without<Dying>, Without<Dead>),
+    >,
+    // NOTE: Dying sources still hurt (explosions live their whole lives dying), so take `Damage` off when you die
+    sources: Query<&Damage, Without<Birthing>>,
+    collisions: Query<&TriggerCollisionRecord>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (eid, mut health, rx, gtran, shield) in &mut targets {
+        let mut still_touching = HashSet::new();
+        for cid in rx.collisions.iter() {
+            let Ok(collision) = collisions.get(*cid) else {
+                continue;
+            };
+            if !health.vulnerable_to.contains(&collision.other_kind) {
+                continue;
+            }
+            let Ok(damage) = sources.get(collision.other_eid) else {
+                continue;
+            };
+            if damage.once_per_touch {
+                still_touching.insert(collision.other_eid);
+                if health.touching.contains(&collision.other_eid) {
+                    continue;
+                }
+            }
+            if shield
+                .is_some_and(|shield| shield.blocks(collision.pos, gtran.translation().truncate()))
+            {
+                continue;
+            }
+            damage_events.send(DamageEvent::from_damage(
+                eid,
+                Some(collision.other_eid),
+                damage,
+                collision.pos,
+                gtran.translation().truncate(),
+            ));
+        }
+        health.touching = still_touching;
+    }
+}
+
+fn apply_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut targets: Query<(&mut Health, Option<&mut DynoTran>, Option<&Parent>)>,
+    mut parents: Query<&mut DynoTran, Without<Health>>,
+    mut damage_taken: EventWriter<DamageTaken>,
+    mut hit_stop: ResMut<HitStop>,
+    mut camera_director: ResMut<CameraDirector>,
+) {
+    for event in damage_events.read() {
+        let Ok((mut health, dyno_tran, parent)) = targets.get_mut(event.target) else {
+            continue;
+        };
+        if !health.can_take_damage() {
+            continue;
+        }
+        health.current = health.current.saturating_sub(event.amount);
+        if health.i_frames > 0.0 {
+            health.immune = Some(Timer::from_seconds(health.i_frames, TimerMode::Once));
+        }
+        // Hurtboxes are usually children of whatever is actually moving
+        if let Some(mut dyno_tran) = dyno_tran {
+            dyno_tran.vel += event.knockback;
+        } else if let Some(mut parent_dyno_tran) =
+            parent.and_then(|parent| parents.get_mut(parent.get()).ok())
+        {
+            parent_dyno_tran.vel += event.knockback;
+        }
+        hit_stop.start(event.hit_stop);
+        camera_director.add_trauma(event.shake);
+        damage_taken.send(DamageTaken {
+            target: event.target,
+            source: event.source,
+            amount: event.amount,
+            killed: health.is_dead(),
+        });
+    }
+}
+
+pub(super) struct HealthPlugin;
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>();
+        app.add_event::<DamageTaken>();
+        app.insert_resource(HitStop::default());
+        app.register_type::<Health>();
+        app.register_type::<Damage>();
+        app.register_type::<DamageShield>();
+
+        app.add_systems(
+            Update,
+            (tick_immunity, detect_contact_damage, apply_damage)
+                .chain()
+                .in_set(DamageSet)
+                .run_if(in_state(PhysicsState::Active))
+                .after(PhysicsSet),
+        );
+        app.add_systems(Update, update_hit_stop);
+    }
+}
@@ -1,4 +1,4 @@
-use bevy::window::PrimaryWindow;
+use bevy::{input::mouse::MouseWheel, window::PrimaryWindow};
 
 use crate::prelude::*;
 
@@ -9,24 +9,41 @@ use crate::prelude::*;
 pub struct InputSet;
 
 /// Mouse state. This may be hard to controler-ize. Oh well.
+/// NOTE: Positions are kept in screen space (ideal pixels, origin in the middle) so that drags
+/// don't change just because the camera moved. The getters hand back world positions.
 #[derive(Resource)]
 pub struct MouseInput {
-    world_pos: Vec2,
+    screen_pos: Vec2,
+    camera_offset: Vec2,
+    camera_zoom: f32,
     pub buttons: ButtonInput<MouseButton>,
     left_drag_start: Option<Vec2>,
     right_drag_start: Option<Vec2>,
 }
 impl MouseInput {
     pub fn get_world_pos(&self) -> Vec2 {
-        self.world_pos
+        self.to_world(self.screen_pos)
+    }
+
+    pub fn get_screen_pos(&self) -> Vec2 {
+        self.screen_pos
     }
 
     pub fn get_left_drag_start(&self) -> Option<Vec2> {
-        self.left_drag_start
+        self.left_drag_start.map(|start| self.to_world(start))
     }
 
     pub fn get_right_drag_start(&self) -> Option<Vec2> {
-        self.right_drag_start
+        self.right_drag_start.map(|start| self.to_world(start))
+    }
+
+    fn to_world(&self, screen_pos: Vec2) -> Vec2 {
+        screen_pos / self.camera_zoom + self.camera_offset
+    }
+
+    /// What a `Launch` would be if the left button came up right now
+    pub fn get_left_drag(&self) -> Option<Vec2> {
+        self.left_drag_start.map(|start| start - self.screen_pos)
     }
 }
 
@@ -67,10 +84,24 @@ pub struct Launch(pub Vec2);
 #[derive(Event)]
 pub struct Fire(pub Vec2);
 
+/// Event that corresponds to input that _should_ switch which kind of bullet is fired.
+/// Usually means Q/E or the scroll wheel. Positive goes forward, negative goes back.
+#[derive(Event)]
+pub struct CycleBulletKind(pub i32);
+
+/// Event that corresponds to input that _should_ trigger one of the bird's abilities.
+/// Usually means Shift (dash), F (shield) or C (ground pound).
+#[derive(Event)]
+pub struct UseAbility(pub AbilityKind);
+
 /// Pretty wide net, but should cover all input in menus, cutscenes, pause screens, convos...
 #[derive(Event)]
 pub enum NonGameInput {
     Continue,
+    Left,
+    Right,
+    Up,
+    Down,
 }
 
 // INTERNAL INPUT SYSTEM (ONLY USED IN THIS FILE)
@@ -80,6 +111,7 @@ fn update_mouse_input(
     buttons: Res<ButtonInput<MouseButton>>,
     q_windows: Query<&Window, With<PrimaryWindow>>,
     mut state: ResMut<MouseInput>,
+    camera_director: Res<CameraDirector>,
     mut launch_writer: EventWriter<Launch>,
     mut fire_writer: EventWriter<Fire>,
 ) {
@@ -87,16 +119,16 @@ fn update_mouse_input(
         // Mouse is not in the window, don't do anything
         return;
     };
-    let world_pos = Vec2::new(
+    let screen_pos = Vec2::new(
         mouse_pos.x - WINDOW_WIDTH_f32 / 2.0,
         -mouse_pos.y + WINDOW_HEIGHT_f32 / 2.0,
     ) / IDEAL_GROWTH_f32;
     let left_drag_start = if buttons.just_pressed(MouseButton::Left) {
-        Some(world_pos)
+        Some(screen_pos)
     } else {
         if let Some(drag_start) = state.left_drag_start {
             if !buttons.pressed(MouseButton::Left) || buttons.just_released(MouseButton::Left) {
-                launch_writer.send(Launch(drag_start - world_pos));
+                launch_writer.send(Launch(drag_start - screen_pos));
                 None
             } else {
                 Some(drag_start)
@@ -106,11 +138,11 @@ fn update_mouse_input(
         }
     };
     let right_drag_start = if buttons.just_pressed(MouseButton::Right) {
-        Some(world_pos)
+        Some(screen_pos)
     } else {
         if let Some(drag_start) = state.right_drag_start {
             if !buttons.pressed(MouseButton::Right) || buttons.just_released(MouseButton::Right) {
-                fire_writer.send(Fire(drag_start - world_pos));
+                fire_writer.send(Fire(drag_start - screen_pos));
                 None
             } else {
                 Some(drag_start)
@@ -120,7 +152,9 @@ fn update_mouse_input(
         }
     };
     *state = MouseInput {
-        world_pos,
+        screen_pos,
+        camera_offset: camera_director.get_offset(),
+        camera_zoom: camera_director.get_zoom(),
         buttons: buttons.clone(),
         left_drag_start,
         right_drag_start,
@@ -145,6 +179,41 @@ fn update_movement_input(keyboard: Res<ButtonInput<KeyCode>>, mut movement: ResM
     movement.fast_stop = keyboard.pressed(KeyCode::Space);
 }
 
+fn update_bullet_kind_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut cycle_writer: EventWriter<CycleBulletKind>,
+) {
+    let mut dir = 0;
+    if keyboard.just_pressed(KeyCode::KeyQ) {
+        dir -= 1;
+    }
+    if keyboard.just_pressed(KeyCode::KeyE) {
+        dir += 1;
+    }
+    for scroll in wheel.read() {
+        dir += scroll.y.signum() as i32;
+    }
+    if dir != 0 {
+        cycle_writer.send(CycleBulletKind(dir));
+    }
+}
+
+fn update_ability_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut ability_writer: EventWriter<UseAbility>,
+) {
+    if keyboard.any_just_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        ability_writer.send(UseAbility(AbilityKind::Dash));
+    }
+    if keyboard.just_pressed(KeyCode::KeyF) {
+        ability_writer.send(UseAbility(AbilityKind::Shield));
+    }
+    if keyboard.just_pressed(KeyCode::KeyC) {
+        ability_writer.send(UseAbility(AbilityKind::GroundPound));
+    }
+}
+
 fn update_convo_input(
     keyboard: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
@@ -169,6 +238,22 @@ fn watch_non_game_input(
         non_game_writer.send(NonGameInput::Continue);
         return;
     }
+    if keyboard.any_just_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        non_game_writer.send(NonGameInput::Left);
+        return;
+    }
+    if keyboard.any_just_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        non_game_writer.send(NonGameInput::Right);
+        return;
+    }
+    if keyboard.any_just_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        non_game_writer.send(NonGameInput::Up);
+        return;
+    }
+    if keyboard.any_just_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        non_game_writer.send(NonGameInput::Down);
+        return;
+    }
 }
 
 pub(super) struct InputPlugin;
@@ -176,7 +261,9 @@ impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         // Resources
         app.insert_resource(MouseInput {
-            world_pos: default(),
+            screen_pos: default(),
+            camera_offset: default(),
+            camera_zoom: 1.0,
             buttons: default(),
             left_drag_start: None,
             right_drag_start: None,
@@ -190,6 +277,8 @@ impl Plugin for InputPlugin {
         // Events
         app.add_event::<Launch>();
         app.add_event::<Fire>();
+        app.add_event::<CycleBulletKind>();
+        app.add_event::<UseAbility>();
         app.add_event::<NonGameInput>();
 
         // Systems
@@ -198,6 +287,8 @@ impl Plugin for InputPlugin {
             (
                 update_mouse_input,
                 update_movement_input,
+                update_bullet_kind_input,
+                update_ability_input,
                 update_convo_input,
                 watch_non_game_input,
             )
@@ -13,12 +13,14 @@ pub mod cutscene;
 pub mod debug;
 pub mod enemies;
 pub mod environment;
+pub mod health;
 pub mod input;
 pub mod macros;
 pub mod math;
 pub mod menu;
 pub mod particles;
 pub mod physics;
+pub mod rng;
 pub mod room;
 pub mod roots;
 pub mod settings;
@@ -41,6 +43,7 @@ pub mod prelude {
     #[allow(unused_imports)]
     pub use super::enemies::*;
     pub use super::environment::*;
+    pub use super::health::*;
     pub use super::input::*;
     pub use super::macros::*;
     pub use super::math::*;
@@ -48,6 +51,9 @@ pub mod prelude {
     pub use super::menu::*;
     pub use super::particles::*;
     pub use super::physics::*;
+    pub use super::rng::*;
+    #[allow(unused_imports)]
+    pub use super::room::*;
     pub use super::roots::*;
     pub use super::settings::*;
     pub use super::sound::*;
@@ -99,10 +105,12 @@ fn main() {
         .add_plugins(debug::DebugPlugin)
         .add_plugins(enemies::EnemiesPlugin)
         .add_plugins(environment::EnvironmentPlugin)
+        .add_plugins(health::HealthPlugin)
         .add_plugins(input::InputPlugin)
         .add_plugins(menu::MenuPlugin)
         .add_plugins(particles::ParticlesPlugin)
         .add_plugins(physics::PhysicsPlugin)
+        .add_plugins(rng::RngPlugin)
         .add_plugins(room::RoomPlugin)
         .add_plugins(roots::RootPlugin)
         .add_plugins(settings::SettingsPlugin)
@@ -0,0 +1,215 @@
+use bevy::sprite::Anchor;
+
+use crate::prelude::*;
+
+/// The bird currently being looked at. Doesn't actually get chosen until continuing.
+#[derive(Component)]
+struct CharacterPreview {
+    character: BirdCharacter,
+}
+
+#[derive(Component)]
+struct CharacterText;
+
+#[derive(Component)]
+struct RunModeText;
+
+fn run_mode_text(run_mode: RunMode, high_scores: &HighScores) -> String {
+    match run_mode {
+        RunMode::Story => "Story".to_string(),
+        RunMode::Endless => format!("Endless\n\n{}", high_scores.table(5)),
+    }
+}
+
+fn character_text(character: BirdCharacter) -> String {
+    let def = character.def();
+    format!(
+        "{}\n{}\n{}\n\nLaunches {}  Bullets {}  Health {}",
+        def.name,
+        def.blurb,
+        def.passive.description(),
+        def.base_skill.get_num_launches(),
+        def.base_skill.get_num_bullets(),
+        def.base_skill.get_max_health(),
+    )
+}
+
+fn setup_character_select(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    current: Res<BirdCharacter>,
+    run_mode: Res<RunMode>,
+    high_scores: Res<HighScores>,
+) {
+    BackgroundKind::SkyOnly.spawn(default(), menu_root.eid(), &mut commands);
+    commands
+        .spawn((
+            Name::new("character_preview"),
+            CharacterPreview {
+                character: *current,
+            },
+            spat_tran(0.0, 30.0, 100.0),
+            multi!(anim_man!({
+                path: current.def().fly_path,
+                size: (24, 24),
+                length: 3,
+                fps: 16.0,
+                color: current.def().color,
+            })
+            .with_scale(Vec2::ONE * 2.0)
+            .with_render_layers(MenuCamera::render_layers())),
+        ))
+        .set_parent(menu_root.eid());
+    commands
+        .spawn((
+            Name::new("character_text"),
+            CharacterText,
+            Text2dBundle {
+                text: Text::from_section(
+                    character_text(*current),
+                    TextStyle {
+                        font_size: 11.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                transform: Transform::from_translation(Vec3::new(0.0, -35.0, 100.0)),
+                ..default()
+            },
+            MenuCamera::render_layers(),
+        ))
+        .set_parent(menu_root.eid());
+    commands
+        .spawn((
+            Name::new("character_controls"),
+            Text2dBundle {
+                text: Text::from_section(
+                    "< A / D >    W / S for mode    Enter to fly",
+                    TextStyle {
+                        font_size: 9.0,
+                        color: Color::srgb_u8(245, 219, 203),
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                transform: Transform::from_translation(Vec3::new(0.0, -80.0, 100.0)),
+                ..default()
+            },
+            MenuCamera::render_layers(),
+        ))
+        .set_parent(menu_root.eid());
+    commands
+        .spawn((
+            Name::new("run_mode_text"),
+            RunModeText,
+            Text2dBundle {
+                text: Text::from_section(
+                    run_mode_text(*run_mode, &high_scores),
+                    TextStyle {
+                        font_size: 9.0,
+                        color: Color::srgb_u8(245, 219, 203),
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Right),
+                text_anchor: Anchor::TopRight,
+                transform: Transform::from_translation(Vec3::new(152.0, 82.0, 100.0)),
+                ..default()
+            },
+            MenuCamera::render_layers(),
+        ))
+        .set_parent(menu_root.eid());
+}
+
+fn destroy_character_select(mut commands: Commands, menu_root: Res<MenuRoot>) {
+    commands.entity(menu_root.eid()).despawn_descendants();
+}
+
+fn update_character_select(
+    mut input: EventReader<NonGameInput>,
+    current_transition: Res<State<MetaTransitionState>>,
+    mut next_transition: ResMut<NextState<MetaTransitionState>>,
+    mut preview: Query<(&mut CharacterPreview, &mut MultiAnimationManager)>,
+    mut text: Query<&mut Text, With<CharacterText>>,
+    mut current: ResMut<BirdCharacter>,
+    mut flight_consts: ResMut<BirdFlightConsts>,
+    mut permanent_skills: ResMut<PermanentSkill>,
+    run_mode: Res<RunMode>,
+    mut commands: Commands,
+) {
+    let Some(input) = input.read().last() else {
+        return;
+    };
+    let (Ok((mut preview, mut multi)), Ok(mut text)) =
+        (preview.get_single_mut(), text.get_single_mut())
+    else {
+        return;
+    };
+    let dir = match input {
+        NonGameInput::Continue => {
+            if matches!(current_transition.get(), MetaTransitionState::Stable) {
+                preview
+                    .character
+                    .choose(&mut current, &mut flight_consts, &mut permanent_skills);
+                commands.spawn(SoundEffect::universal(
+                    "sound_effects/choose_upgrade.ogg",
+                    0.2,
+                ));
+                next_transition.set(
+                    TransitionKind::FadeToBlack
+                        .to_meta_transition_state(1.0, run_mode.first_room()),
+                );
+            }
+            return;
+        }
+        NonGameInput::Left => -1,
+        NonGameInput::Right => 1,
+        // Mode, see `update_run_mode`
+        NonGameInput::Up | NonGameInput::Down => return,
+    };
+    preview.character = preview.character.cycle(dir);
+    let def = preview.character.def();
+    let manager = multi.single_mut();
+    for node in manager.map.values_mut() {
+        node.sprite.path = def.fly_path.to_string();
+        node.sprite.color = def.color;
+    }
+    manager.force_reset(&mut commands);
+    text.sections[0].value = character_text(preview.character);
+    commands.spawn(SoundEffect::universal("sound_effects/fly_spot.ogg", 0.2));
+}
+
+fn update_run_mode(
+    mut input: EventReader<NonGameInput>,
+    mut run_mode: ResMut<RunMode>,
+    high_scores: Res<HighScores>,
+    mut text: Query<&mut Text, With<RunModeText>>,
+    mut commands: Commands,
+) {
+    // There's only two modes, so up and down both flip it
+    let Some(NonGameInput::Up | NonGameInput::Down) = input.read().last() else {
+        return;
+    };
+    run_mode.toggle();
+    if let Ok(mut text) = text.get_single_mut() {
+        text.sections[0].value = run_mode_text(*run_mode, &high_scores);
+    }
+    commands.spawn(SoundEffect::universal("sound_effects/fly_spot.ogg", 0.2));
+}
+
+pub(super) fn register_character_select(app: &mut App) {
+    app.add_systems(
+        OnEnter(MenuState::CharacterSelect.to_meta_state()),
+        setup_character_select,
+    );
+    app.add_systems(
+        OnExit(MenuState::CharacterSelect.to_meta_state()),
+        destroy_character_select,
+    );
+    app.add_systems(
+        Update,
+        (update_character_select, update_run_mode)
+            .run_if(in_state(MenuState::CharacterSelect.to_meta_state())),
+    );
+}
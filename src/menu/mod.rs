@@ -1,11 +1,13 @@
 use crate::prelude::*;
 
+mod character_select;
 mod studio;
 mod title;
 
 pub(super) struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
+        character_select::register_character_select(app);
         studio::register_studio(app);
         title::register_title(app);
     }
@@ -111,12 +111,13 @@ fn update_title(
                 if matches!(current_transition.get(), MetaTransitionState::Stable) {
                     next_transition.set(
                         TransitionKind::FadeToBlack.to_meta_transition_state(
-                            1.0,
-                            TutorialState::LearnToFly.to_meta_state(),
+                            0.5,
+                            MenuState::CharacterSelect.to_meta_state(),
                         ),
                     );
                 }
             }
+            _ => (),
         }
     }
     for (eid, mut dyno, gt) in &mut stuck_birds {
@@ -101,6 +101,47 @@ impl StickyPhysicsBundle {
     }
 }
 
+#[derive(Bundle)]
+pub struct SpikesPhysicsBundle {
+    bounds: Bounds,
+    trigger_rx: TriggerReceiver,
+    spatial: SpatialBundle,
+}
+impl SpikesPhysicsBundle {
+    pub fn new(pos: Vec2, angle: f32, length: f32, thickness: f32) -> Self {
+        let mut tran = Transform::from_translation(pos.extend(ZIX_STICKY));
+        tran.set_angle(angle);
+        Self {
+            bounds: Bounds::from_shape(Shape::Polygon {
+                points: simple_rect(length, thickness),
+            }),
+            trigger_rx: TriggerReceiver::from_kind(TriggerKind::Hazard),
+            spatial: SpatialBundle::from_transform(tran),
+        }
+    }
+}
+
+/// The box a laser fires out of. Hard, so nothing sticks to it.
+#[derive(Bundle)]
+pub struct LaserEmitterPhysicsBundle {
+    bounds: Bounds,
+    statics: StaticProvider,
+    spatial: SpatialBundle,
+}
+impl LaserEmitterPhysicsBundle {
+    pub fn new(pos: Vec2, angle: f32, size: f32) -> Self {
+        let mut tran = Transform::from_translation(pos.extend(ZIX_STICKY));
+        tran.set_angle(angle);
+        Self {
+            bounds: Bounds::from_shape(Shape::Polygon {
+                points: simple_rect(size, size),
+            }),
+            statics: StaticProvider::from_kind(StaticProviderKind::Normal),
+            spatial: SpatialBundle::from_transform(tran),
+        }
+    }
+}
+
 #[derive(Bundle)]
 pub struct BulletPhysicsBundle {
     dyno_tran: DynoTran,
@@ -128,6 +169,11 @@ impl BulletPhysicsBundle {
             )),
         }
     }
+
+    pub fn with_receiver_kind(mut self, kind: StaticReceiverKind) -> Self {
+        self.static_rx = StaticReceiver::from_kind(kind);
+        self
+    }
 }
 
 #[derive(Bundle)]
@@ -179,67 +225,63 @@ impl GoNextTriggerPhysicsBundle {
 }
 
 #[derive(Bundle)]
-pub struct SimpGuidePhysicsBundle {
-    dyno_tran: DynoTran,
+pub struct PropTriggerPhysicsBundle {
     bounds: Bounds,
+    trigger_rx: TriggerReceiver,
     spatial: SpatialBundle,
 }
-impl SimpGuidePhysicsBundle {
-    pub fn new(pos: Vec2, radius: f32) -> Self {
-        let mut rng = rand::thread_rng();
-        let z_nudge = rng.r#gen::<f32>();
+impl PropTriggerPhysicsBundle {
+    pub fn new(pos: Vec2, points: Vec<Vec2>) -> Self {
         Self {
-            dyno_tran: DynoTran { vel: Vec2::ZERO },
-            bounds: Bounds::from_shape(Shape::Circle { radius }),
-            spatial: spat_tran(pos.x, pos.y, ZIX_SIMP + z_nudge),
+            bounds: Bounds::from_shape(Shape::Polygon { points }),
+            trigger_rx: TriggerReceiver::from_kind(TriggerKind::Prop),
+            spatial: spat_tran(pos.x, pos.y, ZIX_STICKY),
         }
     }
 }
 
 #[derive(Bundle)]
-pub struct SpewGuidePhysicsBundle {
-    dyno_tran: DynoTran,
-    bounds: Bounds,
+pub struct ObjectiveTriggerPhysicsBundle {
+    pub bounds: Bounds,
+    trigger_rx: TriggerReceiver,
     spatial: SpatialBundle,
 }
-impl SpewGuidePhysicsBundle {
+impl ObjectiveTriggerPhysicsBundle {
     pub fn new(pos: Vec2, radius: f32) -> Self {
-        let mut rng = rand::thread_rng();
-        let z_nudge = rng.r#gen::<f32>();
         Self {
-            dyno_tran: DynoTran { vel: Vec2::ZERO },
             bounds: Bounds::from_shape(Shape::Circle { radius }),
-            spatial: spat_tran(pos.x, pos.y, ZIX_SIMP + z_nudge),
+            trigger_rx: TriggerReceiver::from_kind(TriggerKind::Objective),
+            spatial: spat_tran(pos.x, pos.y, ZIX_TUTORIAL_TRIGGER),
         }
     }
 }
 
 #[derive(Bundle)]
-pub struct SimpHurtboxPhysicsBundle {
+pub struct SimpGuidePhysicsBundle {
+    dyno_tran: DynoTran,
     bounds: Bounds,
-    trigger_rx: TriggerReceiver,
     spatial: SpatialBundle,
-    dyno_rot: DynoRot,
 }
-impl SimpHurtboxPhysicsBundle {
-    pub fn new(radius: f32) -> Self {
+impl SimpGuidePhysicsBundle {
+    pub fn new(pos: Vec2, radius: f32) -> Self {
+        let mut rng = rand::thread_rng();
+        let z_nudge = rng.r#gen::<f32>();
         Self {
+            dyno_tran: DynoTran { vel: Vec2::ZERO },
             bounds: Bounds::from_shape(Shape::Circle { radius }),
-            trigger_rx: TriggerReceiver::from_kind(TriggerKind::SimpBody),
-            spatial: default(),
-            dyno_rot: DynoRot { rot: 0.0 },
+            spatial: spat_tran(pos.x, pos.y, ZIX_SIMP + z_nudge),
         }
     }
 }
 
 #[derive(Bundle)]
-pub struct SpewHurtboxPhysicsBundle {
+pub struct SimpHurtboxPhysicsBundle {
     bounds: Bounds,
     trigger_rx: TriggerReceiver,
     spatial: SpatialBundle,
     dyno_rot: DynoRot,
 }
-impl SpewHurtboxPhysicsBundle {
+impl SimpHurtboxPhysicsBundle {
     pub fn new(radius: f32) -> Self {
         Self {
             bounds: Bounds::from_shape(Shape::Circle { radius }),
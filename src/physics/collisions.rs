@@ -11,12 +11,14 @@ pub(super) fn handle_bullet_collisions(
             &mut MultiAnimationManager,
             &StaticReceiver,
             &TriggerReceiver,
+            &mut DynoTran,
+            Option<&mut Bullet>,
         ),
         With<AnyBullet>,
     >,
     mut commands: Commands,
 ) {
-    for (mut multi, static_receiver, trigger_receiver) in &mut bullets {
+    for (mut multi, static_receiver, trigger_receiver, mut dyno_tran, bullet) in &mut bullets {
         if static_receiver.collisions.is_empty() {
             continue;
         }
@@ -26,6 +28,21 @@ pub(super) fn handle_bullet_collisions(
                     // We've already started doing stuff to this bullet
                     continue;
                 }
+                if let Some(mut bullet) = bullet {
+                    if bullet.bounces_left > 0 {
+                        bullet.bounces_left -= 1;
+                        continue;
+                    }
+                }
+                dyno_tran.vel = Vec2::ZERO;
+                multi.manager_mut("core").reset_key_with_points("explode", &mut commands);
+            }
+            TriggerKind::BulletBad => {
+                // Enemy bullets don't bounce, they just pop
+                if multi.manager("core").get_key().as_str() != "solid" {
+                    continue;
+                }
+                dyno_tran.vel = Vec2::ZERO;
                 multi.manager_mut("core").reset_key_with_points("explode", &mut commands);
             }
             _ => panic!("Unsupported handle_bullet_collisions kind. How did AnyBullet end up on this component?")
@@ -273,6 +273,9 @@ fn resolve_static_collisions(
                     dyno_tran.vel += Vec2::new(mvmt.y, -mvmt.x) * mult as f32;
                 }
             }
+            (_, StaticReceiverKind::Bounce) => {
+                dyno_tran.vel = bounce_with_friction(dyno_tran.vel, 0.8, 0.0);
+            }
             (StaticProviderKind::Normal, StaticReceiverKind::Normal) => {
                 dyno_tran.vel = bounce_with_friction(dyno_tran.vel, 0.2, 0.03);
             }
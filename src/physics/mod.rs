@@ -7,6 +7,7 @@ pub mod bundles;
 pub mod collisions;
 pub mod dyno;
 mod logic;
+pub mod spatial;
 pub mod statics;
 pub mod triggers;
 
@@ -14,6 +15,7 @@ pub use bounds::*;
 pub use bundles::*;
 pub use collisions::*;
 pub use dyno::*;
+pub use spatial::*;
 pub use statics::*;
 pub use triggers::*;
 
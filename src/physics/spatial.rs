@@ -0,0 +1,39 @@
+use crate::prelude::*;
+
+/// Buckets points into square cells so "who's near me" doesn't have to check everyone.
+/// Cheap enough to rebuild from scratch every frame.
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+}
+impl SpatialHash {
+    /// `cell_size` should be about the radius you plan on querying with
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(1.0),
+            cells: default(),
+        }
+    }
+
+    fn cell(&self, pos: Vec2) -> IVec2 {
+        (pos / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn insert(&mut self, eid: Entity, pos: Vec2) {
+        let cell = self.cell(pos);
+        self.cells.entry(cell).or_default().push((eid, pos));
+    }
+
+    /// Everything within `radius` of `pos` (including whatever is at `pos`)
+    pub fn within(&self, pos: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = self.cell(pos - Vec2::splat(radius));
+        let max = self.cell(pos + Vec2::splat(radius));
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |(_, other)| other.distance_squared(pos) <= radius * radius)
+    }
+}
@@ -39,6 +39,8 @@ pub enum StaticReceiverKind {
     /// Will try to go around things. That is, when there's a collision it will adjust it's velocity either
     /// left or right to try and go around it
     GoAround { mult: i32 },
+    /// Bounces off of everything (even sticky things) without losing much speed.
+    Bounce,
 }
 
 /// Marks a component as something that should interact with statics. Should be attached to entities with `Bounds`.
@@ -17,6 +17,14 @@ pub enum TriggerKind {
     Heart,
     /// The thing to shoot to go to the next room
     GoNext,
+    /// Something an enemy dropped
+    Loot,
+    /// Something an encounter objective cares about (pickups, the egg)
+    Objective,
+    /// Spikes and anything else in the room that hurts to touch
+    Hazard,
+    /// Something in the room that breaks when shot
+    Prop,
 }
 
 /// Marks an object as being a "triggerable" physics object. Should be attached to entities with `Bounds`.
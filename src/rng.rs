@@ -0,0 +1,197 @@
+//! Every run has a seed, and everything random about a run should come from it.
+//! Each subsystem gets its own stream so, say, an extra loot roll can't change the next room's layout.
+//! Streams are restarted at the top of every room, so a seed plus a room is enough to reproduce a bug.
+//! Purely cosmetic stuff (camera shake, rattles, z nudges, title screen birds) still uses `thread_rng`, it can't change what happens.
+//! Start a run from a seed with `cargo run -- --seed <seed>`.
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    /// Platforms, spawner positions, anything placed when a room is made
+    Layout,
+    /// What spawners spawn, where and when
+    Spawns,
+    /// Drops and hearts
+    Loot,
+    /// Which upgrades get offered
+    Upgrades,
+    /// The run's map
+    Map,
+    /// What each encounter asks of the bird, and where its pickups go
+    Objective,
+    /// Enemy stats, bullet patterns, cooldowns and how long their brains stay in each state
+    Enemies,
+}
+impl RngStream {
+    const ALL: [Self; 7] = [
+        Self::Layout,
+        Self::Spawns,
+        Self::Loot,
+        Self::Upgrades,
+        Self::Map,
+        Self::Objective,
+        Self::Enemies,
+    ];
+
+    /// Mixed into the seed so each stream comes out different
+    fn salt(&self) -> u64 {
+        match self {
+            Self::Layout => 0x9E37_79B9_7F4A_7C15,
+            Self::Spawns => 0xC2B2_AE3D_27D4_EB4F,
+            Self::Loot => 0x1656_67B1_9E37_79F9,
+            Self::Upgrades => 0x27D4_EB2F_1656_67C5,
+            Self::Map => 0x85EB_CA77_C2B2_AE63,
+            Self::Objective => 0x94D0_49BB_1331_11EB,
+            Self::Enemies => 0xBF58_476D_1CE4_E5B9,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct RunRng {
+    seed: u64,
+    /// Set from the command line, used by the next run that starts (and only that one)
+    forced_seed: Option<u64>,
+    streams: HashMap<RngStream, StdRng>,
+}
+impl RunRng {
+    fn new(seed: u64, forced_seed: Option<u64>) -> Self {
+        let mut result = Self {
+            seed,
+            forced_seed,
+            streams: default(),
+        };
+        result.enter_room(0);
+        result
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Picks the seed for a brand new run
+    pub fn start_run(&mut self) {
+        self.seed = self
+            .forced_seed
+            .take()
+            .unwrap_or_else(|| thread_rng().gen());
+    }
+
+    /// Restarts every stream for the given room. Same seed + same room = same room.
+    pub fn enter_room(&mut self, room_ix: u32) {
+        for stream in RngStream::ALL {
+            let seed = self
+                .seed
+                .wrapping_mul(stream.salt())
+                .wrapping_add(room_ix as u64)
+                .rotate_left(17)
+                ^ stream.salt();
+            self.streams.insert(stream, StdRng::seed_from_u64(seed));
+        }
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        self.streams
+            .entry(stream)
+            .or_insert_with(|| StdRng::seed_from_u64(stream.salt()))
+    }
+}
+impl Default for RunRng {
+    fn default() -> Self {
+        let mut args = std::env::args();
+        let mut forced_seed = None;
+        while let Some(arg) = args.next() {
+            if arg == "--seed" {
+                match args.next().map(|seed| seed.parse::<u64>()) {
+                    Some(Ok(seed)) => forced_seed = Some(seed),
+                    _ => warn!("--seed needs a number after it"),
+                }
+            }
+        }
+        Self::new(thread_rng().gen(), forced_seed)
+    }
+}
+
+pub(super) struct RngPlugin;
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunRng>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw(rng: &mut RunRng, stream: RngStream) -> Vec<u32> {
+        (0..8).map(|_| rng.stream(stream).gen()).collect()
+    }
+
+    #[test]
+    fn same_seed_same_room_same_rolls() {
+        let mut a = RunRng::new(1234, None);
+        let mut b = RunRng::new(1234, None);
+        a.enter_room(5);
+        b.enter_room(5);
+        for stream in RngStream::ALL {
+            assert_eq!(draw(&mut a, stream), draw(&mut b, stream));
+        }
+    }
+
+    #[test]
+    fn rooms_get_different_rolls() {
+        let mut rng = RunRng::new(1234, None);
+        rng.enter_room(1);
+        let first = draw(&mut rng, RngStream::Layout);
+        rng.enter_room(2);
+        assert_ne!(first, draw(&mut rng, RngStream::Layout));
+    }
+
+    #[test]
+    fn seeds_get_different_rolls() {
+        let mut a = RunRng::new(1, None);
+        let mut b = RunRng::new(2, None);
+        a.enter_room(3);
+        b.enter_room(3);
+        assert_ne!(
+            draw(&mut a, RngStream::Spawns),
+            draw(&mut b, RngStream::Spawns)
+        );
+    }
+
+    #[test]
+    fn streams_dont_share_rolls() {
+        let mut rng = RunRng::new(1234, None);
+        rng.enter_room(1);
+        let rolls = RngStream::ALL.map(|stream| draw(&mut rng, stream));
+        for (ix, a) in rolls.iter().enumerate() {
+            for b in &rolls[ix + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn entering_a_room_again_starts_it_over() {
+        let mut rng = RunRng::new(1234, None);
+        rng.enter_room(4);
+        let first = draw(&mut rng, RngStream::Loot);
+        // Using up some other stream can't change anything either
+        draw(&mut rng, RngStream::Upgrades);
+        rng.enter_room(4);
+        assert_eq!(first, draw(&mut rng, RngStream::Loot));
+    }
+
+    #[test]
+    fn forced_seed_is_used_once() {
+        let mut rng = RunRng::new(1, Some(99));
+        rng.start_run();
+        assert_eq!(rng.seed(), 99);
+        rng.start_run();
+        // Could be 99 by chance, but not really
+        assert_ne!(rng.seed(), 99);
+    }
+}
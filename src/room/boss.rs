@@ -0,0 +1,76 @@
+use crate::prelude::*;
+
+/// Boss rooms are emptier than normal ones, the boss needs space to throw its weight around
+pub(super) fn create_boss_room(
+    mut commands: Commands,
+    room_root: Res<RoomRoot>,
+    mut music_manager: ResMut<MusicManager>,
+    boss_state: Res<State<BossState>>,
+    ephemeral_skills: Res<EphemeralSkill>,
+    character: Res<BirdCharacter>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    // Clear the room just to be sure
+    commands.entity(room_root.eid()).despawn_descendants();
+
+    let boss_state = boss_state.get();
+    // Way past any encounter index so boss rooms get their own streams
+    run_rng.enter_room(1000 + boss_state.level);
+    music_manager.fade_to_song(MusicKind::BossBattle);
+
+    // Background and room border
+    BackgroundKind::Forest.spawn(default(), room_root.eid(), &mut commands);
+    commands
+        .spawn(HardPlatformBundle::around_room())
+        .set_parent(room_root.eid());
+
+    // Get all the placements
+    let bot_left = -(IDEAL_VEC_f32 / 2.0 - Vec2::ONE * 6.0);
+    let top_right = -bot_left;
+    let bird_pos = Vec2::new(0.0, -50.0);
+    let boss_pos = Vec2::new(0.0, 40.0);
+    let avoid = vec![
+        (Shape::Circle { radius: 7.0 }, bird_pos, 0.0),
+        (Shape::Circle { radius: 40.0 }, boss_pos, 0.0),
+    ];
+    let circle_placements = LayoutGen::new(6, bot_left, top_right)
+        .with_radius(10.0, 24.0)
+        .with_rot(-3.0, 3.0)
+        .with_gap(30.0)
+        .reachable_from(bird_pos)
+        .generate(run_rng.stream(RngStream::Layout), &avoid);
+
+    // Spawn the circles
+    for (ix, (shape, pos, rot)) in circle_placements.into_iter().enumerate() {
+        commands
+            .spawn(StickyPlatformBundle::new(
+                format!("shape_{ix}").as_str(),
+                pos,
+                shape,
+            ))
+            .insert(DynoRot { rot })
+            .set_parent(room_root.eid());
+    }
+
+    // Spawn the boss!
+    BossBundle::spawn(boss_pos, boss_state.level, &mut commands, room_root.eid());
+
+    // Spawn the bird! It has to kill the boss and everything the boss calls in
+    commands
+        .spawn(BirdBundle::new(
+            *character,
+            bird_pos,
+            default(),
+            ephemeral_skills.get_num_launches(),
+            ephemeral_skills.get_num_bullets(),
+            1 + BossPhase::total_minions(boss_state.level),
+        ))
+        .set_parent(room_root.eid());
+}
+
+pub(super) fn register_bosses(app: &mut App) {
+    app.add_systems(
+        OnEnter(EncounterProgress::Entering),
+        create_boss_room.run_if(state_exists::<BossState>),
+    );
+}
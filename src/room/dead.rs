@@ -12,16 +12,40 @@ fn setup_dead(mut commands: Commands, room_root: Res<RoomRoot>) {
         .set_parent(room_root.eid());
 }
 
+/// So the run can be shared (or reported)
+fn show_seed(
+    mut help_text: ResMut<HelpText>,
+    run_rng: Res<RunRng>,
+    run_mode: Res<RunMode>,
+    score: Res<RunScore>,
+    high_scores: Res<HighScores>,
+) {
+    if run_mode.is_endless() {
+        help_text.set(&format!(
+            "Score: {}  Best: {}\nRun seed: {}",
+            score.total(),
+            high_scores.best().unwrap_or_default(),
+            run_rng.seed()
+        ));
+    } else {
+        help_text.set(&format!("Run seed: {}", run_rng.seed()));
+    }
+}
+
+fn hide_seed(mut help_text: ResMut<HelpText>) {
+    help_text.clear();
+}
+
 fn drop_egg(mut commands: Commands, room_root: Res<RoomRoot>) {
     commands
         .spawn(EggChoice::new(Vec2::new(-110.0, 140.0)))
         .set_parent(room_root.eid());
 }
 
-fn spawn_upgrades(commands: &mut Commands, room_root: &RoomRoot) {
+fn spawn_upgrades(commands: &mut Commands, room_root: &RoomRoot, rng: &mut impl Rng) {
     let mut kinds = vec![];
     while kinds.len() < 2 {
-        let kind = UpgradeKind::new(0.3, 0.1);
+        let kind = UpgradeKind::new(0.3, 0.1, rng);
         if kinds.is_empty() || std::mem::discriminant(&kinds[0]) != std::mem::discriminant(&kind) {
             kinds.push(kind);
         }
@@ -52,6 +76,8 @@ fn update(
     upgrade_applied: Query<&UpgradeButton, With<UpgradeButtonApplied>>,
     mut next_transition_state: ResMut<NextState<MetaTransitionState>>,
     room_root: Res<RoomRoot>,
+    mut run_rng: ResMut<RunRng>,
+    run_mode: Res<RunMode>,
 ) {
     let mut data = data.single_mut();
 
@@ -80,7 +106,11 @@ fn update(
             // This is like, unbelievably cursed code. Like 4 rounds of hacky changes.
             // I think I could remove it, but it works, so why would I?
             data.time_since_egg_hit_ground = -1.0;
-            spawn_upgrades(&mut commands, &room_root);
+            spawn_upgrades(
+                &mut commands,
+                &room_root,
+                run_rng.stream(RngStream::Upgrades),
+            );
         }
         if let Ok(upgrade_applied) = upgrade_applied.get_single() {
             // We've applied the upgrade! Yay!
@@ -90,7 +120,7 @@ fn update(
                 multi.manager_mut(&multi_key).set_key("bird", &mut commands);
                 next_transition_state.set(
                     TransitionKind::FadeToBlack
-                        .to_meta_transition_state(1.0, RoomState::Dead.next_room().to_meta_state()),
+                        .to_meta_transition_state(1.0, run_mode.after_death()),
                 );
             }
         }
@@ -100,8 +130,13 @@ fn update(
 pub(super) fn register_dead(app: &mut App) {
     app.add_systems(
         OnEnter(RoomState::Dead.to_meta_state()),
-        (setup_dead, drop_egg),
+        (
+            setup_dead,
+            drop_egg,
+            show_seed.after(super::endless::record_score),
+        ),
     );
+    app.add_systems(OnExit(RoomState::Dead.to_meta_state()), hide_seed);
     app.add_systems(
         Update,
         update
@@ -0,0 +1,100 @@
+//! How rooms scale with `EncounterState::difficulty`. Endless mode rides these forever, so every curve has a cap.
+
+/// `base + scale * floor(difficulty^power)`, rounded down and capped at `max`.
+/// The power gets floored on its own first, that's how the numbers always worked.
+#[derive(Debug, Clone, Copy)]
+pub struct DifficultyCurve {
+    base: f32,
+    scale: f32,
+    power: f32,
+    max: usize,
+}
+impl DifficultyCurve {
+    pub const fn new(base: f32, scale: f32, power: f32, max: usize) -> Self {
+        Self {
+            base,
+            scale,
+            power,
+            max,
+        }
+    }
+
+    pub fn at(&self, difficulty: u32) -> usize {
+        let grown = (difficulty as f32).powf(self.power).floor();
+        ((self.base + self.scale * grown) as usize).min(self.max)
+    }
+
+    // Caps sit a bit past where a map run is by act three, so they only kick in deep into a run
+
+    /// Enemies in a steelbeak room
+    pub const STEELBEAK_ENEMIES: Self = Self::new(3.0, 3.0, 2.0, 30);
+    /// Biggest batch a steelbeak spawner sends at once (smallest is 6)
+    pub const STEELBEAK_BATCH: Self = Self::new(6.0, 3.0, 2.0, 33);
+    pub const BOTH_SIMPS: Self = Self::new(4.0, 3.0, 1.7, 120);
+    pub const BOTH_SPEWS: Self = Self::new(4.0, 2.0, 1.3, 40);
+    /// Spitbeaks are rare, a room full of them would be a bullet hell
+    pub const BOTH_SPITS: Self = Self::new(1.0, 0.5, 1.0, 8);
+    /// Biggest batch in a mixed room (smallest is 4)
+    pub const BOTH_BATCH: Self = Self::new(6.0, 1.0, 1.4, 30);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What the room code used to compute inline, before the curves
+    #[test]
+    fn matches_the_old_formulas() {
+        for d in 1..=8u32 {
+            let df = d as f32;
+            // Steelbeak rooms never go past 3
+            if d <= 3 {
+                assert_eq!(
+                    DifficultyCurve::STEELBEAK_ENEMIES.at(d),
+                    3 + 3 * d.pow(2) as usize
+                );
+                assert_eq!(
+                    DifficultyCurve::STEELBEAK_BATCH.at(d),
+                    6 + 3 * d.pow(2) as usize
+                );
+            }
+            assert_eq!(
+                DifficultyCurve::BOTH_SIMPS.at(d),
+                4 + 3 * df.powf(1.7) as usize
+            );
+            assert_eq!(
+                DifficultyCurve::BOTH_SPEWS.at(d),
+                4 + 2 * df.powf(1.3) as usize
+            );
+            assert_eq!(DifficultyCurve::BOTH_SPITS.at(d), 1 + d as usize / 2);
+            assert_eq!(DifficultyCurve::BOTH_BATCH.at(d), 6 + df.powf(1.4) as usize);
+        }
+    }
+
+    #[test]
+    fn never_shrinks() {
+        let curves = [
+            DifficultyCurve::STEELBEAK_ENEMIES,
+            DifficultyCurve::STEELBEAK_BATCH,
+            DifficultyCurve::BOTH_SIMPS,
+            DifficultyCurve::BOTH_SPEWS,
+            DifficultyCurve::BOTH_SPITS,
+            DifficultyCurve::BOTH_BATCH,
+        ];
+        for curve in curves {
+            for d in 0..200 {
+                assert!(curve.at(d + 1) >= curve.at(d));
+            }
+        }
+    }
+
+    #[test]
+    fn caps_hold_in_endless() {
+        let curve = DifficultyCurve::new(1.0, 1.0, 2.0, 10);
+        assert_eq!(curve.at(2), 5);
+        assert_eq!(curve.at(3), 10);
+        assert_eq!(curve.at(4), 10);
+        assert_eq!(DifficultyCurve::BOTH_SIMPS.at(1000), 120);
+        assert_eq!(DifficultyCurve::BOTH_SPITS.at(u32::MAX), 8);
+    }
+}
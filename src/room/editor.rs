@@ -0,0 +1,553 @@
+//! A dev-only editor for `RoomDef`s. F2 from the title screen to get in (and out).
+//! What's on screen while editing is the actual room, just with physics off, so it looks the same when you play it.
+//!
+//! - `1`-`4`: circle, polygon, spawner and bird tools. Left click uses the tool.
+//! - `[`/`]`: circle radius. `Enter` closes a polygon, `Escape` throws it away.
+//! - `H`: sticky, hard or crumbling for the next platform. `N`: start a new spawner.
+//! - Right drag on a platform: spin it (`DynoRot`). Drag right for clockwise.
+//! - `Backspace`: undo the last thing for the current tool.
+//! - `L`: load the next set piece. `R`: rename (type, then `Enter`). New rooms get a timestamped name.
+//! - `Ctrl+S`: save to `assets/rooms/<name>.room.json`. Overwriting a file that's already there takes a second `Ctrl+S`.
+//!   Saved rooms go straight into the set piece pool, no need to list them anywhere.
+//! - `P`: playtest, and `P` again (or dying) to get back to editing.
+
+use bevy::input::{
+    keyboard::{Key, KeyboardInput},
+    ButtonState,
+};
+use bevy::utils::SystemTime;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditorTool {
+    Circle,
+    Polygon,
+    Spawner,
+    Bird,
+}
+
+#[derive(Resource)]
+struct EditorRoom {
+    def: RoomDef,
+    tool: EditorTool,
+    kind: PlatformKind,
+    radius: f32,
+    /// Points of the polygon being placed, in world space, in the order they were clicked
+    pending: Vec<Vec2>,
+    /// Which platform is being spun with the right mouse button, and where the drag started
+    spinning: Option<(usize, Vec2)>,
+    /// The preview needs to be respawned
+    dirty: bool,
+    /// Where `L` got to in `RoomDefs::names`
+    load_ix: usize,
+    /// The name being typed, while renaming
+    renaming: Option<String>,
+    /// `Ctrl+S` was pressed once on a room that already has a file, the next one overwrites it
+    confirm_overwrite: bool,
+}
+impl EditorRoom {
+    const MIN_RADIUS: f32 = 2.0;
+    /// Rot per unit of mouse drag
+    const SPIN_PER_PIXEL: f32 = 0.02;
+    /// Ideal pixels per second
+    const PAN_SPEED: f32 = 160.0;
+
+    fn empty_def() -> RoomDef {
+        RoomDef {
+            name: Self::fresh_name(),
+            encounters: vec![],
+            min_difficulty: 0,
+            background: BackgroundKind::Forest,
+            border: true,
+            size: IDEAL_VEC_f32.to_array(),
+            bird: [0.0, 0.0],
+            platforms: vec![],
+            spawners: vec![],
+            waves: None,
+            triggers: vec![],
+            hazards: vec![],
+            props: vec![],
+        }
+    }
+
+    /// Unique enough that a new room won't land on top of an old one
+    fn fresh_name() -> String {
+        let secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        format!("room_{secs}")
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.def.name = name;
+        self.confirm_overwrite = false;
+    }
+
+    /// Simps to start with, change the table in the file
+    fn new_spawner() -> SpawnerDef {
+        SpawnerDef {
+            table: vec![("simp".to_string(), 1.0)],
+            poses: vec![],
+            batch_sizes: vec![3, 3],
+        }
+    }
+
+    fn spin_from_drag(start: Vec2, now: Vec2) -> f32 {
+        // Screen-right should read as clockwise, which is negative rot
+        -(now.x - start.x) * Self::SPIN_PER_PIXEL
+    }
+
+    /// The platform under the mouse, if any. Rough, but good enough for grabbing things.
+    fn platform_at(&self, pos: Vec2) -> Option<usize> {
+        self.def
+            .platforms
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, platform)| {
+                let dist = pos.distance(Vec2::from_array(platform.pos));
+                let reach = match platform.shape.to_shape() {
+                    Shape::Circle { radius } => radius,
+                    Shape::Polygon { points } => points
+                        .iter()
+                        .map(|point| point.length())
+                        .fold(0.0, f32::max),
+                };
+                (dist <= reach).then_some((ix, dist))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(ix, _)| ix)
+    }
+
+    /// Turns the clicked points into a platform centered on their middle, flipping them if they came in counterclockwise
+    fn close_polygon(&mut self) {
+        if self.pending.len() < 3 {
+            return;
+        }
+        let center = self.pending.iter().sum::<Vec2>() / self.pending.len() as f32;
+        let mut points = self
+            .pending
+            .drain(..)
+            .map(|point| point - center)
+            .collect::<Vec<_>>();
+        // Shoelace, positive means counterclockwise
+        let area = points
+            .to_lines()
+            .iter()
+            .map(|[p1, p2]| p1.x * p2.y - p2.x * p1.y)
+            .sum::<f32>();
+        if area > 0.0 {
+            points.reverse();
+        }
+        self.def.platforms.push(PlatformDef {
+            kind: self.kind,
+            pos: center.to_array(),
+            shape: ShapeDef::Polygon {
+                points: points.into_iter().map(|point| point.to_array()).collect(),
+            },
+            rot: 0.0,
+        });
+        self.dirty = true;
+    }
+
+    fn help(&self) -> String {
+        if let Some(draft) = &self.renaming {
+            return format!("Name: {draft}_\nEnter to keep, Escape to cancel");
+        }
+        if self.confirm_overwrite {
+            return format!(
+                "{} already has a file\nCtrl+S again to overwrite it, R to rename",
+                self.def.name
+            );
+        }
+        let tool = match self.tool {
+            EditorTool::Circle => format!("Circle (r={})", self.radius),
+            EditorTool::Polygon => format!("Polygon ({} points)", self.pending.len()),
+            EditorTool::Spawner => format!("Spawner #{}", self.def.spawners.len().max(1)),
+            EditorTool::Bird => "Bird".to_string(),
+        };
+        format!(
+            "{}: {tool}, {:?}\n1-4 tools, H kind, arrows pan, P play, R rename, Ctrl+S save",
+            self.def.name, self.kind
+        )
+    }
+}
+impl Default for EditorRoom {
+    fn default() -> Self {
+        Self {
+            def: Self::empty_def(),
+            tool: EditorTool::Circle,
+            kind: PlatformKind::Sticky,
+            radius: 12.0,
+            pending: vec![],
+            spinning: None,
+            dirty: true,
+            load_ix: 0,
+            renaming: None,
+            confirm_overwrite: false,
+        }
+    }
+}
+
+fn toggle_editor(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    meta_state: Res<State<MetaState>>,
+    mut next_meta_state: ResMut<NextState<MetaState>>,
+    mut commands: Commands,
+    room_root: Res<RoomRoot>,
+    mut help_text: ResMut<HelpText>,
+) {
+    if !keyboard.just_pressed(KeyCode::F2) {
+        return;
+    }
+    if meta_state.get_editor_state().is_some() {
+        commands.entity(room_root.eid()).despawn_descendants();
+        help_text.clear();
+        next_meta_state.set(MenuState::Title.to_meta_state());
+    } else if meta_state.get_menu_state() == Some(MenuState::Title) {
+        next_meta_state.set(EditorState::Editing.to_meta_state());
+    }
+}
+
+fn update_editor(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<MouseInput>,
+    mut editor: ResMut<EditorRoom>,
+    mut next_meta_state: ResMut<NextState<MetaState>>,
+    mut room_defs: ResMut<RoomDefs>,
+    room_def_assets: Res<Assets<RoomDef>>,
+    asset_server: Res<AssetServer>,
+    mut key_events: EventReader<KeyboardInput>,
+    mut camera_director: ResMut<CameraDirector>,
+    time: Res<Time>,
+) {
+    let editor = editor.as_mut();
+    let mouse_pos = mouse_input.get_world_pos();
+
+    // Renaming eats the keyboard until it's done. Always read so old keys don't pile up.
+    let typed = key_events
+        .read()
+        .filter(|event| event.state == ButtonState::Pressed)
+        .map(|event| event.logical_key.clone())
+        .collect::<Vec<_>>();
+    if let Some(mut draft) = editor.renaming.take() {
+        let mut done = false;
+        for key in typed {
+            match key {
+                // It ends up in a file name
+                Key::Character(chars) => draft.extend(
+                    chars
+                        .chars()
+                        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-')),
+                ),
+                Key::Backspace => {
+                    draft.pop();
+                }
+                Key::Enter => {
+                    if !draft.is_empty() {
+                        editor.set_name(draft.clone());
+                    }
+                    done = true;
+                }
+                Key::Escape => done = true,
+                _ => (),
+            }
+        }
+        if !done {
+            editor.renaming = Some(draft);
+        }
+        return;
+    }
+    if keyboard.just_pressed(KeyCode::KeyR) {
+        editor.renaming = Some(String::new());
+        return;
+    }
+
+    // Arrows look around rooms that don't fit on the screen
+    let mut pan = Vec2::ZERO;
+    for (key, dir) in [
+        (KeyCode::ArrowLeft, -Vec2::X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowUp, Vec2::Y),
+        (KeyCode::ArrowDown, -Vec2::Y),
+    ] {
+        if keyboard.pressed(key) {
+            pan += dir;
+        }
+    }
+    let max_pan = (editor.def.size() - IDEAL_VEC_f32).max(Vec2::ZERO) / 2.0;
+    camera_director.idle = (camera_director.idle
+        + pan * EditorRoom::PAN_SPEED * time.delta_seconds())
+    .clamp(-max_pan, max_pan);
+
+    // Tools and settings
+    for (key, tool) in [
+        (KeyCode::Digit1, EditorTool::Circle),
+        (KeyCode::Digit2, EditorTool::Polygon),
+        (KeyCode::Digit3, EditorTool::Spawner),
+        (KeyCode::Digit4, EditorTool::Bird),
+    ] {
+        if keyboard.just_pressed(key) {
+            editor.tool = tool;
+            editor.pending.clear();
+        }
+    }
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        editor.radius = (editor.radius - 2.0).max(EditorRoom::MIN_RADIUS);
+    }
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        editor.radius += 2.0;
+    }
+    if keyboard.just_pressed(KeyCode::KeyH) {
+        editor.kind = match editor.kind {
+            PlatformKind::Sticky => PlatformKind::Hard,
+            PlatformKind::Hard => PlatformKind::Crumbling,
+            PlatformKind::Crumbling => PlatformKind::Sticky,
+        };
+    }
+    if keyboard.just_pressed(KeyCode::KeyN) {
+        editor.def.spawners.push(EditorRoom::new_spawner());
+        editor.tool = EditorTool::Spawner;
+    }
+
+    // Placing
+    if mouse_input.buttons.just_pressed(MouseButton::Left) {
+        match editor.tool {
+            EditorTool::Circle => {
+                editor.def.platforms.push(PlatformDef {
+                    kind: editor.kind,
+                    pos: mouse_pos.to_array(),
+                    shape: ShapeDef::Circle {
+                        radius: editor.radius,
+                    },
+                    rot: 0.0,
+                });
+                editor.dirty = true;
+            }
+            EditorTool::Polygon => editor.pending.push(mouse_pos),
+            EditorTool::Spawner => {
+                if editor.def.spawners.is_empty() {
+                    editor.def.spawners.push(EditorRoom::new_spawner());
+                }
+                if let Some(spawner) = editor.def.spawners.last_mut() {
+                    spawner.poses.push(mouse_pos.to_array());
+                }
+            }
+            EditorTool::Bird => editor.def.bird = mouse_pos.to_array(),
+        }
+    }
+    if keyboard.just_pressed(KeyCode::Enter) {
+        editor.close_polygon();
+    }
+    if keyboard.just_pressed(KeyCode::Escape) {
+        editor.pending.clear();
+    }
+    if keyboard.just_pressed(KeyCode::Backspace) {
+        if editor.pending.pop().is_none() {
+            match editor.tool {
+                EditorTool::Circle | EditorTool::Polygon => {
+                    editor.def.platforms.pop();
+                    editor.dirty = true;
+                }
+                EditorTool::Spawner => {
+                    if let Some(spawner) = editor.def.spawners.last_mut() {
+                        spawner.poses.pop();
+                        // A spawner with nowhere to spawn is no spawner at all
+                        if spawner.poses.is_empty() {
+                            editor.def.spawners.pop();
+                        }
+                    }
+                }
+                EditorTool::Bird => (),
+            }
+        }
+    }
+
+    // Spinning
+    if mouse_input.buttons.just_pressed(MouseButton::Right) {
+        editor.spinning = editor.platform_at(mouse_pos).map(|ix| (ix, mouse_pos));
+    }
+    if let Some((ix, start)) = editor.spinning {
+        if !mouse_input.buttons.pressed(MouseButton::Right) {
+            editor.def.platforms[ix].rot = EditorRoom::spin_from_drag(start, mouse_pos);
+            editor.spinning = None;
+            editor.dirty = true;
+        }
+    }
+
+    // Files
+    let names = room_defs.names();
+    if keyboard.just_pressed(KeyCode::KeyL) && !names.is_empty() {
+        let name = names[editor.load_ix % names.len()];
+        editor.load_ix += 1;
+        match room_defs.get(name, &room_def_assets) {
+            Some(def) => {
+                editor.def = def.clone();
+                editor.confirm_overwrite = false;
+                editor.pending.clear();
+                editor.dirty = true;
+            }
+            None => warn!("room {name} isn't loaded"),
+        }
+    }
+    if keyboard.pressed(KeyCode::ControlLeft) && keyboard.just_pressed(KeyCode::KeyS) {
+        // `N` makes a spawner before it has any points, don't save one that never got any
+        editor
+            .def
+            .spawners
+            .retain(|spawner| !spawner.poses.is_empty());
+        let path = format!("assets/rooms/{}.room.json", editor.def.name);
+        if std::path::Path::new(&path).exists() && !editor.confirm_overwrite {
+            // Probably a shipped room that got loaded with `L`, make sure
+            editor.confirm_overwrite = true;
+            warn!("{path} already exists, Ctrl+S again to overwrite it");
+        } else {
+            editor.confirm_overwrite = false;
+            let result = serde_json::to_string_pretty(&editor.def)
+                .map_err(|err| err.to_string())
+                .and_then(|json| std::fs::write(&path, json).map_err(|err| err.to_string()));
+            match result {
+                Ok(()) => {
+                    room_defs.track(&editor.def.name, &asset_server);
+                    info!("saved {path}");
+                }
+                Err(err) => warn!("couldn't save {path}: {err}"),
+            }
+        }
+    }
+    if keyboard.just_pressed(KeyCode::KeyP) {
+        editor.close_polygon();
+        next_meta_state.set(EditorState::Playtesting.to_meta_state());
+    }
+}
+
+/// Respawns the room whenever the def changes so what you see is what you'll play
+fn refresh_preview(
+    mut editor: ResMut<EditorRoom>,
+    mut commands: Commands,
+    room_root: Res<RoomRoot>,
+    wave_scripts: Res<WaveScripts>,
+    mut help_text: ResMut<HelpText>,
+    mut room_bounds: ResMut<RoomBounds>,
+) {
+    help_text.set(&editor.help());
+    if !editor.dirty {
+        return;
+    }
+    editor.dirty = false;
+    *room_bounds = RoomBounds::new(editor.def.size());
+    commands.entity(room_root.eid()).despawn_descendants();
+    editor
+        .def
+        .spawn(room_root.eid(), &mut commands, &wave_scripts);
+}
+
+/// The stuff that's invisible in the real room
+fn draw_editor(editor: Res<EditorRoom>, mouse_input: Res<MouseInput>, mut gz: Gizmos) {
+    let mouse_pos = mouse_input.get_world_pos();
+    gz.circle_2d(editor.def.bird_pos(), 7.0, tailwind::GREEN_500);
+    for (ix, spawner) in editor.def.spawners.iter().enumerate() {
+        // The one that clicks go to is brighter
+        let color = if ix + 1 == editor.def.spawners.len() {
+            tailwind::RED_400
+        } else {
+            tailwind::RED_800
+        };
+        for pos in &spawner.poses {
+            gz.circle_2d(Vec2::from_array(*pos), 4.0, color);
+        }
+    }
+    for platform in &editor.def.platforms {
+        if platform.rot != 0.0 {
+            let pos = Vec2::from_array(platform.pos);
+            gz.line_2d(
+                pos,
+                pos + Vec2::X.my_rotate(platform.rot) * 8.0,
+                tailwind::SKY_400,
+            );
+        }
+    }
+    match editor.tool {
+        EditorTool::Circle => {
+            gz.circle_2d(mouse_pos, editor.radius, tailwind::ZINC_400);
+        }
+        EditorTool::Polygon => {
+            let mut points = editor.pending.clone();
+            points.push(mouse_pos);
+            gz.linestrip_2d(points, tailwind::ZINC_400);
+        }
+        _ => (),
+    }
+    if let Some((ix, start)) = editor.spinning {
+        let pos = Vec2::from_array(editor.def.platforms[ix].pos);
+        let rot = EditorRoom::spin_from_drag(start, mouse_pos);
+        gz.line_2d(start, mouse_pos, tailwind::ORANGE_700);
+        gz.line_2d(pos, pos + Vec2::X.my_rotate(rot) * 8.0, tailwind::SKY_200);
+    }
+}
+
+fn start_playtest(
+    editor: Res<EditorRoom>,
+    mut commands: Commands,
+    room_root: Res<RoomRoot>,
+    wave_scripts: Res<WaveScripts>,
+    scripts: Res<Assets<WaveScript>>,
+    character: Res<BirdCharacter>,
+    permanent_skills: Res<PermanentSkill>,
+    mut ephemeral_skills: ResMut<EphemeralSkill>,
+    mut help_text: ResMut<HelpText>,
+    mut room_bounds: ResMut<RoomBounds>,
+) {
+    commands.entity(room_root.eid()).despawn_descendants();
+    *room_bounds = RoomBounds::new(editor.def.size());
+    ephemeral_skills.start_attempt(&permanent_skills);
+    editor
+        .def
+        .spawn(room_root.eid(), &mut commands, &wave_scripts);
+    commands
+        .spawn(BirdBundle::new(
+            *character,
+            editor.def.bird_pos(),
+            default(),
+            ephemeral_skills.get_num_launches(),
+            ephemeral_skills.get_num_bullets(),
+            editor.def.num_enemies(&wave_scripts, &scripts),
+        ))
+        .set_parent(room_root.eid());
+    help_text.clear();
+}
+
+fn stop_playtest(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut next_meta_state: ResMut<NextState<MetaState>>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyP) {
+        next_meta_state.set(EditorState::Editing.to_meta_state());
+    }
+}
+
+fn mark_dirty(mut editor: ResMut<EditorRoom>) {
+    editor.dirty = true;
+}
+
+pub(super) fn register_editor(app: &mut App) {
+    app.init_resource::<EditorRoom>();
+
+    app.add_systems(Update, toggle_editor.run_if(in_state(AppMode::Dev)));
+    app.add_systems(OnEnter(EditorState::Editing.to_meta_state()), mark_dirty);
+    app.add_systems(
+        Update,
+        (update_editor, refresh_preview, draw_editor)
+            .chain()
+            .run_if(in_state(EditorState::Editing.to_meta_state()))
+            .after(InputSet),
+    );
+    app.add_systems(
+        OnEnter(EditorState::Playtesting.to_meta_state()),
+        start_playtest,
+    );
+    app.add_systems(
+        Update,
+        stop_playtest.run_if(in_state(EditorState::Playtesting.to_meta_state())),
+    );
+}
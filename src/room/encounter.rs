@@ -1,156 +1,101 @@
+use std::f32::consts::PI;
+
 use crate::prelude::*;
 use rand::Rng;
 
-/// ChatGpt for the... mediocre code?
-pub fn generate_circles(
-    num_circles: u32,
-    bot_left: Vec2,
-    top_right: Vec2,
-    rad_range: (f32, f32),
-    rot_range: (f32, f32),
-    dist_between: f32,
-    also_avoid: &[(Shape, Vec2, f32)],
-) -> Vec<(Shape, Vec2, f32)> {
-    let mut rng = rand::thread_rng();
-    let mut result = Vec::new();
-
-    for _ in 0..num_circles {
-        let radius = rng.gen_range(rad_range.0..rad_range.1);
-        let rot = rng.gen_range(rot_range.0..rot_range.1);
-
-        for _ in 0..100 {
-            let x = rng.gen_range(bot_left.x + dist_between..top_right.x - dist_between);
-            let y = rng.gen_range(bot_left.y + dist_between..top_right.y - dist_between);
-            let center = Vec2 { x, y };
-
-            if is_valid(
-                &center,
-                radius,
-                &result,
-                also_avoid,
-                bot_left,
-                top_right,
-                dist_between,
-            ) {
-                result.push((Shape::Circle { radius }, center, rot));
-                break;
-            }
-        }
-    }
-
-    result
-}
-
-fn is_valid(
-    center: &Vec2,
-    radius: f32,
-    circles: &[(Shape, Vec2, f32)],
-    also_avoid: &[(Shape, Vec2, f32)],
-    bot_left: Vec2,
-    top_right: Vec2,
-    dist_between: f32,
-) -> bool {
-    // Check distance from edges
-    if center.x - radius < bot_left.x + dist_between
-        || center.x + radius > top_right.x - dist_between
-        || center.y - radius < bot_left.y + dist_between
-        || center.y + radius > top_right.y - dist_between
-    {
-        return false;
-    }
-
-    // Check distance from other circles
-    for (shape, other_center, _) in circles.iter().chain(also_avoid.iter()) {
-        if let Shape::Circle {
-            radius: other_radius,
-        } = shape
-        {
-            let distance = center.distance(*other_center);
-            let min_dist = radius + other_radius + dist_between;
-            if distance < min_dist {
-                return false;
-            }
-        }
-    }
-
-    true
-}
-
 /// This is the system responsible for randomly generating each room.
 /// First it generates the spinning wheels.
 /// Then it places the spawners.
-fn create_room(
+pub(super) fn create_room(
     mut commands: Commands,
     room_root: Res<RoomRoot>,
     mut music_manager: ResMut<MusicManager>,
     encounter_state: Res<State<EncounterState>>,
-    mut permanent_skills: ResMut<PermanentSkill>,
-    mut ephemeral_skills: ResMut<EphemeralSkill>,
+    ephemeral_skills: Res<EphemeralSkill>,
+    character: Res<BirdCharacter>,
+    wave_scripts: Res<WaveScripts>,
+    scripts: Res<Assets<WaveScript>>,
+    mut run_rng: ResMut<RunRng>,
+    room_defs: Res<RoomDefs>,
+    room_def_assets: Res<Assets<RoomDef>>,
+    mut room_bounds: ResMut<RoomBounds>,
 ) {
     // Clear the room just to be sure
     commands.entity(room_root.eid()).despawn_descendants();
 
     let encounter_state = encounter_state.get();
 
-    // Enfource minimums
-    // Mind is too messy to keep the full state transitions in the working set
-    // If for whatever reason we end up here with stupid defaults, fix them
-    // Once again, skill issue
-    if permanent_skills.get_num_launches() < 2 {
-        permanent_skills.force_set_num_launches(2);
-    }
-    if permanent_skills.get_num_bullets() < 3 {
-        permanent_skills.force_set_num_bullets(3);
-    }
-    if permanent_skills.get_max_health() < 3 {
-        permanent_skills.force_set_max_health(3);
+    // Runs (and their seeds) start on the map, every room restarts the streams
+    run_rng.enter_room(encounter_state.index());
+
+    // Sometimes it's a hand-built room instead
+    if let Some(def) = room_defs.pick(
+        encounter_state,
+        &room_def_assets,
+        run_rng.stream(RngStream::Layout),
+    ) {
+        music_manager.fade_to_song(MusicKind::NormalBattle);
+        *room_bounds = RoomBounds::new(def.size());
+        def.spawn(room_root.eid(), &mut commands, &wave_scripts);
+        commands
+            .spawn(BirdBundle::new(
+                *character,
+                def.bird_pos(),
+                default(),
+                ephemeral_skills.get_num_launches(),
+                ephemeral_skills.get_num_bullets(),
+                def.num_enemies(&wave_scripts, &scripts),
+            ))
+            .set_parent(room_root.eid());
+        return;
     }
 
+    // The pukebeak script was laid out for one screen
+    let room_size = match encounter_state.kind {
+        EncounterKind::PukebeakOnly => IDEAL_VEC_f32,
+        _ => roll_room_size(
+            encounter_state.difficulty,
+            run_rng.stream(RngStream::Layout),
+        ),
+    };
+    *room_bounds = RoomBounds::new(room_size);
+    // Same density of circles no matter how big the room is
+    let num_circles =
+        (12.0 * room_size.x * room_size.y / (IDEAL_WIDTH_f32 * IDEAL_HEIGHT_f32)).round() as u32;
+
     match encounter_state.kind {
         EncounterKind::SteelbeakOnly => {
-            if encounter_state.difficulty == 1 {
-                // YO we found it, the place where attempts start
-                ephemeral_skills.start_attempt(&permanent_skills);
-            }
-
             music_manager.fade_to_song(MusicKind::NormalBattle); // remember this does nothing if it's already this song
 
             // Background and room border
             BackgroundKind::Forest.spawn(default(), room_root.eid(), &mut commands);
             commands
-                .spawn(HardPlatformBundle::around_room())
+                .spawn(HardPlatformBundle::around_room_sized(room_size))
                 .set_parent(room_root.eid());
 
             // Get all the placements
-            let bot_left = -(IDEAL_VEC_f32 / 2.0 - Vec2::ONE * 6.0);
+            let bot_left = -(room_size / 2.0 - Vec2::ONE * 6.0);
             let top_right = -bot_left;
             let num_spawners = encounter_state.difficulty + 2;
-            let num_enemies = 3 + 3 * encounter_state.difficulty.pow(2) as usize;
+            let num_enemies = DifficultyCurve::STEELBEAK_ENEMIES.at(encounter_state.difficulty);
             let bird_placements = vec![(Shape::Circle { radius: 7.0 }, Vec2::ZERO, 0.0)];
-            let spawner_placements = generate_circles(
-                num_spawners,
-                bot_left,
-                top_right,
-                (10.0, 10.1),
-                (0.0, 0.1),
-                0.0,
-                &bird_placements,
-            );
+            let spawner_placements = LayoutGen::new(num_spawners, bot_left, top_right)
+                .with_radius(10.0, 10.0)
+                .generate(run_rng.stream(RngStream::Layout), &bird_placements);
             let mut combined_avoid = bird_placements.clone();
             combined_avoid.extend(spawner_placements.clone().into_iter());
-            let circle_placements = generate_circles(
-                12,
-                bot_left,
-                top_right,
-                (12.0, 32.0),
-                (-5.0, 5.0),
-                23.0,
-                &combined_avoid,
-            );
+            let circle_placements = LayoutGen::new(num_circles, bot_left, top_right)
+                .with_radius(12.0, 32.0)
+                .with_rot(-5.0, 5.0)
+                .with_gap(23.0)
+                .with_difficulty(encounter_state.difficulty)
+                .reachable_from(bird_placements[0].1)
+                .generate(run_rng.stream(RngStream::Layout), &combined_avoid);
 
             // Spawn the bird!
             commands
                 .spawn(BirdBundle::new(
+                    *character,
                     bird_placements[0].1,
                     default(),
                     ephemeral_skills.get_num_launches(),
@@ -160,84 +105,84 @@ fn create_room(
                 .set_parent(room_root.eid());
 
             // Calculate the batches and spawn the spawner
-            let batch_size_range = 6..(6 + 3 * encounter_state.difficulty.pow(2) as usize);
+            let batch_size_range =
+                6..DifficultyCurve::STEELBEAK_BATCH.at(encounter_state.difficulty);
             let mut batch_sizes = vec![];
             let mut unaccounted_for = num_enemies;
             while unaccounted_for > 0 {
-                let batch_size = rand::thread_rng().gen_range(batch_size_range.clone());
+                let batch_size = run_rng
+                    .stream(RngStream::Spawns)
+                    .gen_range(batch_size_range.clone());
                 let batch_size = batch_size.min(unaccounted_for);
                 batch_sizes.push(batch_size);
                 unaccounted_for -= batch_size;
             }
             commands
-                .spawn(EnemySpawnerBundle::<SimpBundle>::new(
+                .spawn(EnemySpawnerBundle::new(
+                    &[("simp", 1.0)],
                     spawner_placements.into_iter().map(|(_, b, _)| b).collect(),
                     batch_sizes,
                 ))
                 .set_parent(room_root.eid());
 
-            // Spawn the circles
-            for (ix, (shape, pos, rot)) in circle_placements.into_iter().enumerate() {
-                commands
-                    .spawn(StickyPlatformBundle::new(
-                        format!("shape_{ix}").as_str(),
-                        pos,
-                        shape,
-                    ))
-                    .insert(DynoRot { rot })
-                    .set_parent(room_root.eid());
-            }
+            // Spawn the circles, then whatever's out to get you
+            spawn_platforms(
+                circle_placements,
+                encounter_state.difficulty,
+                run_rng.stream(RngStream::Layout),
+                room_root.eid(),
+                &mut commands,
+            );
+            spawn_hazards(
+                encounter_state.difficulty,
+                room_size,
+                run_rng.stream(RngStream::Layout),
+                room_root.eid(),
+                &mut commands,
+            );
         }
         EncounterKind::PukebeakOnly => {
-            music_manager.fade_to_song(MusicKind::BossBattle);
+            // Save the boss music for actual bosses
+            music_manager.fade_to_song(MusicKind::NormalBattle);
 
             // Background and room border
             BackgroundKind::Forest.spawn(default(), room_root.eid(), &mut commands);
             commands
-                .spawn(HardPlatformBundle::around_room())
+                .spawn(HardPlatformBundle::around_room_sized(room_size))
                 .set_parent(room_root.eid());
 
             // Get all the placements
-            let bot_left = -(IDEAL_VEC_f32 / 2.0 - Vec2::ONE * 6.0);
+            let bot_left = -(room_size / 2.0 - Vec2::ONE * 6.0);
             let top_right = -bot_left;
             let num_spawners = 2;
-            let num_enemies = 8;
+            // This room is scripted, unless the script somehow isn't loaded yet
+            let script = wave_scripts.get("pukebeak", &scripts);
+            let num_enemies = script.map_or(8, |script| script.total() as usize);
             let bird_placements = vec![(Shape::Circle { radius: 7.0 }, Vec2::ZERO, 0.0)];
-            let spawner_placements = generate_circles(
-                num_spawners,
-                bot_left,
-                top_right,
-                (10.0, 10.1),
-                (0.0, 0.1),
-                0.0,
-                &bird_placements,
-            );
+            let spawner_placements = match script {
+                Some(script) => script
+                    .spawns
+                    .iter()
+                    .map(|spawn| (Shape::Circle { radius: 10.0 }, spawn.pos(), 0.0))
+                    .collect(),
+                None => LayoutGen::new(num_spawners, bot_left, top_right)
+                    .with_radius(10.0, 10.0)
+                    .generate(run_rng.stream(RngStream::Layout), &bird_placements),
+            };
             let mut combined_avoid = bird_placements.clone();
             combined_avoid.extend(spawner_placements.clone().into_iter());
-            let mut circle_placements = generate_circles(
-                12,
-                bot_left,
-                top_right,
-                (12.0, 32.0),
-                (-5.0, 5.0),
-                23.0,
-                &combined_avoid,
-            );
-            while circle_placements.len() == 0 {
-                circle_placements = generate_circles(
-                    12,
-                    bot_left,
-                    top_right,
-                    (12.0, 32.0),
-                    (-5.0, 5.0),
-                    23.0,
-                    &combined_avoid,
-                );
-            }
+            let circle_placements = LayoutGen::new(num_circles, bot_left, top_right)
+                .with_radius(12.0, 32.0)
+                .with_rot(-5.0, 5.0)
+                .with_gap(23.0)
+                .with_difficulty(encounter_state.difficulty)
+                .reachable_from(bird_placements[0].1)
+                .generate(run_rng.stream(RngStream::Layout), &combined_avoid);
 
             // Spawn the bird!
             commands
                 .spawn(BirdBundle::new(
+                    *character,
                     bird_placements[0].1,
                     default(),
                     ephemeral_skills.get_num_launches(),
@@ -246,22 +191,31 @@ fn create_room(
                 ))
                 .set_parent(room_root.eid());
 
-            // Calculate the batches and spawn the spawner
-            let batch_size_range = 1..=2;
-            let mut batch_sizes = vec![];
-            let mut unaccounted_for = num_enemies;
-            while unaccounted_for > 0 {
-                let batch_size = rand::thread_rng().gen_range(batch_size_range.clone());
-                let batch_size = batch_size.min(unaccounted_for);
-                batch_sizes.push(batch_size);
-                unaccounted_for -= batch_size;
+            if let Some(handle) = script.and(wave_scripts.handle("pukebeak")) {
+                commands
+                    .spawn(ScriptedWavesBundle::new(handle))
+                    .set_parent(room_root.eid());
+            } else {
+                // Calculate the batches and spawn the spawner
+                let batch_size_range = 1..=2;
+                let mut batch_sizes = vec![];
+                let mut unaccounted_for = num_enemies;
+                while unaccounted_for > 0 {
+                    let batch_size = run_rng
+                        .stream(RngStream::Spawns)
+                        .gen_range(batch_size_range.clone());
+                    let batch_size = batch_size.min(unaccounted_for);
+                    batch_sizes.push(batch_size);
+                    unaccounted_for -= batch_size;
+                }
+                commands
+                    .spawn(EnemySpawnerBundle::new(
+                        &[("spew", 1.0)],
+                        spawner_placements.into_iter().map(|(_, b, _)| b).collect(),
+                        batch_sizes,
+                    ))
+                    .set_parent(room_root.eid());
             }
-            commands
-                .spawn(EnemySpawnerBundle::<SpewBundle>::new(
-                    spawner_placements.into_iter().map(|(_, b, _)| b).collect(),
-                    batch_sizes,
-                ))
-                .set_parent(room_root.eid());
 
             // Spawn the circles
             for (ix, (shape, pos, rot)) in circle_placements.into_iter().enumerate() {
@@ -281,139 +235,188 @@ fn create_room(
             // Background and room border
             BackgroundKind::Forest.spawn(default(), room_root.eid(), &mut commands);
             commands
-                .spawn(HardPlatformBundle::around_room())
+                .spawn(HardPlatformBundle::around_room_sized(room_size))
                 .set_parent(room_root.eid());
 
             // Get all the placements
-            let bot_left = -(IDEAL_VEC_f32 / 2.0 - Vec2::ONE * 6.0);
+            let bot_left = -(room_size / 2.0 - Vec2::ONE * 6.0);
             let top_right = -bot_left;
             let num_spawners = (encounter_state.difficulty + 2).min(4);
             let bird_placements = vec![(Shape::Circle { radius: 7.0 }, Vec2::ZERO, 0.0)];
-            let spawner_placements = generate_circles(
-                num_spawners,
-                bot_left,
-                top_right,
-                (18.0, 18.1),
-                (0.0, 0.1),
-                0.0,
-                &bird_placements,
-            );
+            let spawner_placements = LayoutGen::new(num_spawners, bot_left, top_right)
+                .with_radius(18.0, 18.0)
+                .generate(run_rng.stream(RngStream::Layout), &bird_placements);
             let mut combined_avoid = bird_placements.clone();
             combined_avoid.extend(spawner_placements.clone().into_iter());
-            let circle_placements = generate_circles(
-                20,
-                bot_left,
-                top_right,
-                (8.0, 32.0),
-                (-5.0, 5.0),
-                23.0,
-                &combined_avoid,
-            )
-            .into_iter()
-            .take(12)
-            .collect::<Vec<_>>();
-
-            // Calculate simp batches
-            let num_simps = 4 + 3 * (encounter_state.difficulty as f32).powf(1.7) as usize;
-            let simp_batch_size_range =
-                6..(6 + (encounter_state.difficulty as f32).powf(1.4) as usize);
-            let mut simp_batch_sizes = vec![];
-            let mut unaccounted_for = num_simps;
-            while unaccounted_for > 0 {
-                let batch_size = rand::thread_rng().gen_range(simp_batch_size_range.clone());
-                let batch_size = batch_size.min(unaccounted_for);
-                simp_batch_sizes.push(batch_size);
-                unaccounted_for -= batch_size;
-            }
-
-            // Calculate the spew batches
-            let num_spews = 4 + 2 * (encounter_state.difficulty as f32).powf(1.3) as usize;
-            let spew_batch_size_range = 1..(encounter_state.difficulty as usize + 1);
-            let mut spew_batch_sizes = vec![];
-            let mut unaccounted_for = num_spews;
+            let circle_placements = LayoutGen::new(num_circles, bot_left, top_right)
+                .with_radius(8.0, 32.0)
+                .with_rot(-5.0, 5.0)
+                .with_gap(23.0)
+                .with_difficulty(encounter_state.difficulty)
+                .reachable_from(bird_placements[0].1)
+                .generate(run_rng.stream(RngStream::Layout), &combined_avoid);
+
+            // Calculate the batches. Everything comes out of the same spawner, mixed by how many of each there should be
+            let difficulty = encounter_state.difficulty;
+            let num_simps = DifficultyCurve::BOTH_SIMPS.at(difficulty);
+            let num_spews = DifficultyCurve::BOTH_SPEWS.at(difficulty);
+            let num_spits = DifficultyCurve::BOTH_SPITS.at(difficulty);
+            let num_enemies = num_simps + num_spews + num_spits;
+            let batch_size_range = 4..DifficultyCurve::BOTH_BATCH.at(difficulty);
+            let mut batch_sizes = vec![];
+            let mut unaccounted_for = num_enemies;
             while unaccounted_for > 0 {
-                let batch_size = rand::thread_rng().gen_range(spew_batch_size_range.clone());
+                let batch_size = run_rng
+                    .stream(RngStream::Spawns)
+                    .gen_range(batch_size_range.clone());
                 let batch_size = batch_size.min(unaccounted_for);
-                spew_batch_sizes.push(batch_size);
+                batch_sizes.push(batch_size);
                 unaccounted_for -= batch_size;
             }
 
-            // Spawn da spawners
+            // Spawn da spawner
             commands
-                .spawn(EnemySpawnerBundle::<SimpBundle>::new(
-                    spawner_placements
-                        .clone()
-                        .into_iter()
-                        .map(|(_, b, _)| b)
-                        .collect(),
-                    simp_batch_sizes,
-                ))
-                .set_parent(room_root.eid());
-            commands
-                .spawn(EnemySpawnerBundle::<SpewBundle>::new(
+                .spawn(EnemySpawnerBundle::new(
+                    &[
+                        ("simp", num_simps as f32),
+                        ("spew", num_spews as f32),
+                        ("spit", num_spits as f32),
+                    ],
                     spawner_placements.into_iter().map(|(_, b, _)| b).collect(),
-                    spew_batch_sizes,
+                    batch_sizes,
                 ))
                 .set_parent(room_root.eid());
 
-            // Spawn the circles
-            for (ix, (shape, pos, rot)) in circle_placements.into_iter().enumerate() {
-                commands
-                    .spawn(StickyPlatformBundle::new(
-                        format!("shape_{ix}").as_str(),
-                        pos,
-                        shape,
-                    ))
-                    .insert(DynoRot { rot })
-                    .set_parent(room_root.eid());
-            }
+            // Spawn the circles, then whatever's out to get you
+            spawn_platforms(
+                circle_placements,
+                encounter_state.difficulty,
+                run_rng.stream(RngStream::Layout),
+                room_root.eid(),
+                &mut commands,
+            );
+            spawn_hazards(
+                encounter_state.difficulty,
+                room_size,
+                run_rng.stream(RngStream::Layout),
+                room_root.eid(),
+                &mut commands,
+            );
 
             // Spawn the bird!
             commands
                 .spawn(BirdBundle::new(
+                    *character,
                     bird_placements[0].1,
                     default(),
                     ephemeral_skills.get_num_launches(),
                     ephemeral_skills.get_num_bullets(),
-                    (num_simps + num_spews) as u32,
+                    num_enemies as u32,
                 ))
                 .set_parent(room_root.eid());
         }
     }
 }
 
+/// Later on, some of the platforms won't hold you for long.
+/// Rolled after everything else in the layout so turning this up doesn't move anything around.
+fn spawn_platforms(
+    placements: Vec<(Shape, Vec2, f32)>,
+    difficulty: u32,
+    rng: &mut impl Rng,
+    parent: Entity,
+    commands: &mut Commands,
+) {
+    let crumble_chance = (0.08 * difficulty.saturating_sub(1) as f32).min(0.35);
+    for (ix, (shape, pos, rot)) in placements.into_iter().enumerate() {
+        let name = format!("shape_{ix}");
+        let mut platform_commands = if rng.gen::<f32>() < crumble_chance {
+            commands.spawn(CrumblingPlatformBundle::new(&name, pos, shape))
+        } else {
+            commands.spawn(StickyPlatformBundle::new(&name, pos, shape))
+        };
+        platform_commands.insert(DynoRot { rot }).set_parent(parent);
+    }
+}
+
+/// From difficulty 3, half the generated fights are wider than the screen. From 5 they can be taller too.
+fn roll_room_size(difficulty: u32, rng: &mut impl Rng) -> Vec2 {
+    if difficulty < 3 || rng.gen_bool(0.5) {
+        return IDEAL_VEC_f32;
+    }
+    let tall = difficulty >= 5 && rng.gen_bool(0.5);
+    IDEAL_VEC_f32 * Vec2::new(1.5, if tall { 1.5 } else { 1.0 })
+}
+
+/// Spikes on the floor from difficulty 2, a laser on the wall from 3
+fn spawn_hazards(
+    difficulty: u32,
+    room_size: Vec2,
+    rng: &mut impl Rng,
+    parent: Entity,
+    commands: &mut Commands,
+) {
+    // Just inside the room border
+    let inner = room_size / 2.0 - Vec2::ONE * 5.0;
+    if difficulty >= 2 {
+        let length = rng.gen_range(40.0..80.0);
+        let x = rng.gen_range(-inner.x + length / 2.0..inner.x - length / 2.0);
+        commands
+            .spawn(SpikesBundle::new(Vec2::new(x, -inner.y + 2.5), length, 0.0))
+            .set_parent(parent);
+    }
+    if difficulty >= 3 {
+        let y = rng.gen_range(-inner.y * 0.6..inner.y * 0.6);
+        let (x, angle) = if rng.gen_bool(0.5) {
+            (-inner.x + 4.0, 0.0)
+        } else {
+            (inner.x - 4.0, PI)
+        };
+        commands
+            .spawn(LaserEmitterBundle::new(
+                Vec2::new(x, y),
+                angle,
+                rng.gen_range(4.0..6.0),
+                2.0,
+            ))
+            .set_parent(parent);
+    }
+}
+
 /// At one point I wanted to have a cool transition here but I guess not
 /// NOTE: For some reason shit doesn't work if I try to do this state transition from the OnEnter(Entering). Idk why. Skill issue or bevy issue
+/// Boss rooms go through here too
 fn update_encounter_enter(
-    encounter_state: Res<State<EncounterState>>,
+    meta_state: Res<State<MetaState>>,
     mut next_meta_state: ResMut<NextState<MetaState>>,
 ) {
+    let Some(room_state) = meta_state.get_room_state() else {
+        return;
+    };
     next_meta_state.set(
-        RoomState::Encounter(EncounterState {
-            progress: EncounterProgress::Fighting,
-            kind: encounter_state.get().kind,
-            difficulty: encounter_state.get().difficulty,
-        })
-        .to_meta_state(),
+        room_state
+            .with_progress(EncounterProgress::Fighting)
+            .to_meta_state(),
     );
 }
 
 fn update_encounter_fighting(
-    encounter_state: Res<State<EncounterState>>,
+    meta_state: Res<State<MetaState>>,
     mut next_meta_state: ResMut<NextState<MetaState>>,
     bird: Query<&mut Bird>,
+    objective: Res<RoomObjective>,
 ) {
     let Ok(bird) = bird.get_single() else {
         return;
     };
-    if bird.get_kills_left() == 0 {
+    let Some(room_state) = meta_state.get_room_state() else {
+        return;
+    };
+    if objective.is_complete(&bird) {
         next_meta_state.set(
-            RoomState::Encounter(EncounterState {
-                progress: EncounterProgress::Meandering,
-                kind: encounter_state.get().kind,
-                difficulty: encounter_state.get().difficulty,
-            })
-            .to_meta_state(),
+            room_state
+                .with_progress(EncounterProgress::Meandering)
+                .to_meta_state(),
         );
         return;
     }
@@ -422,27 +425,47 @@ fn update_encounter_fighting(
 fn enter_meandering(
     mut commands: Commands,
     mut music_manager: ResMut<MusicManager>,
-    steelbeak_spawners: Query<(Entity, &EnemySpawner<SimpBundle>)>,
+    spawners: Query<(Entity, &EnemySpawner)>,
     room_root: Res<RoomRoot>,
     mut help_text: ResMut<HelpText>,
+    mut run_rng: ResMut<RunRng>,
+    encounter_state: Option<Res<State<EncounterState>>>,
+    mut camera_director: ResMut<CameraDirector>,
 ) {
     commands.spawn(SoundEffect::universal("sound_effects/room_clear.ogg", 0.3));
+    // Savor it
+    camera_director.punch_zoom(0.15, 0.8);
+    camera_director.add_trauma(0.3);
     music_manager.fade_to_song(MusicKind::SandCastles);
 
     let mut possible_heart_poses = vec![];
-    for (eid, spawner) in &steelbeak_spawners {
+    for (eid, spawner) in &spawners {
+        if !spawner.has_kind("simp") {
+            continue;
+        }
         possible_heart_poses.extend(spawner.poses.clone().into_iter());
         commands.entity(eid).despawn_recursive();
     }
 
     for pos in possible_heart_poses {
-        if thread_rng().gen::<f32>() < 0.2 {
+        if run_rng.stream(RngStream::Loot).gen::<f32>() < 0.2 {
             commands
                 .spawn(HeartBundle::new(pos))
                 .set_parent(room_root.eid());
         }
     }
 
+    // Elite rooms pay out
+    if encounter_state.is_some_and(|state| state.get().elite) {
+        let rng = run_rng.stream(RngStream::Loot);
+        for _ in 0..5 {
+            let vel = Vec2::new(rng.gen_range(-60.0..60.0), rng.gen_range(80.0..140.0));
+            commands
+                .spawn(LootBundle::new(LootKind::Feather, Vec2::ZERO, vel))
+                .set_parent(room_root.eid());
+        }
+    }
+
     commands
         .spawn(GoNextBundle::new(Vec2::ZERO))
         .set_parent(room_root.eid());
@@ -455,7 +478,10 @@ fn exit_meandering(mut help_text: ResMut<HelpText>) {
 }
 
 pub(super) fn register_encounters(app: &mut App) {
-    app.add_systems(OnEnter(EncounterProgress::Entering), create_room);
+    app.add_systems(
+        OnEnter(EncounterProgress::Entering),
+        create_room.run_if(state_exists::<EncounterState>),
+    );
     app.add_systems(
         Update,
         update_encounter_enter.run_if(in_state(EncounterProgress::Entering)),
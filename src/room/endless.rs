@@ -0,0 +1,330 @@
+//! Endless mode: no map, no bosses, just mixed rooms that keep getting harder until you die.
+//! Runs are scored, and the best ones get written to `high_scores.json` next to wherever the game was run from.
+
+use std::time::Duration;
+
+use bevy::sprite::Anchor;
+
+use crate::prelude::*;
+
+/// Picked on character select
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RunMode {
+    #[default]
+    Story,
+    Endless,
+}
+impl RunMode {
+    pub fn is_endless(&self) -> bool {
+        matches!(self, Self::Endless)
+    }
+
+    pub fn toggle(&mut self) {
+        *self = match self {
+            Self::Story => Self::Endless,
+            Self::Endless => Self::Story,
+        };
+    }
+
+    /// The first room of a run in this mode
+    pub fn first_room(&self) -> MetaState {
+        match self {
+            Self::Story => TutorialState::LearnToFly.to_meta_state(),
+            Self::Endless => RoomState::xth_encounter(EncounterKind::Both, 1).to_meta_state(),
+        }
+    }
+
+    /// Where to go after shooting `GoNext` in `room_state`
+    pub fn after_room(&self, room_state: &RoomState) -> MetaState {
+        match (self, room_state) {
+            (Self::Endless, RoomState::Encounter(encounter_state)) => {
+                RoomState::xth_encounter(EncounterKind::Both, encounter_state.difficulty + 1)
+                    .to_meta_state()
+            }
+            _ => MetaState::Map,
+        }
+    }
+
+    /// Where to go once the bird has picked its upgrade after dying
+    pub fn after_death(&self) -> MetaState {
+        match self {
+            Self::Story => MetaState::Map,
+            Self::Endless => RoomState::xth_encounter(EncounterKind::Both, 1).to_meta_state(),
+        }
+    }
+}
+
+/// The score of the endless run that's going
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RunScore {
+    kills: u32,
+    rooms: u32,
+    secs: f32,
+    damage_taken: u32,
+    /// Kills in a row without getting hit or waiting too long
+    combo: u32,
+    best_combo: u32,
+    combo_timer: Timer,
+    /// From kills, multiplier already applied
+    kill_points: u32,
+}
+impl RunScore {
+    const KILL_POINTS: f32 = 100.0;
+    const ROOM_POINTS: u32 = 500;
+    const POINTS_PER_SEC: f32 = 5.0;
+    const DAMAGE_PENALTY: u32 = 250;
+    /// Seconds between kills before the combo drops
+    const COMBO_WINDOW: f32 = 3.0;
+
+    pub fn multiplier(&self) -> f32 {
+        1.0 + 0.2 * self.combo as f32
+    }
+
+    pub fn total(&self) -> u32 {
+        (self.kill_points
+            + self.rooms * Self::ROOM_POINTS
+            + (self.secs * Self::POINTS_PER_SEC) as u32)
+            .saturating_sub(self.damage_taken * Self::DAMAGE_PENALTY)
+    }
+
+    fn add_kill(&mut self) {
+        self.kill_points += (Self::KILL_POINTS * self.multiplier()) as u32;
+        self.kills += 1;
+        self.combo += 1;
+        self.best_combo = self.best_combo.max(self.combo);
+        self.combo_timer = Timer::from_seconds(Self::COMBO_WINDOW, TimerMode::Once);
+    }
+
+    fn take_damage(&mut self, amount: u32) {
+        self.damage_taken += amount;
+        self.combo = 0;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub kills: u32,
+    pub rooms: u32,
+    pub secs: f32,
+    pub best_combo: u32,
+    pub character: String,
+    pub seed: u64,
+}
+
+/// The best endless runs on this machine, best first
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+impl HighScores {
+    const PATH: &'static str = "high_scores.json";
+    const MAX_ENTRIES: usize = 10;
+
+    fn load() -> Self {
+        let Ok(bytes) = std::fs::read(Self::PATH) else {
+            // Nothing saved yet
+            return default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+            warn!("couldn't read {}: {err}", Self::PATH);
+            default()
+        })
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(|err| err.to_string())
+            .and_then(|json| std::fs::write(Self::PATH, json).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("couldn't save {}: {err}", Self::PATH);
+        }
+    }
+
+    /// Returns the rank (0 is best) if it made the table
+    fn submit(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| other.score < entry.score)
+            .unwrap_or(self.entries.len());
+        if rank >= Self::MAX_ENTRIES {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(Self::MAX_ENTRIES);
+        Some(rank)
+    }
+
+    pub fn best(&self) -> Option<u32> {
+        self.entries.first().map(|entry| entry.score)
+    }
+
+    /// A few lines for showing on screen
+    pub fn table(&self, count: usize) -> String {
+        if self.entries.is_empty() {
+            return "No endless runs yet".to_string();
+        }
+        self.entries
+            .iter()
+            .take(count)
+            .enumerate()
+            .map(|(ix, entry)| {
+                format!(
+                    "{}. {} - {} ({} rooms)",
+                    ix + 1,
+                    entry.score,
+                    entry.character,
+                    entry.rooms
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[derive(Component)]
+struct ScoreText;
+
+fn reset_score(
+    mut score: ResMut<RunScore>,
+    mut permanent_skills: ResMut<PermanentSkill>,
+    mut ephemeral_skills: ResMut<EphemeralSkill>,
+    character: Res<BirdCharacter>,
+    mut run_rng: ResMut<RunRng>,
+) {
+    *score = default();
+    super::map::start_new_run(
+        &mut permanent_skills,
+        &mut ephemeral_skills,
+        *character,
+        &mut run_rng,
+    );
+}
+
+fn update_score(
+    mut score: ResMut<RunScore>,
+    mut damage_taken: EventReader<DamageTaken>,
+    birds: Query<Entity, With<Bird>>,
+    enemies: Query<(), With<EnemyHurtbox>>,
+    time: Res<Time>,
+) {
+    for event in damage_taken.read() {
+        if birds.contains(event.target) {
+            score.take_damage(event.amount);
+        } else if event.killed && enemies.contains(event.target) {
+            score.add_kill();
+        }
+    }
+    // Real time, bullet time doesn't buy you points
+    score.secs += time.delta_seconds();
+    score.combo_timer.tick(time.delta());
+    if score.combo_timer.finished() {
+        score.combo = 0;
+        score.combo_timer.set_elapsed(Duration::ZERO);
+        score.combo_timer.pause();
+    }
+}
+
+fn count_room(mut score: ResMut<RunScore>) {
+    score.rooms += 1;
+}
+
+fn spawn_score_text(mut commands: Commands, room_root: Res<RoomRoot>) {
+    commands
+        .spawn((
+            Name::new("score_text"),
+            ScoreText,
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 9.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Right),
+                text_anchor: Anchor::TopRight,
+                transform: Transform::from_translation(Vec3::new(152.0, 82.0, ZIX_MAX - 0.1)),
+                ..default()
+            },
+            MenuCamera::render_layers(),
+            ScreenSpace::new(152.0, 82.0),
+        ))
+        .set_parent(room_root.eid());
+}
+
+fn update_score_text(score: Res<RunScore>, mut text: Query<&mut Text, With<ScoreText>>) {
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    text.sections[0].value = if score.combo > 1 {
+        format!("{}  x{:.1}", score.total(), score.multiplier())
+    } else {
+        score.total().to_string()
+    };
+}
+
+pub(super) fn record_score(
+    score: Res<RunScore>,
+    mut high_scores: ResMut<HighScores>,
+    character: Res<BirdCharacter>,
+    run_rng: Res<RunRng>,
+) {
+    let rank = high_scores.submit(HighScore {
+        score: score.total(),
+        kills: score.kills,
+        rooms: score.rooms,
+        secs: score.secs,
+        best_combo: score.best_combo,
+        character: character.def().name.to_string(),
+        seed: run_rng.seed(),
+    });
+    if rank.is_some() {
+        high_scores.save();
+    }
+}
+
+fn is_endless(run_mode: Res<RunMode>) -> bool {
+    run_mode.is_endless()
+}
+
+fn is_first_endless_room(encounter_state: Option<Res<State<EncounterState>>>) -> bool {
+    encounter_state.is_some_and(|state| state.get().difficulty == 1)
+}
+
+pub(super) fn register_endless(app: &mut App) {
+    app.init_resource::<RunMode>();
+    app.init_resource::<RunScore>();
+    app.insert_resource(HighScores::load());
+
+    app.add_systems(
+        OnEnter(EncounterProgress::Entering),
+        reset_score
+            .run_if(is_endless)
+            .run_if(is_first_endless_room)
+            .before(super::encounter::create_room),
+    );
+    app.add_systems(
+        Update,
+        update_score
+            .run_if(is_endless)
+            .run_if(in_state(EncounterProgress::Fighting))
+            .run_if(in_state(PhysicsState::Active))
+            .after(DamageSet),
+    );
+    app.add_systems(
+        OnEnter(EncounterProgress::Meandering),
+        count_room.run_if(is_endless),
+    );
+    app.add_systems(
+        OnEnter(EncounterProgress::Fighting),
+        spawn_score_text.run_if(is_endless),
+    );
+    app.add_systems(Update, update_score_text.run_if(is_endless));
+    app.add_systems(
+        OnEnter(RoomState::Dead.to_meta_state()),
+        record_score.run_if(is_endless),
+    );
+}
@@ -0,0 +1,397 @@
+//! Procedural platform layouts.
+//! Points come from Poisson-disk sampling (Bridson's), so they're spread out evenly without piling up,
+//! and the spacing shrinks until there's room for everything, so you always get the count you asked for.
+//! Each piece is then sized to fit in the space around its point.
+
+use std::f32::consts::PI;
+
+use rand::seq::SliceRandom;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceKind {
+    Circle,
+    /// A random regular polygon, 3 to 6 sides
+    Polygon,
+    /// A long thin plank at a random angle
+    Log,
+}
+
+/// Builds a list of `(shape, pos, rot)` placements
+#[derive(Debug, Clone)]
+pub struct LayoutGen {
+    count: u32,
+    bot_left: Vec2,
+    top_right: Vec2,
+    rad_range: (f32, f32),
+    rot_range: (f32, f32),
+    /// Minimum space between pieces (and between pieces and the edge)
+    gap: f32,
+    /// Weights for each kind of piece
+    mix: Vec<(PieceKind, f32)>,
+    /// If set, every piece has to be reachable by launching from here (hopping between pieces)
+    reachable_from: Option<Vec2>,
+}
+impl LayoutGen {
+    /// Candidates Bridson's tries around each point before giving up on it
+    const BRIDSON_TRIES: u32 = 30;
+    /// How much the spacing shrinks each time there isn't room for enough points
+    const SHRINK: f32 = 0.85;
+    /// Pieces never get smaller than this, even if they're squeezed
+    const MIN_RADIUS: f32 = 3.0;
+    const LOG_THICKNESS: f32 = 6.0;
+    /// Furthest gap the bird can comfortably launch across
+    const LAUNCH_REACH: f32 = 140.0;
+    /// Layouts that fail the reachability check get redone this many times before we give up and take one anyway
+    const MAX_ATTEMPTS: u32 = 8;
+
+    pub fn new(count: u32, bot_left: Vec2, top_right: Vec2) -> Self {
+        Self {
+            count,
+            bot_left,
+            top_right,
+            rad_range: (10.0, 10.0),
+            rot_range: (0.0, 0.0),
+            gap: 0.0,
+            mix: vec![(PieceKind::Circle, 1.0)],
+            reachable_from: None,
+        }
+    }
+
+    pub fn with_radius(mut self, min: f32, max: f32) -> Self {
+        self.rad_range = (min, max);
+        self
+    }
+
+    pub fn with_rot(mut self, min: f32, max: f32) -> Self {
+        self.rot_range = (min, max);
+        self
+    }
+
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn with_mix(mut self, mix: &[(PieceKind, f32)]) -> Self {
+        self.mix = mix.to_vec();
+        self
+    }
+
+    pub fn reachable_from(mut self, pos: Vec2) -> Self {
+        self.reachable_from = Some(pos);
+        self
+    }
+
+    /// Harder rooms get fewer places to perch, and more of them are awkward shapes
+    pub fn with_difficulty(mut self, difficulty: u32) -> Self {
+        let harder = difficulty.saturating_sub(1) as f32;
+        self.count = ((self.count as f32 / (1.0 + 0.15 * harder)).round() as u32).max(4);
+        self.gap += 3.0 * harder;
+        self.mix = vec![
+            (PieceKind::Circle, 1.0),
+            (PieceKind::Polygon, 0.2 + 0.15 * harder),
+            (PieceKind::Log, 0.1 + 0.15 * harder),
+        ];
+        self
+    }
+
+    pub fn generate(
+        &self,
+        rng: &mut impl Rng,
+        also_avoid: &[(Shape, Vec2, f32)],
+    ) -> Vec<(Shape, Vec2, f32)> {
+        let mut result = vec![];
+        for _ in 0..Self::MAX_ATTEMPTS {
+            result = self.generate_once(rng, also_avoid);
+            if self.all_reachable(&result) {
+                return result;
+            }
+        }
+        warn!("couldn't make a layout where everything is reachable, using one that isn't");
+        result
+    }
+
+    fn generate_once(
+        &self,
+        rng: &mut impl Rng,
+        also_avoid: &[(Shape, Vec2, f32)],
+    ) -> Vec<(Shape, Vec2, f32)> {
+        let avoid = also_avoid
+            .iter()
+            .map(|(shape, pos, _)| (*pos, reach_of(shape)))
+            .collect::<Vec<_>>();
+        let points = self.sample_points(rng, &avoid);
+        points
+            .iter()
+            .enumerate()
+            .map(|(ix, point)| {
+                // Half the space to the nearest neighbor is ours, which means two pieces can never touch
+                let to_neighbor = points
+                    .iter()
+                    .enumerate()
+                    .filter(|(other_ix, _)| *other_ix != ix)
+                    .map(|(_, other)| (point.distance(*other) - self.gap) / 2.0)
+                    .fold(f32::MAX, f32::min);
+                let to_avoid = avoid
+                    .iter()
+                    .map(|(pos, reach)| point.distance(*pos) - reach - self.gap)
+                    .fold(f32::MAX, f32::min);
+                let to_edge = self.edge_clearance(*point);
+                let wanted = rng.gen_range(self.rad_range.0..=self.rad_range.1);
+                let radius = wanted
+                    .min(to_neighbor)
+                    .min(to_avoid)
+                    .min(to_edge)
+                    .max(Self::MIN_RADIUS);
+                let rot = rng.gen_range(self.rot_range.0..=self.rot_range.1);
+                (self.roll_shape(rng, radius), *point, rot)
+            })
+            .collect()
+    }
+
+    /// Room left between a point and the edge of the area, after the gap
+    fn edge_clearance(&self, point: Vec2) -> f32 {
+        (point.x - self.bot_left.x)
+            .min(self.top_right.x - point.x)
+            .min(point.y - self.bot_left.y)
+            .min(self.top_right.y - point.y)
+            - self.gap
+    }
+
+    /// Exactly `count` points (unless the area is absurdly small), spaced as far apart as they'll go
+    fn sample_points(&self, rng: &mut impl Rng, avoid: &[(Vec2, f32)]) -> Vec<Vec2> {
+        let mut spacing = 2.0 * self.rad_range.1 + self.gap;
+        loop {
+            let mut points = self.bridson(rng, spacing, avoid);
+            if points.len() >= self.count as usize || spacing < 1.0 {
+                points.shuffle(rng);
+                points.truncate(self.count as usize);
+                return points;
+            }
+            spacing *= Self::SHRINK;
+        }
+    }
+
+    fn bridson(&self, rng: &mut impl Rng, spacing: f32, avoid: &[(Vec2, f32)]) -> Vec<Vec2> {
+        let margin = Self::MIN_RADIUS + self.gap;
+        let valid = |point: Vec2, points: &[Vec2]| {
+            self.edge_clearance(point) >= Self::MIN_RADIUS
+                && avoid
+                    .iter()
+                    .all(|(pos, reach)| point.distance(*pos) - reach >= margin)
+                && points.iter().all(|other| point.distance(*other) >= spacing)
+        };
+        let mut points: Vec<Vec2> = vec![];
+        let mut active = vec![];
+        // Seed it with a few random points, one isn't enough if the avoid list carves the area up
+        for _ in 0..Self::BRIDSON_TRIES {
+            let point = Vec2::new(
+                rng.gen_range(self.bot_left.x..self.top_right.x),
+                rng.gen_range(self.bot_left.y..self.top_right.y),
+            );
+            if valid(point, &points) {
+                active.push(points.len());
+                points.push(point);
+            }
+        }
+        while !active.is_empty() {
+            let active_ix = rng.gen_range(0..active.len());
+            let center = points[active[active_ix]];
+            let mut found = None;
+            for _ in 0..Self::BRIDSON_TRIES {
+                let angle = rng.gen_range(0.0..2.0 * PI);
+                let dist = rng.gen_range(spacing..2.0 * spacing);
+                let candidate = center + Vec2::X.my_rotate(angle) * dist;
+                if valid(candidate, &points) {
+                    found = Some(candidate);
+                    break;
+                }
+            }
+            match found {
+                Some(point) => {
+                    active.push(points.len());
+                    points.push(point);
+                }
+                None => {
+                    active.swap_remove(active_ix);
+                }
+            }
+        }
+        points
+    }
+
+    fn roll_shape(&self, rng: &mut impl Rng, radius: f32) -> Shape {
+        let total = self.mix.iter().map(|(_, weight)| weight).sum::<f32>();
+        let mut roll = rng.gen_range(0.0..total.max(f32::EPSILON));
+        let mut kind = PieceKind::Circle;
+        for (mix_kind, weight) in &self.mix {
+            if roll < *weight {
+                kind = *mix_kind;
+                break;
+            }
+            roll -= weight;
+        }
+        match kind {
+            PieceKind::Circle => Shape::Circle { radius },
+            PieceKind::Polygon => Shape::Polygon {
+                // Goes around clockwise already
+                points: regular_polygon(rng.gen_range(3..=6), rng.gen_range(0.0..360.0), radius),
+            },
+            PieceKind::Log => {
+                let angle = rng.gen_range(0.0..PI);
+                Shape::Polygon {
+                    points: simple_rect(2.0 * radius, Self::LOG_THICKNESS.min(radius))
+                        .into_iter()
+                        .map(|point| point.my_rotate(angle))
+                        .collect(),
+                }
+            }
+        }
+    }
+
+    /// Flood fill from `reachable_from`. Two pieces are connected if they're close enough
+    /// and nothing else is in the way.
+    fn all_reachable(&self, placements: &[(Shape, Vec2, f32)]) -> bool {
+        let Some(start) = self.reachable_from else {
+            return true;
+        };
+        let nodes = std::iter::once((start, 0.0))
+            .chain(
+                placements
+                    .iter()
+                    .map(|(shape, pos, _)| (*pos, reach_of(shape))),
+            )
+            .collect::<Vec<_>>();
+        let connected = |a: usize, b: usize| {
+            let ((pos_a, reach_a), (pos_b, reach_b)) = (nodes[a], nodes[b]);
+            if pos_a.distance(pos_b) - reach_a - reach_b > Self::LAUNCH_REACH {
+                return false;
+            }
+            nodes.iter().enumerate().all(|(ix, (pos, reach))| {
+                ix == a
+                    || ix == b
+                    || closest_point_on_segment(*pos, [pos_a, pos_b]).distance(*pos) >= *reach
+            })
+        };
+        let mut seen = vec![false; nodes.len()];
+        seen[0] = true;
+        let mut frontier = vec![0];
+        while let Some(node) = frontier.pop() {
+            for other in 0..nodes.len() {
+                if !seen[other] && connected(node, other) {
+                    seen[other] = true;
+                    frontier.push(other);
+                }
+            }
+        }
+        seen.into_iter().all(|seen| seen)
+    }
+}
+
+/// How far a shape reaches from its center
+fn reach_of(shape: &Shape) -> f32 {
+    match shape {
+        Shape::Circle { radius } => *radius,
+        Shape::Polygon { points } => points
+            .iter()
+            .map(|point| point.length())
+            .fold(0.0, f32::max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    const BOT_LEFT: Vec2 = Vec2::new(-200.0, -150.0);
+    const TOP_RIGHT: Vec2 = Vec2::new(200.0, 150.0);
+    const GAP: f32 = 8.0;
+
+    fn circles(seed: u64, gen: &LayoutGen, avoid: &[(Shape, Vec2, f32)]) -> Vec<(f32, Vec2)> {
+        gen.generate(&mut StdRng::seed_from_u64(seed), avoid)
+            .into_iter()
+            .map(|(shape, pos, _)| (reach_of(&shape), pos))
+            .collect()
+    }
+
+    #[test]
+    fn gives_the_count_asked_for() {
+        let gen = LayoutGen::new(12, BOT_LEFT, TOP_RIGHT).with_radius(10.0, 20.0);
+        for seed in 0..20 {
+            assert_eq!(circles(seed, &gen, &[]).len(), 12);
+        }
+        // Even when they have to squeeze
+        let cramped =
+            LayoutGen::new(6, Vec2::splat(-30.0), Vec2::splat(30.0)).with_radius(10.0, 10.0);
+        for seed in 0..20 {
+            assert_eq!(circles(seed, &cramped, &[]).len(), 6);
+        }
+    }
+
+    #[test]
+    fn pieces_keep_the_gap() {
+        let gen = LayoutGen::new(12, BOT_LEFT, TOP_RIGHT)
+            .with_radius(10.0, 20.0)
+            .with_gap(GAP);
+        for seed in 0..20 {
+            let placed = circles(seed, &gen, &[]);
+            for (ix, (reach, pos)) in placed.iter().enumerate() {
+                for (other_reach, other_pos) in &placed[ix + 1..] {
+                    assert!(pos.distance(*other_pos) - reach - other_reach >= GAP - 0.01);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pieces_stay_inside() {
+        let gen = LayoutGen::new(12, BOT_LEFT, TOP_RIGHT)
+            .with_radius(10.0, 20.0)
+            .with_gap(GAP);
+        for seed in 0..20 {
+            for (reach, pos) in circles(seed, &gen, &[]) {
+                assert!(pos.x - reach >= BOT_LEFT.x + GAP - 0.01);
+                assert!(pos.x + reach <= TOP_RIGHT.x - GAP + 0.01);
+                assert!(pos.y - reach >= BOT_LEFT.y + GAP - 0.01);
+                assert!(pos.y + reach <= TOP_RIGHT.y - GAP + 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn pieces_keep_away_from_what_they_avoid() {
+        let gen = LayoutGen::new(10, BOT_LEFT, TOP_RIGHT)
+            .with_radius(10.0, 20.0)
+            .with_gap(GAP);
+        let avoid = [(Shape::Circle { radius: 40.0 }, Vec2::ZERO, 0.0)];
+        for seed in 0..20 {
+            for (reach, pos) in circles(seed, &gen, &avoid) {
+                assert!(pos.length() - reach - 40.0 >= GAP - 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_layout() {
+        let gen = LayoutGen::new(10, BOT_LEFT, TOP_RIGHT)
+            .with_radius(10.0, 20.0)
+            .with_difficulty(3);
+        assert_eq!(circles(7, &gen, &[]), circles(7, &gen, &[]));
+    }
+
+    #[test]
+    fn harder_rooms_get_fewer_pieces() {
+        let count = |difficulty| {
+            LayoutGen::new(10, BOT_LEFT, TOP_RIGHT)
+                .with_difficulty(difficulty)
+                .count
+        };
+        assert_eq!(count(1), 10);
+        assert!(count(3) < count(1));
+        assert_eq!(count(100), 4);
+    }
+}
@@ -0,0 +1,447 @@
+//! The map of a run. Each act is a few rows of rooms to pick between, ending in a boss.
+//! After shooting `GoNext` you land here and choose which of the connected rooms to go to next.
+
+use std::ops::RangeInclusive;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapNodeKind {
+    Combat,
+    Elite,
+    Rest,
+    Shop,
+    Boss,
+}
+impl MapNodeKind {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Combat => "Fight",
+            Self::Elite => "Elite",
+            Self::Rest => "Rest",
+            Self::Shop => "Shop",
+            Self::Boss => "Boss",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Self::Combat => tailwind::RED_400.into(),
+            Self::Elite => tailwind::FUCHSIA_400.into(),
+            Self::Rest => tailwind::GREEN_400.into(),
+            Self::Shop => tailwind::AMBER_300.into(),
+            Self::Boss => tailwind::RED_700.into(),
+        }
+    }
+
+    /// What goes in a normal (not first, not boss) row
+    fn roll(rng: &mut impl Rng) -> Self {
+        let roll = rng.gen::<f32>();
+        if roll < 0.55 {
+            Self::Combat
+        } else if roll < 0.75 {
+            Self::Elite
+        } else if roll < 0.87 {
+            Self::Shop
+        } else {
+            Self::Rest
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MapNode {
+    pub kind: MapNodeKind,
+    /// 0 is the first room of the act
+    pub row: u32,
+    /// Where it's drawn on the map screen
+    pub pos: Vec2,
+    /// Indices of the nodes this one leads to
+    pub next: Vec<usize>,
+}
+
+/// The current act of the current run. Empty means no run is going.
+#[derive(Resource, Default)]
+pub struct RunMap {
+    /// 1 for the first act, goes up after every boss
+    act: u32,
+    nodes: Vec<MapNode>,
+    /// Where the bird is. `None` at the start of an act.
+    current: Option<usize>,
+    /// Which of `choices()` is highlighted
+    selected: usize,
+}
+impl RunMap {
+    const ROW_WIDTHS: RangeInclusive<usize> = 2..=3;
+    const COL_SPACING: f32 = 70.0;
+    const ROW_SPACING: f32 = 30.0;
+    const BOTTOM: f32 = -70.0;
+    /// Chance a node also connects to a neighbor of the one straight ahead
+    const BRANCH_CHANCE: f32 = 0.4;
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Ends the run
+    pub fn clear(&mut self) {
+        *self = default();
+    }
+
+    pub fn act(&self) -> u32 {
+        self.act
+    }
+
+    /// Row of the node the bird is on, `None` at the start of an act
+    fn current_row(&self) -> Option<u32> {
+        self.current.map(|ix| self.nodes[ix].row)
+    }
+
+    /// Different for every row of every act. Rooms that can show up more than once an act (rests, shops)
+    /// add this to their `RunRng::enter_room` index so they don't all roll the same.
+    pub fn node_seed(&self) -> u32 {
+        self.act * 100 + self.current_row().unwrap_or(0)
+    }
+
+    /// The act is over once the boss has been picked
+    fn finished_act(&self) -> bool {
+        self.current
+            .is_some_and(|ix| self.nodes[ix].kind == MapNodeKind::Boss)
+    }
+
+    /// The nodes that can be picked right now
+    pub fn choices(&self) -> Vec<usize> {
+        match self.current {
+            Some(ix) => self.nodes[ix].next.clone(),
+            None => (0..self.nodes.len())
+                .filter(|ix| self.nodes[*ix].row == 0)
+                .collect(),
+        }
+    }
+
+    fn generate_act(&mut self, act: u32, rng: &mut impl Rng) {
+        self.act = act;
+        self.nodes.clear();
+        self.current = None;
+        self.selected = 0;
+
+        let mut rows: Vec<Vec<usize>> = vec![];
+        for row in 0..=ENCOUNTERS_PER_BOSS {
+            let width = if row == ENCOUNTERS_PER_BOSS {
+                1
+            } else {
+                rng.gen_range(Self::ROW_WIDTHS)
+            };
+            let mut ixs = vec![];
+            for col in 0..width {
+                let kind = if row == ENCOUNTERS_PER_BOSS {
+                    MapNodeKind::Boss
+                } else if row == 0 {
+                    // Ease in
+                    MapNodeKind::Combat
+                } else if row == ENCOUNTERS_PER_BOSS - 1 && col == 0 {
+                    // Always a chance to catch your breath before the boss
+                    MapNodeKind::Rest
+                } else {
+                    MapNodeKind::roll(rng)
+                };
+                let x = (col as f32 - (width - 1) as f32 / 2.0) * Self::COL_SPACING;
+                let y = Self::BOTTOM + row as f32 * Self::ROW_SPACING;
+                ixs.push(self.nodes.len());
+                self.nodes.push(MapNode {
+                    kind,
+                    row,
+                    pos: Vec2::new(x, y),
+                    next: vec![],
+                });
+            }
+            rows.push(ixs);
+        }
+
+        for pair in rows.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            for (col, &from_ix) in from.iter().enumerate() {
+                // Straight ahead, more or less
+                let frac = col as f32 / (from.len() - 1).max(1) as f32;
+                let ahead = (frac * (to.len() - 1) as f32).round() as usize;
+                self.nodes[from_ix].next.push(to[ahead]);
+                if rng.gen::<f32>() < Self::BRANCH_CHANCE {
+                    let side = if rng.gen() {
+                        ahead + 1
+                    } else {
+                        ahead.wrapping_sub(1)
+                    };
+                    if side < to.len() {
+                        self.nodes[from_ix].next.push(to[side]);
+                    }
+                }
+            }
+            // Nothing should be unreachable
+            for &to_ix in to {
+                if from.iter().any(|ix| self.nodes[*ix].next.contains(&to_ix)) {
+                    continue;
+                }
+                let to_x = self.nodes[to_ix].pos.x;
+                if let Some(&closest) = from.iter().min_by(|a, b| {
+                    let a = (self.nodes[**a].pos.x - to_x).abs();
+                    let b = (self.nodes[**b].pos.x - to_x).abs();
+                    a.total_cmp(&b)
+                }) {
+                    self.nodes[closest].next.push(to_ix);
+                }
+            }
+        }
+    }
+
+    /// The room a node stands for
+    fn room_state(&self, ix: usize) -> RoomState {
+        let node = &self.nodes[ix];
+        let index = (self.act - 1) * ENCOUNTERS_PER_BOSS + node.row + 1;
+        match node.kind {
+            MapNodeKind::Combat => RoomState::nth_encounter(index, false),
+            MapNodeKind::Elite => RoomState::nth_encounter(index, true),
+            MapNodeKind::Rest => RoomState::Rest,
+            MapNodeKind::Shop => RoomState::Shop,
+            MapNodeKind::Boss => RoomState::Boss(BossState {
+                level: self.act,
+                progress: EncounterProgress::Entering,
+            }),
+        }
+    }
+}
+
+#[derive(Component)]
+struct MapNodeMarker {
+    ix: usize,
+}
+
+#[derive(Component)]
+struct MapText;
+
+/// Thin quad from `a` to `b`, for drawing paths with sprites
+fn line_points(a: Vec2, b: Vec2, width: f32) -> Vec<Vec2> {
+    let normal = (b - a).normalize_or_zero().perp() * width / 2.0;
+    vec![a + normal, b + normal, b - normal, a - normal]
+}
+
+/// Fresh skills and a fresh seed. Story runs start on the map, endless runs in their first room.
+pub(super) fn start_new_run(
+    permanent_skills: &mut PermanentSkill,
+    ephemeral_skills: &mut EphemeralSkill,
+    character: BirdCharacter,
+    run_rng: &mut RunRng,
+) {
+    // Enfource minimums
+    // Mind is too messy to keep the full state transitions in the working set
+    // If for whatever reason we end up here with stupid defaults, fix them
+    // Once again, skill issue
+    let base_skill = character.def().base_skill;
+    if permanent_skills.get_num_launches() < base_skill.get_num_launches() {
+        permanent_skills.force_set_num_launches(base_skill.get_num_launches());
+    }
+    if permanent_skills.get_num_bullets() < base_skill.get_num_bullets() {
+        permanent_skills.force_set_num_bullets(base_skill.get_num_bullets());
+    }
+    if permanent_skills.get_max_health() < base_skill.get_max_health() {
+        permanent_skills.force_set_max_health(base_skill.get_max_health());
+    }
+    // YO we found it, the place where attempts start
+    ephemeral_skills.start_attempt(permanent_skills);
+    run_rng.start_run();
+}
+
+fn clear_run(mut run_map: ResMut<RunMap>) {
+    run_map.clear();
+}
+
+fn enter_map(
+    mut commands: Commands,
+    room_root: Res<RoomRoot>,
+    menu_root: Res<MenuRoot>,
+    mut run_map: ResMut<RunMap>,
+    mut run_rng: ResMut<RunRng>,
+    mut permanent_skills: ResMut<PermanentSkill>,
+    mut ephemeral_skills: ResMut<EphemeralSkill>,
+    character: Res<BirdCharacter>,
+) {
+    commands.entity(room_root.eid()).despawn_descendants();
+
+    if run_map.is_empty() {
+        start_new_run(
+            &mut permanent_skills,
+            &mut ephemeral_skills,
+            *character,
+            &mut run_rng,
+        );
+    }
+    if run_map.is_empty() || run_map.finished_act() {
+        let act = run_map.act() + 1;
+        // Way past any encounter (or boss) index
+        run_rng.enter_room(2000 + act);
+        run_map.generate_act(act, run_rng.stream(RngStream::Map));
+    }
+
+    BackgroundKind::SkyOnly.spawn(default(), menu_root.eid(), &mut commands);
+    let choices = run_map.choices();
+    for (ix, node) in run_map.nodes.iter().enumerate() {
+        for next_ix in &node.next {
+            let next = &run_map.nodes[*next_ix];
+            let color: Color = if Some(ix) == run_map.current {
+                Color::WHITE
+            } else {
+                tailwind::ZINC_500.into()
+            };
+            commands
+                .spawn((
+                    Name::new("map_path"),
+                    spat_tran(0.0, 0.0, 100.0),
+                    multi!(anim_man!({
+                        path: "sprites/default.png",
+                        size: (1, 1),
+                        color: color,
+                    })
+                    .with_points(line_points(node.pos, next.pos, 1.0))
+                    .with_render_layers(MenuCamera::render_layers())),
+                ))
+                .set_parent(menu_root.eid());
+        }
+        let past = run_map
+            .current
+            .is_some_and(|current| run_map.nodes[current].row >= node.row);
+        let color = if past && Some(ix) != run_map.current {
+            // Roads not taken (or already taken)
+            tailwind::ZINC_600.into()
+        } else {
+            node.kind.color()
+        };
+        let radius = if node.kind == MapNodeKind::Boss {
+            9.0
+        } else {
+            6.0
+        };
+        commands
+            .spawn((
+                Name::new(format!("map_node_{ix}")),
+                MapNodeMarker { ix },
+                spat_tran(node.pos.x, node.pos.y, 101.0),
+                multi!(anim_man!({
+                    path: "sprites/default.png",
+                    size: (1, 1),
+                    color: color,
+                })
+                .with_points(regular_polygon(12, 0.0, radius))
+                .with_render_layers(MenuCamera::render_layers())),
+            ))
+            .set_parent(menu_root.eid())
+            .with_children(|parent| {
+                if choices.contains(&ix) {
+                    parent.spawn((
+                        Text2dBundle {
+                            text: Text::from_section(
+                                node.kind.label(),
+                                TextStyle {
+                                    font_size: 8.0,
+                                    color: Color::WHITE,
+                                    ..default()
+                                },
+                            ),
+                            transform: Transform::from_translation(Vec3::new(0.0, -12.0, 1.0)),
+                            ..default()
+                        },
+                        MenuCamera::render_layers(),
+                    ));
+                }
+            });
+    }
+    commands
+        .spawn((
+            Name::new("map_text"),
+            MapText,
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 9.0,
+                        color: Color::srgb_u8(245, 219, 203),
+                        ..default()
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                transform: Transform::from_translation(Vec3::new(0.0, 82.0, 100.0)),
+                ..default()
+            },
+            MenuCamera::render_layers(),
+        ))
+        .set_parent(menu_root.eid());
+}
+
+fn exit_map(mut commands: Commands, menu_root: Res<MenuRoot>) {
+    commands.entity(menu_root.eid()).despawn_descendants();
+}
+
+fn update_map(
+    mut input: EventReader<NonGameInput>,
+    current_transition: Res<State<MetaTransitionState>>,
+    mut next_transition: ResMut<NextState<MetaTransitionState>>,
+    mut run_map: ResMut<RunMap>,
+    mut nodes: Query<(&MapNodeMarker, &mut Transform)>,
+    mut text: Query<&mut Text, With<MapText>>,
+    mut commands: Commands,
+) {
+    let choices = run_map.choices();
+    if choices.is_empty() {
+        return;
+    }
+    run_map.selected = run_map.selected.min(choices.len() - 1);
+    if let Some(input) = input.read().last() {
+        match input {
+            NonGameInput::Continue => {
+                if matches!(current_transition.get(), MetaTransitionState::Stable) {
+                    let ix = choices[run_map.selected];
+                    run_map.current = Some(ix);
+                    run_map.selected = 0;
+                    next_transition.set(
+                        TransitionKind::FadeToBlack
+                            .to_meta_transition_state(1.0, run_map.room_state(ix).to_meta_state()),
+                    );
+                    commands.spawn(SoundEffect::universal(
+                        "sound_effects/lenny_go_next.ogg",
+                        0.1,
+                    ));
+                    return;
+                }
+            }
+            NonGameInput::Left => {
+                run_map.selected = (run_map.selected + choices.len() - 1) % choices.len();
+                commands.spawn(SoundEffect::universal("sound_effects/fly_spot.ogg", 0.2));
+            }
+            NonGameInput::Right => {
+                run_map.selected = (run_map.selected + 1) % choices.len();
+                commands.spawn(SoundEffect::universal("sound_effects/fly_spot.ogg", 0.2));
+            }
+            NonGameInput::Up | NonGameInput::Down => (),
+        }
+    }
+    let selected = choices[run_map.selected];
+    for (marker, mut tran) in &mut nodes {
+        let scale = if marker.ix == selected { 1.5 } else { 1.0 };
+        tran.scale = Vec3::new(scale, scale, 1.0);
+    }
+    if let Ok(mut text) = text.get_single_mut() {
+        text.sections[0].value = format!(
+            "Act {}: {}\n< A / D >    Enter to go",
+            run_map.act(),
+            run_map.nodes[selected].kind.label()
+        );
+    }
+}
+
+pub(super) fn register_map(app: &mut App) {
+    app.init_resource::<RunMap>();
+
+    app.add_systems(OnEnter(RoomState::Dead.to_meta_state()), clear_run);
+    app.add_systems(OnEnter(TutorialState::Dead.to_meta_state()), clear_run);
+    app.add_systems(OnEnter(MetaState::Map), enter_map);
+    app.add_systems(OnExit(MetaState::Map), exit_map);
+    app.add_systems(Update, update_map.run_if(in_state(MetaState::Map)));
+}
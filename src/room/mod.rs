@@ -1,12 +1,37 @@
 use crate::prelude::*;
 
+mod boss;
 mod dead;
+pub mod difficulty;
+mod editor;
 mod encounter;
+pub mod endless;
+pub mod layout;
+pub mod map;
+pub mod objective;
+mod rest;
+pub mod room_def;
+mod shop;
+
+pub use difficulty::*;
+pub use endless::*;
+pub use layout::*;
+pub use map::*;
+pub use objective::*;
+pub use room_def::*;
 
 pub(super) struct RoomPlugin;
 impl Plugin for RoomPlugin {
     fn build(&self, app: &mut App) {
+        boss::register_bosses(app);
         dead::register_dead(app);
+        editor::register_editor(app);
         encounter::register_encounters(app);
+        endless::register_endless(app);
+        map::register_map(app);
+        objective::register_objectives(app);
+        rest::register_rest(app);
+        shop::register_shop(app);
+        room_def::register_room_defs(app);
     }
 }
@@ -0,0 +1,348 @@
+//! What the bird has to do to clear an encounter. Usually it's kill everything, but not always.
+
+use std::f32::consts::PI;
+
+use crate::prelude::*;
+use crate::tutorial::targets::{PracticeTarget, PracticeTargetBundle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum ObjectiveKind {
+    /// The classic, kill everything that spawns
+    KillAll,
+    /// Stay alive for this long
+    Survive { secs: f32 },
+    /// Keep the egg in one piece for this long. If it breaks, so do you.
+    ProtectEgg { secs: f32 },
+    /// Grab this many floating pickups
+    Collect { count: u32 },
+    /// Pop this many targets
+    Targets { count: u32 },
+}
+impl ObjectiveKind {
+    /// First room of a run is always a plain fight, after that it's a coin flip
+    fn roll(encounter: &EncounterState, rng: &mut impl Rng) -> Self {
+        let scripted = matches!(encounter.kind, EncounterKind::PukebeakOnly);
+        if scripted || encounter.index() <= 1 || rng.gen_bool(0.5) {
+            return Self::KillAll;
+        }
+        let difficulty = encounter.difficulty;
+        match rng.gen_range(0..4) {
+            0 => Self::Survive {
+                secs: 25.0 + 5.0 * difficulty as f32,
+            },
+            1 => Self::ProtectEgg {
+                secs: 20.0 + 5.0 * difficulty as f32,
+            },
+            2 => Self::Collect {
+                count: 5 + difficulty,
+            },
+            _ => Self::Targets {
+                count: 4 + difficulty,
+            },
+        }
+    }
+
+    fn help(&self) -> String {
+        match self {
+            Self::KillAll => "Kill them all.".to_string(),
+            Self::Survive { secs } => format!("Survive for {secs} seconds."),
+            Self::ProtectEgg { secs } => format!("Keep the egg safe for {secs} seconds."),
+            Self::Collect { count } => format!("Grab all {count} feathers."),
+            Self::Targets { count } => format!("Pop all {count} targets."),
+        }
+    }
+}
+
+/// The objective for the room the bird is in. Boss rooms are always `KillAll`.
+#[derive(Resource, Debug, Clone)]
+pub struct RoomObjective {
+    pub kind: ObjectiveKind,
+    elapsed: f32,
+    /// Pickups grabbed or targets popped
+    done: u32,
+}
+impl Default for RoomObjective {
+    fn default() -> Self {
+        Self::new(ObjectiveKind::KillAll)
+    }
+}
+impl RoomObjective {
+    fn new(kind: ObjectiveKind) -> Self {
+        Self {
+            kind,
+            elapsed: 0.0,
+            done: 0,
+        }
+    }
+
+    /// 0 to 1, for the progress bar
+    pub fn frac_complete(&self, bird: &Bird) -> f32 {
+        match self.kind {
+            ObjectiveKind::KillAll => bird.frac_killed(),
+            // Killing everything early gets you out early too
+            ObjectiveKind::Survive { secs } | ObjectiveKind::ProtectEgg { secs } => {
+                (self.elapsed / secs).max(bird.frac_killed()).min(1.0)
+            }
+            ObjectiveKind::Collect { count } | ObjectiveKind::Targets { count } => {
+                (self.done as f32 / count as f32).min(1.0)
+            }
+        }
+    }
+
+    pub fn is_complete(&self, bird: &Bird) -> bool {
+        self.frac_complete(bird) >= 1.0
+    }
+}
+
+/// Floats in place until the bird flies through it
+#[derive(Component)]
+struct ObjectivePickup;
+
+#[derive(Bundle)]
+struct ObjectivePickupBundle {
+    name: Name,
+    pickup: ObjectivePickup,
+    trigger: ObjectiveTriggerPhysicsBundle,
+    multi: MultiAnimationManager,
+}
+impl ObjectivePickupBundle {
+    const RADIUS: f32 = 4.0;
+
+    fn new(pos: Vec2) -> Self {
+        let points = (0..4)
+            .map(|ix| Vec2::X.my_rotate(PI / 2.0 * ix as f32) * Self::RADIUS)
+            .collect::<Vec<_>>();
+        Self {
+            name: Name::new("objective_pickup"),
+            pickup: ObjectivePickup,
+            trigger: ObjectiveTriggerPhysicsBundle::new(pos, Self::RADIUS + 2.0),
+            multi: multi!([
+                (
+                    "core",
+                    anim_man!({
+                        path: "sprites/default.png",
+                        size: (1, 1),
+                        color: Color::from(tailwind::SKY_200),
+                    })
+                    .with_points(points),
+                ),
+                (
+                    "light",
+                    anim_man!({
+                        path: "lenny/heart_light.png",
+                        size: (36, 36),
+                    })
+                    .with_render_layers(LightCamera::render_layers()),
+                ),
+            ]),
+        }
+    }
+}
+
+#[derive(Component)]
+struct ObjectiveEgg;
+
+#[derive(Bundle)]
+struct ObjectiveEggBundle {
+    name: Name,
+    egg: ObjectiveEgg,
+    health: Health,
+    trigger: ObjectiveTriggerPhysicsBundle,
+    multi: MultiAnimationManager,
+}
+impl ObjectiveEggBundle {
+    fn new(pos: Vec2) -> Self {
+        Self {
+            name: Name::new("objective_egg"),
+            egg: ObjectiveEgg,
+            health: Health::new(3)
+                .with_i_frames(1.0)
+                .with_vulnerable_to([TriggerKind::BulletBad, TriggerKind::SimpBody]),
+            trigger: ObjectiveTriggerPhysicsBundle::new(pos, 9.0),
+            multi: multi!([
+                (
+                    "core",
+                    anim_man!({
+                        path: "lenny/egg.png",
+                        size: (24, 24),
+                    })
+                ),
+                (
+                    "light",
+                    anim_man!({
+                        path: "lenny/spotlight.png",
+                        size: (48, 48),
+                    })
+                    .with_render_layers(LightCamera::render_layers())
+                ),
+            ]),
+        }
+    }
+}
+
+/// Rolls the objective and puts down whatever it needs. Runs after the room is made so it can
+/// see the platforms and keep out of their way.
+/// NOTE: Everything was just spawned, so `GlobalTransform` hasn't caught up yet. Use `Transform`.
+fn setup_objective(
+    mut commands: Commands,
+    room_root: Res<RoomRoot>,
+    encounter_state: Option<Res<State<EncounterState>>>,
+    mut objective: ResMut<RoomObjective>,
+    mut run_rng: ResMut<RunRng>,
+    statics: Query<(&Bounds, &StaticProvider, &Transform)>,
+    birds: Query<&Transform, With<Bird>>,
+    spawners: Query<&EnemySpawner>,
+    room_bounds: Res<RoomBounds>,
+) {
+    let Some(encounter_state) = encounter_state else {
+        // Bosses just want you to kill them
+        *objective = default();
+        return;
+    };
+    let rng = run_rng.stream(RngStream::Objective);
+    *objective = RoomObjective::new(ObjectiveKind::roll(encounter_state.get(), rng));
+
+    let count = match objective.kind {
+        ObjectiveKind::KillAll | ObjectiveKind::Survive { .. } => return,
+        ObjectiveKind::ProtectEgg { .. } => 1,
+        ObjectiveKind::Collect { count } | ObjectiveKind::Targets { count } => count,
+    };
+    let mut avoid = statics
+        .iter()
+        .filter(|(_, provider, _)| provider.kind == StaticProviderKind::Sticky)
+        // Only how far things reach matters for avoiding them, not which way they're turned
+        .map(|(bounds, _, tran)| (bounds.get_shape().clone(), tran.translation.truncate(), 0.0))
+        .collect::<Vec<_>>();
+    avoid.extend(birds.iter().map(|tran| {
+        (
+            Shape::Circle { radius: 16.0 },
+            tran.translation.truncate(),
+            0.0,
+        )
+    }));
+    avoid.extend(
+        spawners
+            .iter()
+            .flat_map(|spawner| spawner.poses.iter())
+            .map(|pos| (Shape::Circle { radius: 12.0 }, *pos, 0.0)),
+    );
+    let bot_left = -(room_bounds.size / 2.0 - Vec2::ONE * 12.0);
+    let placements = LayoutGen::new(count, bot_left, -bot_left)
+        .with_radius(8.0, 8.0)
+        .with_gap(4.0)
+        .generate(rng, &avoid);
+
+    for (ix, (_, pos, _)) in placements.into_iter().enumerate() {
+        let mut entity = match objective.kind {
+            ObjectiveKind::ProtectEgg { .. } => commands.spawn(ObjectiveEggBundle::new(pos)),
+            ObjectiveKind::Collect { .. } => commands.spawn(ObjectivePickupBundle::new(pos)),
+            _ => commands.spawn(PracticeTargetBundle::new(
+                pos,
+                &format!("objective_{ix}"),
+                None,
+            )),
+        };
+        entity.set_parent(room_root.eid());
+    }
+}
+
+fn show_objective(objective: Res<RoomObjective>, mut help_text: ResMut<HelpText>) {
+    if objective.kind != ObjectiveKind::KillAll {
+        help_text.set(&objective.kind.help());
+    }
+}
+
+fn update_objective(
+    mut objective: ResMut<RoomObjective>,
+    pickups: Query<(Entity, &TriggerReceiver), With<ObjectivePickup>>,
+    targets: Query<&PracticeTarget>,
+    eggs: Query<&Health, With<ObjectiveEgg>>,
+    collisions: Query<&TriggerCollisionRecord>,
+    mut ephemeral_skills: ResMut<EphemeralSkill>,
+    mut commands: Commands,
+    time: Res<Time>,
+    bullet_time: Res<BulletTime>,
+) {
+    objective.elapsed += time.delta_seconds() * bullet_time.factor();
+    match objective.kind {
+        ObjectiveKind::Collect { .. } => {
+            for (eid, rx) in &pickups {
+                let grabbed = rx.collisions.iter().any(|cid| {
+                    collisions
+                        .get(*cid)
+                        .is_ok_and(|record| record.other_kind == TriggerKind::Bird)
+                });
+                if grabbed {
+                    objective.done += 1;
+                    commands.entity(eid).despawn_recursive();
+                    commands.spawn(SoundEffect::universal("sound_effects/fly_spot.ogg", 0.2));
+                }
+            }
+        }
+        ObjectiveKind::Targets { count } => {
+            // Popped targets despawn once they're done exploding, so count what's left standing
+            let standing = targets
+                .iter()
+                .filter(|target| target.time_dead.is_none())
+                .count() as u32;
+            objective.done = count.saturating_sub(standing);
+        }
+        ObjectiveKind::ProtectEgg { .. } => {
+            if eggs.iter().any(|health| health.is_dead()) {
+                // No egg, no bird
+                ephemeral_skills.set_current_health(0);
+            }
+        }
+        ObjectiveKind::KillAll | ObjectiveKind::Survive { .. } => {}
+    }
+}
+
+/// Anything still around when the objective is done just goes away.
+/// Enemies get despawned outright, not killed, so elite affixes don't split or explode into a cleared room.
+fn clear_leftovers(
+    objective: Res<RoomObjective>,
+    leftovers: Query<
+        Entity,
+        Or<(
+            With<EnemySpawner>,
+            With<ScriptedWaves>,
+            With<SpawnTelegraph>,
+            With<Unhatched>,
+            With<ObjectivePickup>,
+            With<ObjectiveEgg>,
+        )>,
+    >,
+    enemies: Query<&Parent, (With<EnemyHurtbox>, Without<Dying>)>,
+    mut commands: Commands,
+) {
+    if objective.kind == ObjectiveKind::KillAll {
+        return;
+    }
+    for eid in &leftovers {
+        commands.entity(eid).despawn_recursive();
+    }
+    // Hurtboxes hang off the guide, take the whole enemy
+    for parent in &enemies {
+        commands.entity(parent.get()).despawn_recursive();
+    }
+}
+
+pub(super) fn register_objectives(app: &mut App) {
+    app.init_resource::<RoomObjective>();
+    app.add_systems(
+        OnEnter(EncounterProgress::Entering),
+        setup_objective
+            .after(super::encounter::create_room)
+            .after(super::boss::create_boss_room),
+    );
+    app.add_systems(OnEnter(EncounterProgress::Fighting), show_objective);
+    app.add_systems(
+        Update,
+        update_objective
+            .run_if(in_state(EncounterProgress::Fighting))
+            .run_if(in_state(PhysicsState::Active))
+            .after(PhysicsSet)
+            .after(DamageSet),
+    );
+    app.add_systems(OnEnter(EncounterProgress::Meandering), clear_leftovers);
+}
@@ -0,0 +1,75 @@
+//! A quiet room off the map. Full health, nothing to fight, shoot the arrow to move on.
+
+use crate::prelude::*;
+
+fn create_rest_room(
+    mut commands: Commands,
+    room_root: Res<RoomRoot>,
+    mut music_manager: ResMut<MusicManager>,
+    mut ephemeral_skills: ResMut<EphemeralSkill>,
+    character: Res<BirdCharacter>,
+    mut run_rng: ResMut<RunRng>,
+    mut help_text: ResMut<HelpText>,
+    run_map: Res<RunMap>,
+) {
+    commands.entity(room_root.eid()).despawn_descendants();
+    music_manager.fade_to_song(MusicKind::SandCastles);
+    run_rng.enter_room(3000 + run_map.node_seed());
+
+    let max_health = ephemeral_skills.get_max_health();
+    ephemeral_skills.set_current_health(max_health);
+
+    BackgroundKind::Forest.spawn(default(), room_root.eid(), &mut commands);
+    commands
+        .spawn(HardPlatformBundle::around_room())
+        .set_parent(room_root.eid());
+
+    let bot_left = -(IDEAL_VEC_f32 / 2.0 - Vec2::ONE * 6.0);
+    let top_right = -bot_left;
+    let bird_pos = Vec2::new(0.0, -40.0);
+    let go_next_pos = Vec2::new(0.0, 40.0);
+    let avoid = vec![
+        (Shape::Circle { radius: 7.0 }, bird_pos, 0.0),
+        (Shape::Circle { radius: 12.0 }, go_next_pos, 0.0),
+    ];
+    let circle_placements = LayoutGen::new(5, bot_left, top_right)
+        .with_radius(10.0, 20.0)
+        .with_rot(-1.0, 1.0)
+        .with_gap(30.0)
+        .generate(run_rng.stream(RngStream::Layout), &avoid);
+    for (ix, (shape, pos, rot)) in circle_placements.into_iter().enumerate() {
+        commands
+            .spawn(StickyPlatformBundle::new(
+                format!("shape_{ix}").as_str(),
+                pos,
+                shape,
+            ))
+            .insert(DynoRot { rot })
+            .set_parent(room_root.eid());
+    }
+
+    commands
+        .spawn(BirdBundle::new(
+            *character,
+            bird_pos,
+            default(),
+            ephemeral_skills.get_num_launches(),
+            ephemeral_skills.get_num_bullets(),
+            0,
+        ))
+        .set_parent(room_root.eid());
+    commands
+        .spawn(GoNextBundle::new(go_next_pos))
+        .set_parent(room_root.eid());
+
+    help_text.set("A quiet spot. Health restored.\nShoot the arrow when you're ready.");
+}
+
+fn exit_rest_room(mut help_text: ResMut<HelpText>) {
+    help_text.clear();
+}
+
+pub(super) fn register_rest(app: &mut App) {
+    app.add_systems(OnEnter(RoomState::Rest.to_meta_state()), create_rest_room);
+    app.add_systems(OnExit(RoomState::Rest.to_meta_state()), exit_rest_room);
+}
@@ -0,0 +1,408 @@
+//! Hand-built rooms, described in `assets/rooms/<name>.room.json`.
+//! A def lists everything `create_room` would otherwise make up: background, platforms, spawners, the bird and triggers.
+//! Set pieces get mixed in with the procedural rooms (see `RoomDefs::pick`).
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+
+use crate::prelude::*;
+
+/// `Shape`, but in a form that's nice to write by hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ShapeDef {
+    Circle {
+        radius: f32,
+    },
+    /// Centered on the platform's pos
+    Rect {
+        width: f32,
+        height: f32,
+    },
+    /// CLOCKWISE, same as `Shape::Polygon`
+    Polygon {
+        points: Vec<[f32; 2]>,
+    },
+}
+impl ShapeDef {
+    pub fn to_shape(&self) -> Shape {
+        match self {
+            Self::Circle { radius } => Shape::Circle { radius: *radius },
+            Self::Rect { width, height } => Shape::Polygon {
+                points: simple_rect(*width, *height),
+            },
+            Self::Polygon { points } => Shape::Polygon {
+                points: points
+                    .iter()
+                    .map(|point| Vec2::from_array(*point))
+                    .collect(),
+            },
+        }
+    }
+
+    pub fn from_shape(shape: &Shape) -> Self {
+        match shape {
+            Shape::Circle { radius } => Self::Circle { radius: *radius },
+            Shape::Polygon { points } => Self::Polygon {
+                points: points.iter().map(|point| point.to_array()).collect(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlatformKind {
+    Sticky,
+    Hard,
+    /// Sticky, but only for a moment
+    Crumbling,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformDef {
+    pub kind: PlatformKind,
+    pub pos: [f32; 2],
+    pub shape: ShapeDef,
+    /// How fast it spins
+    #[serde(default)]
+    pub rot: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnerDef {
+    /// Names in the `EnemyRegistry`, with weights
+    pub table: Vec<(String, f32)>,
+    pub poses: Vec<[f32; 2]>,
+    /// Popped off the back
+    pub batch_sizes: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoomTriggerKind {
+    Heart,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomTriggerDef {
+    pub kind: RoomTriggerKind,
+    pub pos: [f32; 2],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropDef {
+    pub kind: PropKind,
+    pub pos: [f32; 2],
+}
+
+fn default_laser_period() -> f32 {
+    4.0
+}
+
+/// Angles are in degrees, 0 is pointing right
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HazardDef {
+    Spikes {
+        pos: [f32; 2],
+        length: f32,
+        #[serde(default)]
+        angle: f32,
+    },
+    /// Fires out of its right side (after rotating)
+    Laser {
+        pos: [f32; 2],
+        #[serde(default)]
+        angle: f32,
+        #[serde(default = "default_laser_period")]
+        period: f32,
+        /// Seconds until the first shot, so lasers in the same room can take turns
+        #[serde(default)]
+        delay: f32,
+    },
+}
+impl HazardDef {
+    pub fn spawn(&self, parent: Entity, commands: &mut Commands) {
+        match *self {
+            Self::Spikes { pos, length, angle } => {
+                commands
+                    .spawn(SpikesBundle::new(
+                        Vec2::from_array(pos),
+                        length,
+                        angle.to_radians(),
+                    ))
+                    .set_parent(parent);
+            }
+            Self::Laser {
+                pos,
+                angle,
+                period,
+                delay,
+            } => {
+                commands
+                    .spawn(LaserEmitterBundle::new(
+                        Vec2::from_array(pos),
+                        angle.to_radians(),
+                        period,
+                        delay,
+                    ))
+                    .set_parent(parent);
+            }
+        }
+    }
+}
+
+fn default_background() -> BackgroundKind {
+    BackgroundKind::Forest
+}
+fn default_border() -> bool {
+    true
+}
+fn default_size() -> [f32; 2] {
+    IDEAL_VEC_f32.to_array()
+}
+
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct RoomDef {
+    pub name: String,
+    /// Which encounters this room can stand in for. Empty means it never gets picked on its own.
+    #[serde(default)]
+    pub encounters: Vec<EncounterKind>,
+    #[serde(default)]
+    pub min_difficulty: u32,
+    #[serde(default = "default_background")]
+    pub background: BackgroundKind,
+    /// Put the usual hard border around the room
+    #[serde(default = "default_border")]
+    pub border: bool,
+    /// Width and height, centered on the origin. Anything bigger than the screen scrolls.
+    #[serde(default = "default_size")]
+    pub size: [f32; 2],
+    pub bird: [f32; 2],
+    #[serde(default)]
+    pub platforms: Vec<PlatformDef>,
+    #[serde(default)]
+    pub spawners: Vec<SpawnerDef>,
+    /// Name of a wave script to run, on top of any spawners
+    #[serde(default)]
+    pub waves: Option<String>,
+    #[serde(default)]
+    pub triggers: Vec<RoomTriggerDef>,
+    #[serde(default)]
+    pub hazards: Vec<HazardDef>,
+    #[serde(default)]
+    pub props: Vec<PropDef>,
+}
+impl RoomDef {
+    pub fn bird_pos(&self) -> Vec2 {
+        Vec2::from_array(self.bird)
+    }
+
+    pub fn size(&self) -> Vec2 {
+        Vec2::from_array(self.size)
+    }
+
+    /// Spawners with nowhere to spawn get skipped (and don't count towards the kills needed)
+    fn live_spawners(&self) -> impl Iterator<Item = &SpawnerDef> {
+        self.spawners
+            .iter()
+            .filter(|spawner| !spawner.poses.is_empty())
+    }
+
+    pub fn fits(&self, encounter: &EncounterState) -> bool {
+        self.encounters.contains(&encounter.kind) && encounter.difficulty >= self.min_difficulty
+    }
+
+    /// How many kills the bird needs to clear this room
+    pub fn num_enemies(&self, wave_scripts: &WaveScripts, scripts: &Assets<WaveScript>) -> u32 {
+        let from_spawners = self
+            .live_spawners()
+            .map(|spawner| spawner.batch_sizes.iter().sum::<usize>() as u32)
+            .sum::<u32>();
+        let from_waves = self
+            .waves
+            .as_ref()
+            .and_then(|name| wave_scripts.get(name, scripts))
+            .map_or(0, |script| script.total());
+        from_spawners + from_waves
+    }
+
+    /// Spawns everything but the bird under `parent`
+    pub fn spawn(&self, parent: Entity, commands: &mut Commands, wave_scripts: &WaveScripts) {
+        self.background.spawn(default(), parent, commands);
+        if self.border {
+            commands
+                .spawn(HardPlatformBundle::around_room_sized(self.size()))
+                .set_parent(parent);
+        }
+        for (ix, platform) in self.platforms.iter().enumerate() {
+            let name = format!("{}_{ix}", self.name);
+            let pos = Vec2::from_array(platform.pos);
+            let shape = platform.shape.to_shape();
+            let mut platform_commands = match platform.kind {
+                PlatformKind::Sticky => {
+                    commands.spawn(StickyPlatformBundle::new(&name, pos, shape))
+                }
+                PlatformKind::Hard => commands.spawn(HardPlatformBundle::new(&name, pos, shape)),
+                PlatformKind::Crumbling => {
+                    commands.spawn(CrumblingPlatformBundle::new(&name, pos, shape))
+                }
+            };
+            if platform.rot != 0.0 {
+                platform_commands.insert(DynoRot { rot: platform.rot });
+            }
+            platform_commands.set_parent(parent);
+        }
+        for spawner in self.live_spawners() {
+            let table = spawner
+                .table
+                .iter()
+                .map(|(kind, weight)| (kind.as_str(), *weight))
+                .collect::<Vec<_>>();
+            commands
+                .spawn(EnemySpawnerBundle::new(
+                    &table,
+                    spawner
+                        .poses
+                        .iter()
+                        .map(|pos| Vec2::from_array(*pos))
+                        .collect(),
+                    spawner.batch_sizes.clone(),
+                ))
+                .set_parent(parent);
+        }
+        if let Some(handle) = self
+            .waves
+            .as_ref()
+            .and_then(|name| wave_scripts.handle(name))
+        {
+            commands
+                .spawn(ScriptedWavesBundle::new(handle))
+                .set_parent(parent);
+        }
+        for hazard in &self.hazards {
+            hazard.spawn(parent, commands);
+        }
+        for prop in &self.props {
+            commands
+                .spawn(PropBundle::new(prop.kind, Vec2::from_array(prop.pos)))
+                .set_parent(parent);
+        }
+        for trigger in &self.triggers {
+            let pos = Vec2::from_array(trigger.pos);
+            match trigger.kind {
+                RoomTriggerKind::Heart => {
+                    commands.spawn(HeartBundle::new(pos)).set_parent(parent);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct RoomDefLoader;
+impl AssetLoader for RoomDefLoader {
+    type Asset = RoomDef;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["room.json"]
+    }
+}
+
+/// Every set piece the game knows about
+#[derive(Resource, Default)]
+pub struct RoomDefs {
+    map: HashMap<String, Handle<RoomDef>>,
+}
+impl RoomDefs {
+    /// What ships with the game. Only used when there's no folder to look in (web builds).
+    const NAMES: [&'static str; 2] = ["pillars", "gauntlet"];
+    /// Chance an encounter uses a set piece (if one fits) instead of being generated
+    const SET_PIECE_CHANCE: f32 = 0.25;
+
+    /// Every `*.room.json` in `assets/rooms`, so rooms saved from the editor just show up
+    fn discover() -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir("assets/rooms") else {
+            return Self::NAMES.iter().map(|name| name.to_string()).collect();
+        };
+        entries
+            .filter_map(|entry| {
+                let file_name = entry.ok()?.file_name();
+                let name = file_name.to_str()?.strip_suffix(".room.json")?;
+                Some(name.to_string())
+            })
+            .collect()
+    }
+
+    fn path(name: &str) -> String {
+        format!("rooms/{name}.room.json")
+    }
+
+    pub fn get<'a>(&self, name: &str, defs: &'a Assets<RoomDef>) -> Option<&'a RoomDef> {
+        self.map.get(name).and_then(|handle| defs.get(handle))
+    }
+
+    /// Sorted, so stepping through them goes the same way every time
+    pub(super) fn names(&self) -> Vec<&str> {
+        let mut names = self.map.keys().map(String::as_str).collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// For a room that was just saved. Picks up new files and reloads ones it already had.
+    pub(super) fn track(&mut self, name: &str, asset_server: &AssetServer) {
+        if self.map.contains_key(name) {
+            asset_server.reload(Self::path(name));
+        } else {
+            self.map
+                .insert(name.to_string(), asset_server.load(Self::path(name)));
+        }
+    }
+
+    /// Maybe picks a set piece for this encounter
+    pub fn pick<'a>(
+        &self,
+        encounter: &EncounterState,
+        defs: &'a Assets<RoomDef>,
+        rng: &mut impl Rng,
+    ) -> Option<&'a RoomDef> {
+        if rng.gen::<f32>() >= Self::SET_PIECE_CHANCE {
+            return None;
+        }
+        // HashMap order isn't stable, seeded runs need it to be
+        let fits = self
+            .names()
+            .into_iter()
+            .filter_map(|name| self.get(name, defs))
+            .filter(|def| def.fits(encounter))
+            .collect::<Vec<_>>();
+        if fits.is_empty() {
+            return None;
+        }
+        Some(fits[rng.gen_range(0..fits.len())])
+    }
+}
+
+pub(super) fn register_room_defs(app: &mut App) {
+    app.init_asset::<RoomDef>();
+    app.init_asset_loader::<RoomDefLoader>();
+    app.insert_resource(RoomDefs::default());
+
+    app.add_systems(
+        Startup,
+        |mut room_defs: ResMut<RoomDefs>, asset_server: Res<AssetServer>| {
+            for name in RoomDefs::discover() {
+                room_defs.track(&name, &asset_server);
+            }
+        },
+    );
+}
@@ -0,0 +1,63 @@
+//! Spend feathers on upgrades. Buying works like the upgrades after dying, but you can buy as many as you can afford.
+
+use crate::prelude::*;
+
+fn create_shop(
+    mut commands: Commands,
+    room_root: Res<RoomRoot>,
+    mut music_manager: ResMut<MusicManager>,
+    mut run_rng: ResMut<RunRng>,
+    run_map: Res<RunMap>,
+) {
+    commands.entity(room_root.eid()).despawn_descendants();
+    music_manager.fade_to_song(MusicKind::SandCastles);
+    run_rng.enter_room(4000 + run_map.node_seed());
+
+    BackgroundKind::Forest.spawn(default(), room_root.eid(), &mut commands);
+    let rng = run_rng.stream(RngStream::Upgrades);
+    for (ix, x) in [-105.0, 0.0, 105.0].into_iter().enumerate() {
+        let kind = UpgradeKind::new(0.3, 0.1, rng);
+        let price = ShopPrice::for_kind(&kind);
+        UpgradeButtonBundle::spawn_for_sale(
+            ix as u32,
+            Vec2::new(x, 0.0),
+            kind,
+            price,
+            &mut commands,
+            room_root.eid(),
+        );
+    }
+}
+
+fn update_shop(
+    mut input: EventReader<NonGameInput>,
+    current_transition: Res<State<MetaTransitionState>>,
+    mut next_transition: ResMut<NextState<MetaTransitionState>>,
+    permanent_skills: Res<PermanentSkill>,
+    mut help_text: ResMut<HelpText>,
+) {
+    help_text.set(&format!(
+        "Feathers: {}\nClick to buy, Enter to leave.",
+        permanent_skills.get_feathers()
+    ));
+    let Some(NonGameInput::Continue) = input.read().last() else {
+        return;
+    };
+    if matches!(current_transition.get(), MetaTransitionState::Stable) {
+        next_transition
+            .set(TransitionKind::FadeToBlack.to_meta_transition_state(1.0, MetaState::Map));
+    }
+}
+
+fn exit_shop(mut help_text: ResMut<HelpText>) {
+    help_text.clear();
+}
+
+pub(super) fn register_shop(app: &mut App) {
+    app.add_systems(OnEnter(RoomState::Shop.to_meta_state()), create_shop);
+    app.add_systems(
+        Update,
+        update_shop.run_if(in_state(RoomState::Shop.to_meta_state())),
+    );
+    app.add_systems(OnExit(RoomState::Shop.to_meta_state()), exit_shop);
+}
@@ -10,6 +10,7 @@ pub use transition::*;
 pub enum MenuState {
     Studio,
     Title,
+    CharacterSelect,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect)]
@@ -25,12 +26,22 @@ pub enum TutorialState {
     Dead,
 }
 
+/// Dev only, see `room::editor`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect)]
+pub enum EditorState {
+    Editing,
+    Playtesting,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, States, Reflect)]
 pub enum MetaState {
     Menu(MenuState),
     Cutscene(CutsceneState),
     Tutorial(TutorialState),
     Room(RoomState),
+    /// Picking the next room, see `room::map`
+    Map,
+    Editor(EditorState),
 }
 
 /// The state that actually holds data about transitions
@@ -49,6 +60,7 @@ pub trait MetaUnfucker {
     fn get_cutscene_state(&self) -> Option<CutsceneState>;
     fn get_tutorial_state(&self) -> Option<TutorialState>;
     fn get_room_state(&self) -> Option<RoomState>;
+    fn get_editor_state(&self) -> Option<EditorState>;
 }
 impl MetaUnfucker for MetaState {
     fn get_menu_state(&self) -> Option<MenuState> {
@@ -78,6 +90,13 @@ impl MetaUnfucker for MetaState {
             _ => None,
         }
     }
+
+    fn get_editor_state(&self) -> Option<EditorState> {
+        match self {
+            MetaState::Editor(editor_state) => Some(editor_state.clone()),
+            _ => None,
+        }
+    }
 }
 impl MetaUnfucker for State<MetaState> {
     fn get_menu_state(&self) -> Option<MenuState> {
@@ -95,6 +114,10 @@ impl MetaUnfucker for State<MetaState> {
     fn get_room_state(&self) -> Option<RoomState> {
         MetaState::get_room_state(self.get())
     }
+
+    fn get_editor_state(&self) -> Option<EditorState> {
+        MetaState::get_editor_state(self.get())
+    }
 }
 
 /// Kills some verbosity for writing meta states
@@ -114,6 +137,7 @@ impl_to_meta_state!(MenuState, Menu);
 impl_to_meta_state!(CutsceneState, Cutscene);
 impl_to_meta_state!(TutorialState, Tutorial);
 impl_to_meta_state!(RoomState, Room);
+impl_to_meta_state!(EditorState, Editor);
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, States, Reflect)]
 pub enum PauseState {
@@ -135,6 +159,11 @@ impl ComputedStates for PhysicsState {
         match sources {
             (MetaState::Tutorial(_), PauseState::Unpaused, ConvoState::None) => Some(Self::Active),
             (MetaState::Room(_), PauseState::Unpaused, ConvoState::None) => Some(Self::Active),
+            (
+                MetaState::Editor(EditorState::Playtesting),
+                PauseState::Unpaused,
+                ConvoState::None,
+            ) => Some(Self::Active),
             (MetaState::Menu(MenuState::Title), PauseState::Unpaused, ConvoState::None) => {
                 Some(Self::Active)
             }
@@ -1,13 +1,14 @@
 use crate::prelude::*;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect, Serialize, Deserialize)]
 pub enum EncounterKind {
     SteelbeakOnly,
     PukebeakOnly,
     Both,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect, Default)]
+/// In the order a room goes through them
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Reflect, Default)]
 pub enum EncounterProgress {
     #[default]
     Entering,
@@ -20,11 +21,51 @@ pub struct EncounterState {
     pub kind: EncounterKind,
     pub difficulty: u32,
     pub progress: EncounterProgress,
+    /// Picked from an elite node on the map. Same room, nastier enemies, better rewards.
+    pub elite: bool,
+}
+
+impl EncounterState {
+    /// How much harder elite rooms roll their elites
+    const ELITE_BONUS: u32 = 4;
+
+    /// How many encounters into the run this is, starting at 1
+    pub fn index(&self) -> u32 {
+        match self.kind {
+            EncounterKind::SteelbeakOnly => self.difficulty,
+            EncounterKind::PukebeakOnly => 4,
+            EncounterKind::Both => 4 + self.difficulty,
+        }
+    }
+
+    /// What spawners roll `EliteAffix`es with
+    pub fn elite_difficulty(&self) -> u32 {
+        if self.elite {
+            self.difficulty + Self::ELITE_BONUS
+        } else {
+            self.difficulty
+        }
+    }
+}
+
+/// A boss shows up after every this many encounters
+pub const ENCOUNTERS_PER_BOSS: u32 = 4;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect)]
+pub struct BossState {
+    /// 1 for the first boss of the run, 2 for the second, etc.
+    pub level: u32,
+    pub progress: EncounterProgress,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect)]
 pub enum RoomState {
     Encounter(EncounterState),
+    Boss(BossState),
+    /// A breather with full health
+    Rest,
+    /// Spend feathers on upgrades
+    Shop,
     Dead,
 }
 impl RoomState {
@@ -33,42 +74,54 @@ impl RoomState {
             kind,
             difficulty,
             progress: EncounterProgress::Entering,
+            elite: false,
+        })
+    }
+
+    /// The `index`th encounter of a run (see `EncounterState::index`).
+    /// Steelbeaks for a few rooms, then pukebeaks, then both forever.
+    pub fn nth_encounter(index: u32, elite: bool) -> Self {
+        let (kind, difficulty) = match index {
+            0..=3 => (EncounterKind::SteelbeakOnly, index.max(1)),
+            4 => (EncounterKind::PukebeakOnly, 1),
+            _ => (EncounterKind::Both, index - 4),
+        };
+        Self::Encounter(EncounterState {
+            kind,
+            difficulty,
+            progress: EncounterProgress::Entering,
+            elite,
         })
     }
 
-    /// The next room to go to (assuming the bird doesn't die, or if it is dead, wants to play again)
-    pub fn next_room(&self) -> Self {
+    pub fn get_progress(&self) -> Option<EncounterProgress> {
         match self {
-            Self::Encounter(encounter_state) => {
-                match (encounter_state.kind, encounter_state.difficulty) {
-                    (EncounterKind::SteelbeakOnly, d) => {
-                        if d < 3 {
-                            RoomState::Encounter(EncounterState {
-                                kind: EncounterKind::SteelbeakOnly,
-                                difficulty: d + 1,
-                                progress: EncounterProgress::Entering,
-                            })
-                        } else {
-                            RoomState::Encounter(EncounterState {
-                                kind: EncounterKind::PukebeakOnly,
-                                difficulty: 1,
-                                progress: EncounterProgress::Entering,
-                            })
-                        }
-                    }
-                    (EncounterKind::PukebeakOnly, _) => RoomState::Encounter(EncounterState {
-                        kind: EncounterKind::Both,
-                        difficulty: 1,
-                        progress: EncounterProgress::Entering,
-                    }),
-                    (EncounterKind::Both, d) => RoomState::Encounter(EncounterState {
-                        kind: EncounterKind::Both,
-                        difficulty: d + 1,
-                        progress: EncounterProgress::Entering,
-                    }),
-                }
-            }
-            Self::Dead => Self::xth_encounter(EncounterKind::SteelbeakOnly, 1),
+            Self::Encounter(encounter_state) => Some(encounter_state.progress),
+            Self::Boss(boss_state) => Some(boss_state.progress),
+            Self::Rest | Self::Shop | Self::Dead => None,
+        }
+    }
+
+    /// Whether `other` is this room, just further along (like going from fighting to meandering)
+    pub fn is_further_along(&self, other: &Self) -> bool {
+        match (self.get_progress(), other.get_progress()) {
+            (Some(from), Some(to)) => to > from && self.with_progress(to) == *other,
+            _ => false,
+        }
+    }
+
+    /// The same room, further along
+    pub fn with_progress(&self, progress: EncounterProgress) -> Self {
+        match *self {
+            Self::Encounter(encounter_state) => Self::Encounter(EncounterState {
+                progress,
+                ..encounter_state
+            }),
+            Self::Boss(boss_state) => Self::Boss(BossState {
+                progress,
+                ..boss_state
+            }),
+            Self::Rest | Self::Shop | Self::Dead => *self,
         }
     }
 }
@@ -91,13 +144,9 @@ impl ComputedStates for EncounterProgress {
     type SourceStates = MetaState;
 
     fn compute(sources: MetaState) -> Option<Self> {
-        match sources.get_room_state() {
-            Some(room_state) => match room_state {
-                RoomState::Encounter(encounter_state) => Some(encounter_state.progress),
-                _ => None,
-            },
-            None => None,
-        }
+        sources
+            .get_room_state()
+            .and_then(|room_state| room_state.get_progress())
     }
 }
 
@@ -115,8 +164,20 @@ impl ComputedStates for EncounterState {
     }
 }
 
+impl ComputedStates for BossState {
+    type SourceStates = MetaState;
+
+    fn compute(sources: MetaState) -> Option<Self> {
+        match sources.get_room_state() {
+            Some(RoomState::Boss(boss_state)) => Some(boss_state),
+            _ => None,
+        }
+    }
+}
+
 pub(super) fn register_room_states(app: &mut App) {
     app.add_computed_state::<EncounterKind>();
     app.add_computed_state::<EncounterProgress>();
     app.add_computed_state::<EncounterState>();
+    app.add_computed_state::<BossState>();
 }
@@ -23,10 +23,14 @@ fn drop_egg(mut commands: Commands, tutorial_root: Res<TutorialRoot>) {
         .set_parent(tutorial_root.eid());
 }
 
-fn spawn_upgrades(mut commands: Commands, tutorial_root: Res<TutorialRoot>) {
+fn spawn_upgrades(
+    mut commands: Commands,
+    tutorial_root: Res<TutorialRoot>,
+    mut run_rng: ResMut<RunRng>,
+) {
     let mut kinds = vec![];
     while kinds.len() < 2 {
-        let kind = UpgradeKind::new(1.0, 0.0);
+        let kind = UpgradeKind::new(1.0, 0.0, run_rng.stream(RngStream::Upgrades));
         if kinds.is_empty() || std::mem::discriminant(&kinds[0]) != std::mem::discriminant(&kind) {
             kinds.push(kind);
         }
@@ -103,10 +107,8 @@ fn update(
                 data.has_hatched = true;
                 let multi_key = format!("core{}", upgrade_applied.ix);
                 multi.manager_mut(&multi_key).set_key("bird", &mut commands);
-                next_transition_state.set(
-                    TransitionKind::FadeToBlack
-                        .to_meta_transition_state(1.0, RoomState::Dead.next_room().to_meta_state()),
-                );
+                next_transition_state
+                    .set(TransitionKind::FadeToBlack.to_meta_transition_state(1.0, MetaState::Map));
             }
         }
     }
@@ -18,6 +18,7 @@ fn setup_impossible_boss(
     mut next_convo_state: ResMut<NextState<ConvoState>>,
     mut music_manager: ResMut<MusicManager>,
     mut help_text: ResMut<HelpText>,
+    character: Res<BirdCharacter>,
 ) {
     help_text.clear();
     music_manager.fade_to_song(MusicKind::BossBattle);
@@ -31,6 +32,7 @@ fn setup_impossible_boss(
         .set_parent(tutorial_root.eid());
     commands
         .spawn(BirdBundle::new(
+            *character,
             Vec2::new(-90.0, 50.0),
             default(),
             ephemeral_skills.get_num_launches(),
@@ -79,14 +81,14 @@ fn setup_impossible_boss(
 fn destroy_impossible_boss() {}
 
 fn update_impossible_boss(
-    simp_guides: Query<&SimpGuide>,
+    simp_guides: Query<(), With<DataEnemy<SimpGuide>>>,
     mut commands: Commands,
     tutorial_root: Res<TutorialRoot>,
-    bird: Query<&Bird>,
+    bird: Query<&Health, With<Bird>>,
     mut data: Query<&mut ImpossibleBossData>,
     mut next_convo_state: ResMut<NextState<ConvoState>>,
     mut ephemeral_skills: ResMut<EphemeralSkill>,
-    mut simp_spawner: Query<&mut EnemySpawner<SimpBundle>>,
+    mut simp_spawner: Query<&mut EnemySpawner>,
     keyboard: Res<ButtonInput<KeyCode>>,
 ) {
     if keyboard.just_pressed(KeyCode::Backspace) {
@@ -147,7 +149,8 @@ fn update_impossible_boss(
             }
             let spawner_placements = vec![Vec2::new(-62.0, 62.0), Vec2::new(0.0, -50.0)];
             commands
-                .spawn(EnemySpawnerBundle::<SimpBundle>::new(
+                .spawn(EnemySpawnerBundle::new(
+                    &[("simp", 1.0)],
                     spawner_placements,
                     batch_sizes,
                 ))
@@ -155,7 +158,7 @@ fn update_impossible_boss(
             return;
         }
 
-        if bird.get_taking_damage().is_some() && !data.has_shown_take_damage {
+        if bird.is_immune() && !data.has_shown_take_damage {
             next_convo_state.set(ConvoState::TutorialTakeDamage);
             data.has_shown_take_damage = true;
             return;
@@ -5,7 +5,7 @@ pub(self) mod fly_spots;
 mod impossible_boss;
 mod learn_to_fly;
 mod learn_to_shoot;
-pub(self) mod targets;
+pub mod targets;
 
 /// Sets up the tutorial.
 /// NOTE: For simplicity, you can't save game mid tutorial, i.e. we are assuming that
@@ -19,6 +19,7 @@ fn setup_tutorial(
     mut next_convo_state: ResMut<NextState<ConvoState>>,
     mut permanent_skills: ResMut<PermanentSkill>,
     mut ephemeral_skills: ResMut<EphemeralSkill>,
+    character: Res<BirdCharacter>,
 ) {
     permanent_skills.force_set_num_launches(0);
     permanent_skills.force_set_num_bullets(0);
@@ -63,6 +64,7 @@ fn setup_tutorial(
         .set_parent(tutorial_root.eid());
     commands
         .spawn(BirdBundle::new(
+            *character,
             Vec2::new(125.0, -78.0),
             Vec2::ZERO,
             ephemeral_skills.get_num_launches(),
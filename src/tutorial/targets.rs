@@ -10,6 +10,7 @@ pub struct PracticeTarget {
 pub struct PracticeTargetBundle {
     name: Name,
     practice_target: PracticeTarget,
+    health: Health,
     trigger: TutorialTriggerPhysicsBundle,
     multi: MultiAnimationManager,
 }
@@ -23,6 +24,7 @@ impl PracticeTargetBundle {
                 respawn_after,
                 time_dead: None,
             },
+            health: Health::new(1).with_vulnerable_to([TriggerKind::BulletGood]),
             trigger,
             multi: multi!([
                 (
@@ -68,16 +70,21 @@ fn update_practice_targets(
     mut practice_targets: Query<(
         Entity,
         &mut PracticeTarget,
-        &TriggerReceiver,
+        &mut Health,
         &mut MultiAnimationManager,
     )>,
-    collisions: Query<&TriggerCollisionRecord>,
+    mut damage_taken: EventReader<DamageTaken>,
     mut status_writer: EventWriter<PracticeTargetStatus>,
     mut commands: Commands,
     time: Res<Time>,
     bullet_time: Res<BulletTime>,
 ) {
-    for (eid, mut practice_target, triggers, mut multi) in &mut practice_targets {
+    let killed_by: HashMap<Entity, Option<Entity>> = damage_taken
+        .read()
+        .filter(|event| event.killed)
+        .map(|event| (event.target, event.source))
+        .collect();
+    for (eid, mut practice_target, mut health, mut multi) in &mut practice_targets {
         let respawn_after = practice_target.respawn_after.clone();
         let key = practice_target.key.clone();
         if let Some(time_dead) = practice_target.time_dead.as_mut() {
@@ -93,6 +100,7 @@ fn update_practice_targets(
                             key: key.clone(),
                         });
                         practice_target.time_dead = None;
+                        health.heal_full();
                         multi
                             .manager_mut("core")
                             .reset_key_with_points("target", &mut commands);
@@ -107,14 +115,9 @@ fn update_practice_targets(
             }
         } else {
             // The target is alive
-            let killed_by = triggers
-                .collisions
-                .iter()
-                .find(|eid| collisions.get(**eid).unwrap().other_kind == TriggerKind::BulletGood);
-            if let Some(killed_by) = killed_by {
-                let collision = collisions.get(*killed_by).unwrap();
+            if let Some(source) = killed_by.get(&eid) {
                 status_writer.send(PracticeTargetStatus {
-                    bullet_index: collision.other_eid.index(),
+                    bullet_index: source.map_or(0, |source| source.index()),
                     alive: false,
                     key: practice_target.key.clone(),
                 });
@@ -135,6 +138,10 @@ pub(super) fn register_practice_targets(app: &mut App) {
         Update,
         update_practice_targets
             .after(PhysicsSet)
-            .run_if(in_state(TutorialState::LearnToShoot.to_meta_state())),
+            .after(DamageSet)
+            .run_if(
+                in_state(TutorialState::LearnToShoot.to_meta_state())
+                    .or_else(in_state(EncounterProgress::Fighting)),
+            ),
     );
 }